@@ -0,0 +1,58 @@
+// run with `cargo bench`. hexi has no loops yet so each script is generated
+// up front, one statement per operation. per op times should stay flat as n
+// grows, if they climb with n something is copying whole values again
+
+use hexi::interpreter::Interpreter;
+use hexi::lexer::Lexer;
+use hexi::parser::Parser;
+use std::time::{Duration, Instant};
+
+const SIZES: &[usize] = &[1_000, 2_000, 4_000, 8_000, 16_000];
+
+fn run(code: &str) -> Duration {
+    let exprs = Parser::new(Lexer::new(code)).parse().expect("bench script should parse");
+    let mut interpreter = Interpreter::new();
+
+    let start = Instant::now();
+    for expr in &exprs {
+        interpreter.evaluate(expr).expect("bench script should run");
+    }
+    start.elapsed()
+}
+
+// grow a collection one push at a time, reading it back through an alias
+fn push_and_read(n: usize) -> String {
+    let mut code = String::from("val a = []\nval b = a\n");
+    for i in 0..n {
+        code.push_str(&format!("a.push({})\n", i));
+        code.push_str(&format!("b[{}]\n", i));
+    }
+    code
+}
+
+// pass a big collection and a long string around without touching them
+fn pass_around(n: usize) -> String {
+    let mut code = String::from("val a = []\n");
+    for i in 0..n {
+        code.push_str(&format!("a.push({})\n", i));
+    }
+    code.push_str(&format!("val s = \"{}\"\n", "x".repeat(n)));
+    // each copy gets its own block since re-declaring in one scope is an error
+    for _ in 0..n {
+        code.push_str("{ val c = a\nval t = s\nstring::len(t) }\n");
+    }
+    code
+}
+
+fn bench(name: &str, script: fn(usize) -> String) {
+    println!("{}", name);
+    for &n in SIZES {
+        let elapsed = run(&script(n));
+        println!("  n = {:>6}  total {:>10.2?}  per op {:>8.0}ns", n, elapsed, elapsed.as_nanos() as f64 / n as f64);
+    }
+}
+
+fn main() {
+    bench("push_and_read", push_and_read);
+    bench("pass_around", pass_around);
+}
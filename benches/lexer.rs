@@ -0,0 +1,56 @@
+// run with `cargo bench --bench lexer`. lexes (and parses) generated scripts
+// of a few megabytes, time per byte should stay flat as the script grows,
+// if it climbs with the size the lexer has gone back to rescanning the source
+
+use hexi::lexer::Lexer;
+use hexi::parser::Parser;
+use std::time::{Duration, Instant};
+
+const SIZES: &[usize] = &[1 << 20, 2 << 20, 4 << 20, 8 << 20];
+
+// a bit of everything the lexer knows about, repeated until it's big enough
+fn script(bytes: usize) -> String {
+    let chunk = "val person = [name = \"bob\", age = 22, pets = [\"cat\", 'dog']]\n\
+                 if person.age >= 18 { io::println(string::fmt(\"{} can vote\", person.name)) } else { io::println(\"nope\") }\n\
+                 val area = 0.5 * 3.25 * (4 + 2) - 1 % 7 / 2\n\
+                 val lookup = person[\"name\"] != 'üñíçødé'\n";
+
+    let mut code = String::with_capacity(bytes + chunk.len());
+    while code.len() < bytes {
+        code.push_str(chunk);
+    }
+    code
+}
+
+fn lex(code: &str) -> (Duration, usize) {
+    let mut lexer = Lexer::new(code);
+    let mut tokens = 0;
+
+    let start = Instant::now();
+    while lexer.next().is_some() {
+        tokens += 1;
+    }
+    (start.elapsed(), tokens)
+}
+
+fn parse(code: &str) -> Duration {
+    let start = Instant::now();
+    Parser::new(Lexer::new(code)).parse().expect("bench script should parse");
+    start.elapsed()
+}
+
+fn main() {
+    println!("lex");
+    for &n in SIZES {
+        let code = script(n);
+        let (elapsed, tokens) = lex(&code);
+        println!("  {:>5} KiB  {:>9} tokens  total {:>10.2?}  per byte {:>6.1}ns", code.len() / 1024, tokens, elapsed, elapsed.as_nanos() as f64 / code.len() as f64);
+    }
+
+    println!("parse");
+    for &n in SIZES {
+        let code = script(n);
+        let elapsed = parse(&code);
+        println!("  {:>5} KiB  total {:>10.2?}  per byte {:>6.1}ns", code.len() / 1024, elapsed, elapsed.as_nanos() as f64 / code.len() as f64);
+    }
+}
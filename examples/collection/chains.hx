@@ -0,0 +1,8 @@
+val people = [
+    [name = "bob", langs = ["hexi", "rust"]],
+    [name = "amy", langs = ["c"]],
+]
+
+io::println(people.get(0).langs[1].upper())
+io::println(people[1].name.len())
+io::println([people[0].name, people.size(), math::abs(0 - 3) + 1])
@@ -3,5 +3,5 @@ include json
 val data = '{"name": "bob", "age": 22}'
 val parsed = json::parse(data)
 
-io::print(parsed.name)
-io::print(parsed.age)
\ No newline at end of file
+io::println(parsed.name)
+io::println(parsed.age)
\ No newline at end of file
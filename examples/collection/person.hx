@@ -1,4 +1,4 @@
 val person = [ name = "bob" ]
 person.insert("age", 22)
 
-io::print(person)
\ No newline at end of file
+io::println(person)
\ No newline at end of file
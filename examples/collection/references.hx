@@ -0,0 +1,8 @@
+val a = [1, 2]
+val b = a
+b.push(3)
+io::println(a)
+
+val person = [ name = "bob", pets = ["cat"] ]
+person.pets.push("dog")
+io::println(person.pets)
@@ -0,0 +1,8 @@
+include bigint
+
+val max = 9223372036854775807
+io::println(max + 1)
+io::println(math::pow(2, 128))
+io::println(bigint::from("ffffffffffffffffffffffffffffffff", 16))
+io::println(bigint::modpow(4, 13, 497))
+io::println(bigint::to_string(math::pow(2, 70), 16))
@@ -0,0 +1,7 @@
+val big = 9007199254740993
+io::println(big + 2)
+io::println(7 / 2)
+io::println(8 / 2)
+io::println(17 % 5)
+io::println(1 == 1.0)
+io::println(math::pow(3, 30))
@@ -0,0 +1,5 @@
+include test
+
+test::case("ints stay ints", "test::assert_eq(math::max(1, 5, 3), 5)")
+test::case("rounding", "test::assert_eq(math::round(3.14159, 2), 3.14)")
+test::case("pi", "test::assert_eq(math::round(math::PI, 2), 3.14)")
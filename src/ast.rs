@@ -1,11 +1,32 @@
-use std::hash::Hash;
+use crate::bigint::BigInt;
+use std::rc::Rc;
+use crate::error::Span;
 use crate::lexer::TokenType;
+use crate::symbol::Symbol;
+use serde::Serialize;
 
-#[derive(Debug, Clone)]
-pub enum Expr {
-    Identifier(String),
+// every node knows where it came from so errors can point at it
+#[derive(Debug, Clone, Serialize)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Expr { kind, span }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum ExprKind {
+    Identifier(Symbol),
+    Int(i64),
+    BigInt(BigInt),     // int literals too big for an i64
     Number(f64),
-    String(String),
+    String(Rc<str>),
+    Bool(bool),
+    Nil,
     Call(Call),
     VarDecl(VarDecl),
     Assignment(Assignment),
@@ -22,83 +43,95 @@ pub enum Expr {
     FieldAccess(FieldAccess),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Block {
     pub exprs: Vec<Expr>,
 }
 
-#[derive(Debug, Clone)]
+// `include json`, `include json as j` or a group, `include [json, time as t]`
+#[derive(Debug, Clone, Serialize)]
 pub struct Include {
+    pub modules: Vec<IncludeItem>,
+    pub grouped: bool,  // written with brackets, even if there's only one
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IncludeItem {
     pub module: String,
+    pub alias: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct If {
     pub cond: Box<Expr>,
     pub block: Block,
     pub else_block: Option<Block>
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Call {
-    pub module: Option<String>,     // acesses from a module? io?
-    pub name: String,
+    pub module: Option<Symbol>,     // acesses from a module? io?
+    pub name: Symbol,
     pub args: Vec<Expr>,
+    #[serde(skip)]
+    sig: Symbol,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct VarDecl {
-    pub name: String,
+    pub name: Symbol,
     pub value: Box<Expr>,   // so we dont recursively set spaces
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Assignment {
-    pub name: String,
+    pub name: Symbol,
     pub assignee: Box<Expr>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct UnaryOp {
     pub operand: Box<Expr>,
     pub op: TokenType, // negate, or other shit idk
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct BinaryOp {
     pub left: Box<Expr>,
     pub right: Box<Expr>,
     pub op: TokenType,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Array {
     pub values: Vec<Expr>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct IndexAccess {
     pub object: Box<Expr>,
     pub index: Box<Expr>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MethodCall {
     pub object: Box<Expr>,
-    pub method: String,
+    pub method: Symbol,
     pub args: Vec<Expr>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Collection {
     pub entries: Vec<CEntry>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum CEntry {
     Indexed(Expr),                      // [1, 2, 3]
-    Keyed(String, Expr),                // [name = "value"] -> like a map, so key -> value
+    Keyed(Rc<str>, Expr),               // [name = "value"] -> like a map, so key -> value
     NumKeyed(f64, Expr),                // [1 = "first", 2 = "second"] - num -> value
+    BoolKeyed(bool, Expr),              // [true = "yes", false = "no"]
+    NilKeyed(Expr),                     // [nil = "nothing"]
 }
 
 impl Collection {
@@ -111,14 +144,14 @@ impl Collection {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FieldAccess {
     pub object: Box<Expr>,
-    pub field: String,
+    pub field: Rc<str>,     // a key rather than a name, so it's ready to look up
 }
 
 impl FieldAccess {
-    pub fn new(object: Expr, field: String) -> Self {
+    pub fn new(object: Expr, field: Rc<str>) -> Self {
         FieldAccess {
             object: Box::new(object),
             field,
@@ -134,7 +167,7 @@ impl Block {
 
 impl Include {
     pub fn new(module: String) -> Include {
-        Include { module }
+        Include { modules: vec![IncludeItem { module, alias: None }], grouped: false }
     }
 }
 
@@ -155,31 +188,30 @@ impl UnaryOp {
 }
 
 impl Call {
-    pub fn new(name: String, args: Vec<Expr>) -> Self {
-        Call { module: None, name, args }
+    pub fn new(name: Symbol, args: Vec<Expr>) -> Self {
+        Call { module: None, name, args, sig: name }
     }
 
-    pub fn new_from_module(module: String, name: String, args: Vec<Expr>) -> Self {
-        Call { module: Some(module), name, args }
+    pub fn new_from_module(module: Symbol, name: Symbol, args: Vec<Expr>) -> Self {
+        let sig = Symbol::intern(&format!("{}_{}", module, name));
+        Call { module: Some(module), name, args, sig }
     }
 
-    // Return the signature name for the function if it's in a module
-    pub fn signature(&self) -> String {
-        match &self.module {
-            Some(m) => format!("{}_{}", m, self.name),
-            None => self.name.clone(),
-        }
+    // Return the signature name for the function if it's in a module,
+    // worked out once up front so calls don't have to build it every time
+    pub fn signature(&self) -> Symbol {
+        self.sig
     }
 }
 
 impl VarDecl {
-    pub fn new(name: String, value: Expr) -> Self {
+    pub fn new(name: Symbol, value: Expr) -> Self {
         VarDecl { name, value: Box::new(value) }
     }
 }
 
 impl Assignment {
-    pub fn new(name: String, assignee: Expr) -> Self {
+    pub fn new(name: Symbol, assignee: Expr) -> Self {
         Assignment { name, assignee: Box::new(assignee) }
     }
 }
@@ -200,7 +232,7 @@ impl IndexAccess {
 }
 
 impl MethodCall {
-    pub fn new(object: Expr, method: String, args: Vec<Expr>) -> Self {
+    pub fn new(object: Expr, method: Symbol, args: Vec<Expr>) -> Self {
         MethodCall {
             object: Box::new(object),
             method,
@@ -0,0 +1,312 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+// limbs are base 10^9 so printing in decimal is cheap
+const BASE: u64 = 1_000_000_000;
+
+// arbitrary precision integer, sign + magnitude with little endian limbs.
+// zero is always stored as no limbs and not negative
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    limbs: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn from_i64(n: i64) -> Self {
+        let negative = n < 0;
+        let mut mag = n.unsigned_abs();
+        let mut limbs = vec![];
+        while mag > 0 {
+            limbs.push((mag % BASE) as u32);
+            mag /= BASE;
+        }
+        BigInt { negative, limbs }
+    }
+
+    fn from_parts(negative: bool, mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        let negative = negative && !limbs.is_empty();
+        BigInt { negative, limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    // back down to an i64 if it fits
+    pub fn to_i64(&self) -> Option<i64> {
+        let mut mag: i128 = 0;
+        for limb in self.limbs.iter().rev() {
+            mag = mag * BASE as i128 + *limb as i128;
+            if mag > i64::MAX as i128 + 1 {
+                return None;
+            }
+        }
+        let n = if self.negative { -mag } else { mag };
+        i64::try_from(n).ok()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let mag = self.limbs.iter().rev().fold(0.0, |acc, l| acc * BASE as f64 + *l as f64);
+        if self.negative { -mag } else { mag }
+    }
+
+    // parse digits in the given radix (2..=36), with an optional leading sign
+    pub fn parse(s: &str, radix: u32) -> Option<Self> {
+        if !(2..=36).contains(&radix) {
+            return None;
+        }
+
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        if digits.is_empty() {
+            return None;
+        }
+
+        let mut mag: Vec<u32> = vec![];
+        for c in digits.chars() {
+            let d = c.to_digit(radix)?;
+            mul_small_add(&mut mag, radix, d);
+        }
+
+        Some(BigInt::from_parts(negative, mag))
+    }
+
+    pub fn to_string_radix(&self, radix: u32) -> String {
+        if radix == 10 {
+            return self.to_string();
+        }
+        if self.is_zero() {
+            return "0".to_string();
+        }
+
+        let mut mag = self.limbs.clone();
+        let mut digits = vec![];
+        while !mag.is_empty() {
+            let rem = div_small(&mut mag, radix);
+            digits.push(std::char::from_digit(rem, radix).unwrap());
+        }
+        if self.negative {
+            digits.push('-');
+        }
+        digits.iter().rev().collect()
+    }
+
+    pub fn neg(&self) -> Self {
+        BigInt::from_parts(!self.negative, self.limbs.clone())
+    }
+
+    pub fn abs(&self) -> Self {
+        BigInt::from_parts(false, self.limbs.clone())
+    }
+
+    pub fn add(&self, other: &BigInt) -> Self {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_mag(&self.limbs, &other.limbs));
+        }
+
+        // different signs, so subtract the smaller magnitude from the bigger one
+        match cmp_mag(&self.limbs, &other.limbs) {
+            Ordering::Less => BigInt::from_parts(other.negative, sub_mag(&other.limbs, &self.limbs)),
+            _ => BigInt::from_parts(self.negative, sub_mag(&self.limbs, &other.limbs)),
+        }
+    }
+
+    pub fn sub(&self, other: &BigInt) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &BigInt) -> Self {
+        let mut out = vec![0u64; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let cur = out[i + j] + *a as u64 * *b as u64 + carry;
+                out[i + j] = cur % BASE;
+                carry = cur / BASE;
+            }
+            let mut k = i + other.limbs.len();
+            while carry > 0 {
+                let cur = out[k] + carry;
+                out[k] = cur % BASE;
+                carry = cur / BASE;
+                k += 1;
+            }
+        }
+
+        let limbs = out.into_iter().map(|l| l as u32).collect();
+        BigInt::from_parts(self.negative != other.negative, limbs)
+    }
+
+    // truncating division like i64, the remainder takes the sign of the dividend.
+    // None when dividing by zero
+    pub fn div_rem(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let mut quotient = vec![0u32; self.limbs.len()];
+        let mut rem: Vec<u32> = vec![];
+
+        for i in (0..self.limbs.len()).rev() {
+            // rem = rem * BASE + limb
+            rem.insert(0, self.limbs[i]);
+            trim(&mut rem);
+
+            // binary search the biggest digit q with other * q <= rem
+            let (mut lo, mut hi) = (0u32, (BASE - 1) as u32);
+            while lo < hi {
+                let mid = lo + (hi - lo).div_ceil(2);
+                if cmp_mag(&mul_small(&other.limbs, mid), &rem) != Ordering::Greater {
+                    lo = mid;
+                } else {
+                    hi = mid - 1;
+                }
+            }
+
+            if lo > 0 {
+                rem = sub_mag(&rem, &mul_small(&other.limbs, lo));
+            }
+            quotient[i] = lo;
+        }
+
+        Some((
+            BigInt::from_parts(self.negative != other.negative, quotient),
+            BigInt::from_parts(self.negative, rem),
+        ))
+    }
+
+    pub fn pow(&self, mut exp: u64) -> Self {
+        let mut base = self.clone();
+        let mut result = BigInt::from_i64(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base);
+            }
+            base = base.mul(&base);
+            exp >>= 1;
+        }
+        result
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.limbs, &other.limbs),
+            (true, true) => cmp_mag(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        // most significant limb unpadded, the rest padded out to 9 digits
+        let mut limbs = self.limbs.iter().rev();
+        write!(f, "{}", limbs.next().unwrap())?;
+        for limb in limbs {
+            write!(f, "{:09}", limb)?;
+        }
+        Ok(())
+    }
+}
+
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let cur = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        out.push((cur % BASE) as u32);
+        carry = cur / BASE;
+    }
+    if carry > 0 {
+        out.push(carry as u32);
+    }
+    out
+}
+
+// a - b where |a| >= |b|
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, limb) in a.iter().enumerate() {
+        let mut cur = *limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if cur < 0 {
+            cur += BASE as i64;
+            borrow = 1;
+        }
+        out.push(cur as u32);
+    }
+    trim(&mut out);
+    out
+}
+
+fn mul_small(a: &[u32], m: u32) -> Vec<u32> {
+    let mut out = a.to_vec();
+    mul_small_add(&mut out, m, 0);
+    out
+}
+
+// a = a * m + add
+fn mul_small_add(a: &mut Vec<u32>, m: u32, add: u32) {
+    let mut carry = add as u64;
+    for limb in a.iter_mut() {
+        let cur = *limb as u64 * m as u64 + carry;
+        *limb = (cur % BASE) as u32;
+        carry = cur / BASE;
+    }
+    while carry > 0 {
+        a.push((carry % BASE) as u32);
+        carry /= BASE;
+    }
+    trim(a);
+}
+
+// a = a / d, returns the remainder
+fn div_small(a: &mut Vec<u32>, d: u32) -> u32 {
+    let mut rem = 0u64;
+    for limb in a.iter_mut().rev() {
+        let cur = rem * BASE + *limb as u64;
+        *limb = (cur / d as u64) as u32;
+        rem = cur % d as u64;
+    }
+    trim(a);
+    rem as u32
+}
@@ -0,0 +1,105 @@
+use crate::error::HexiError;
+use crate::interpreter::Interpreter;
+use crate::limits::Limits;
+use std::io::{BufRead, Write};
+
+// for embedders that want a say in what an interpreter starts out with,
+// Interpreter::new() is the same as Interpreter::builder().build() minus the Result
+//
+// let hexi = Interpreter::builder()
+//     .preload(&["io", "json"])   // usable straight away, no include needed
+//     .allow(&["math"])           // the only other module scripts can include
+//     .limits(Limits { max_steps: Some(1_000_000), ..Default::default() })
+//     .output(capture.clone())
+//     .build()?;
+pub struct InterpreterBuilder {
+    preload: Option<Vec<String>>,   // None is the standard set, io, math, collection, string, bytes and number
+    allow: Option<Vec<String>>,     // None lets scripts include anything
+    limits: Limits,
+    float_precision: Option<usize>,
+    output: Option<Box<dyn Write>>,
+    error_output: Option<Box<dyn Write>>,
+    input: Option<Box<dyn BufRead>>,
+}
+
+impl InterpreterBuilder {
+    pub fn new() -> Self {
+        InterpreterBuilder { preload: None, allow: None, limits: Limits::default(), float_precision: None, output: None, error_output: None, input: None }
+    }
+
+    // modules loaded before any script runs, in place of the standard ones.
+    // any module `include` could find works here, optional ones and plugins too
+    pub fn preload(mut self, modules: &[&str]) -> Self {
+        self.preload = Some(modules.iter().map(|m| m.to_string()).collect());
+        self
+    }
+
+    // the modules scripts may `include` on top of the preloaded ones, anything
+    // else fails like a module that doesn't exist. an empty list allows none
+    pub fn allow(mut self, modules: &[&str]) -> Self {
+        self.allow = Some(modules.iter().map(|m| m.to_string()).collect());
+        self
+    }
+
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    // see Interpreter::set_float_precision
+    pub fn float_precision(mut self, digits: usize) -> Self {
+        self.float_precision = Some(digits);
+        self
+    }
+
+    pub fn output(mut self, output: impl Write + 'static) -> Self {
+        self.output = Some(Box::new(output));
+        self
+    }
+
+    pub fn error_output(mut self, output: impl Write + 'static) -> Self {
+        self.error_output = Some(Box::new(output));
+        self
+    }
+
+    pub fn input(mut self, input: impl BufRead + 'static) -> Self {
+        self.input = Some(Box::new(input));
+        self
+    }
+
+    // fails if a preloaded module can't be found or loaded
+    pub fn build(self) -> Result<Interpreter, HexiError> {
+        let mut i = Interpreter::bare();
+
+        if let Some(output) = self.output {
+            i.set_output(output);
+        }
+        if let Some(output) = self.error_output {
+            i.set_error_output(output);
+        }
+        if let Some(input) = self.input {
+            i.set_input(input);
+        }
+        i.set_limits(self.limits);
+        i.set_float_precision(self.float_precision);
+
+        match self.preload {
+            Some(modules) => {
+                for m in modules {
+                    i.load_module(&m)?;
+                }
+            },
+            None => i.load_std(),
+        }
+
+        // after preloading so the allow list only applies to scripts
+        i.set_allowed_modules(self.allow);
+        Ok(i)
+    }
+}
+
+impl Default for InterpreterBuilder {
+    fn default() -> Self {
+        InterpreterBuilder::new()
+    }
+}
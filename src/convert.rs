@@ -0,0 +1,180 @@
+use crate::interpreter::{CKey, CValue, Value};
+use crate::stdlib::bytes;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// conversions between rust and hexi values, mostly for hosts embedding hexi and
+// for natives. going from a Value can fail, those errors are plain strings so
+// natives can just `?` them
+
+impl From<i64> for Value {
+    fn from(i: i64) -> Self {
+        Value::Int(i)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(i: i32) -> Self {
+        Value::Int(i as i64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s.into())
+    }
+}
+
+impl From<Rc<str>> for Value {
+    fn from(s: Rc<str>) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(b: Vec<u8>) -> Self {
+        Value::Bytes(b.into())
+    }
+}
+
+impl From<&[u8]> for Value {
+    fn from(b: &[u8]) -> Self {
+        Value::Bytes(b.into())
+    }
+}
+
+impl From<()> for Value {
+    fn from(_: ()) -> Self {
+        Value::Nil
+    }
+}
+
+// None is nil
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(o: Option<T>) -> Self {
+        o.map_or(Value::Nil, Into::into)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(values: Vec<Value>) -> Self {
+        Value::collection(CValue::from_array(values))
+    }
+}
+
+impl From<HashMap<String, Value>> for Value {
+    fn from(obj: HashMap<String, Value>) -> Self {
+        Value::collection(CValue::from_object(obj))
+    }
+}
+
+impl TryFrom<Value> for i64 {
+    type Error = String;
+
+    // whole floats count too, 2.0 is as good as 2
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match v {
+            Value::Int(i) => Ok(i),
+            Value::Number(n) if n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64 => Ok(n as i64),
+            _ => Err(format!("expected an int, got {} {}", v.type_name(), v)),
+        }
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = String;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        v.as_f64().ok_or_else(|| format!("expected a number, got {} {}", v.type_name(), v))
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = String;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match v {
+            Value::Bool(b) => Ok(b),
+            _ => Err(format!("expected a bool, got {} {}", v.type_name(), v)),
+        }
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = String;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match v {
+            Value::String(s) => Ok(s.to_string()),
+            _ => Err(format!("expected a string, got {} {}", v.type_name(), v)),
+        }
+    }
+}
+
+impl TryFrom<Value> for Vec<u8> {
+    type Error = String;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match v {
+            Value::Bytes(b) => Ok(b.to_vec()),
+            _ => Err(format!("expected bytes, got {} {}", v.type_name(), v)),
+        }
+    }
+}
+
+// only array-like collections, holes come back as nil
+impl TryFrom<Value> for Vec<Value> {
+    type Error = String;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match &v {
+            Value::Collection(c) if c.borrow().is_array_like() => {
+                let c = c.borrow();
+                Ok((0..c.size()).map(|i| c.get_by_index(i).cloned().unwrap_or(Value::Nil)).collect())
+            },
+            _ => Err(format!("expected an array-like collection, got {} {}", v.type_name(), v)),
+        }
+    }
+}
+
+// every entry of a collection, index, number, bool and nil keys get turned
+// into strings. collections and userdata used as keys have no string form
+impl TryFrom<Value> for HashMap<String, Value> {
+    type Error = String;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match &v {
+            Value::Collection(c) => c.borrow().iter().map(|(k, v)| {
+                let key = match k {
+                    CKey::Index(i) => i.to_string(),
+                    CKey::String(s) => s.to_string(),
+                    CKey::Number(n) => n,
+                    CKey::Bool(b) => b.to_string(),
+                    CKey::Nil => "nil".to_string(),
+                    CKey::Bytes(b) => bytes::to_hex(&b),
+                    CKey::Ref(r) => return Err(format!("a {} key can't be turned into a string", r.value().type_name())),
+                };
+                Ok((key, v.clone()))
+            }).collect(),
+            _ => Err(format!("expected a collection, got {} {}", v.type_name(), v)),
+        }
+    }
+}
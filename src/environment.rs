@@ -0,0 +1,74 @@
+use crate::interpreter::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::symbol::Symbol;
+
+// a single scope of variables, blocks (and later functions/loops) get their own
+// scope which points back to the one they were created in
+#[derive(Debug, Default)]
+pub struct Environment {
+    vars: HashMap<Symbol, Value>,
+    parent: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Environment {
+            vars: HashMap::new(),
+            parent: None,
+        }
+    }
+
+    // new child scope of `parent`, lookups that miss here fall through to it
+    pub fn with_parent(parent: Rc<RefCell<Environment>>) -> Self {
+        Environment {
+            vars: HashMap::new(),
+            parent: Some(parent),
+        }
+    }
+
+    pub fn parent(&self) -> Option<Rc<RefCell<Environment>>> {
+        self.parent.clone()
+    }
+
+    // walk up the scope chain until we find the variable
+    pub fn get(&self, name: Symbol) -> Option<Value> {
+        match self.vars.get(&name) {
+            Some(v) => Some(v.clone()),
+            None => self.parent.as_ref().and_then(|p| p.borrow().get(name)),
+        }
+    }
+
+    pub fn contains(&self, name: Symbol) -> bool {
+        self.vars.contains_key(&name) || self.parent.as_ref().is_some_and(|p| p.borrow().contains(name))
+    }
+
+    // only checks this scope, so shadowing a variable from an outer scope is fine
+    pub fn define(&mut self, name: Symbol, value: Value) -> Result<(), String> {
+        if self.vars.contains_key(&name) {
+            return Err(format!("variable '{}' already defined!", name));
+        }
+
+        self.vars.insert(name, value);
+        Ok(())
+    }
+
+    // assign to the closest scope that defines the variable
+    pub fn assign(&mut self, name: Symbol, value: Value) -> Result<(), String> {
+        if let Some(v) = self.vars.get_mut(&name) {
+            *v = value;
+            return Ok(());
+        }
+
+        match &self.parent {
+            Some(p) => p.borrow_mut().assign(name, value),
+            None => Err(format!("variable '{}' not defined!", name)),
+        }
+    }
+
+    // variables defined in this scope only
+    pub fn locals(&self) -> impl Iterator<Item = (&Symbol, &Value)> {
+        self.vars.iter()
+    }
+}
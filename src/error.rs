@@ -0,0 +1,123 @@
+use serde::Serialize;
+use std::fmt;
+
+// where in the source something happened, lines and cols start at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct Span {
+    pub line: usize,
+    pub col: usize,
+    pub len: usize,
+}
+
+impl Span {
+    pub fn new(line: usize, col: usize, len: usize) -> Self {
+        Span { line, col, len }
+    }
+
+    // stretch this span up to the end of `other`, only works within a single
+    // line since that's all the caret can show anyway
+    pub fn to(self, other: Span) -> Span {
+        if other.line == self.line && other.col >= self.col {
+            Span::new(self.line, self.col, other.col + other.len - self.col)
+        } else {
+            self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Parse,
+    Runtime,
+    Limit,  // the script ran into one of the interpreter's limits
+    Interrupted,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::Parse => write!(f, "parser error"),
+            ErrorKind::Runtime => write!(f, "runtime error"),
+            ErrorKind::Limit => write!(f, "limit exceeded"),
+            ErrorKind::Interrupted => write!(f, "interrupted"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HexiError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub span: Option<Span>,  // errors from natives don't know where they came from until the call site fills it in
+}
+
+impl HexiError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>, span: Option<Span>) -> Self {
+        HexiError { kind, message: message.into(), span }
+    }
+
+    pub fn parse(message: impl Into<String>, span: Span) -> Self {
+        HexiError::new(ErrorKind::Parse, message, Some(span))
+    }
+
+    pub fn runtime(message: impl Into<String>) -> Self {
+        HexiError::new(ErrorKind::Runtime, message, None)
+    }
+
+    pub fn limit(message: impl Into<String>) -> Self {
+        HexiError::new(ErrorKind::Limit, message, None)
+    }
+
+    // attach a span, but keep the original one if the error already has one,
+    // the innermost location is the most useful
+    pub fn with_span(mut self, span: Span) -> Self {
+        if self.span.is_none() {
+            self.span = Some(span);
+        }
+        self
+    }
+
+    // render the error the way the cli shows it:
+    //
+    // file.hx:12:5: runtime error: undefined variable or reference 'x'
+    //    12 | io::println(x)
+    //       |             ^
+    pub fn report(&self, source: &str, filename: &str) -> String {
+        let span = match self.span {
+            Some(s) if s.line > 0 => s,
+            _ => return format!("{}: {}", filename, self),
+        };
+
+        let mut out = format!("{}:{}:{}: {}", filename, span.line, span.col, self);
+
+        if let Some(line) = source.lines().nth(span.line - 1) {
+            let gutter = span.line.to_string();
+            let pad = " ".repeat(gutter.len());
+            // keep tabs so the caret lines up with the source
+            let indent: String = line.chars()
+                .take(span.col.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+
+            out.push_str(&format!("\n {} | {}", gutter, line));
+            out.push_str(&format!("\n {} | {}{}", pad, indent, "^".repeat(span.len.max(1))));
+        }
+
+        out
+    }
+}
+
+impl fmt::Display for HexiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.message)
+    }
+}
+
+impl std::error::Error for HexiError {}
+
+// natives and value helpers still report plain strings, those are runtime errors
+impl From<String> for HexiError {
+    fn from(message: String) -> Self {
+        HexiError::runtime(message)
+    }
+}
@@ -0,0 +1,282 @@
+use crate::ast::{Block, CEntry, Collection, Expr, ExprKind, If};
+use crate::lexer::TokenType;
+
+// turns a parsed tree back into hexi source with one canonical layout:
+// four space indents, one expression per line, spaces around binary ops and
+// `=`, and collections that don't fit on a line get one entry per line with
+// a trailing comma. formatting the output again gives back the same text
+
+const INDENT: &str = "    ";
+const MAX_WIDTH: usize = 80;
+
+// how tightly something binds when it's the operand of something else,
+// anything weaker than the spot it's in gets wrapped in parens
+const LOOSE: u8 = 0;    // val, assignments, ifs, blocks, includes
+const UNARY: u8 = 4;
+const POSTFIX: u8 = 5;  // literals, names, calls, indexing, field access
+
+// a whole program, one top level expression per line
+pub fn format_exprs(exprs: &[Expr]) -> String {
+    let mut out = String::new();
+    write_lines(&mut out, exprs, 0);
+    out
+}
+
+// a single expression, as it would appear at the top level
+pub fn format_expr(expr: &Expr) -> String {
+    expr_str(expr, 0, 0)
+}
+
+fn write_lines(out: &mut String, exprs: &[Expr], indent: usize) {
+    let lines: Vec<String> = exprs.iter().map(|e| expr_str(e, indent, INDENT.len() * indent)).collect();
+
+    for (i, line) in lines.iter().enumerate() {
+        out.push_str(&INDENT.repeat(indent));
+        out.push_str(line);
+
+        // newlines mean nothing to the parser, so `a` followed by `-b` or `(b)`
+        // would glue together into `a - b` or a call. the semi keeps them apart
+        if lines.get(i + 1).is_some_and(|next| next.starts_with(['-', '(', '['])) {
+            out.push(';');
+        }
+        out.push('\n');
+    }
+}
+
+// the column `s` leaves off at when it's written starting from `col`
+fn after(col: usize, s: &str) -> usize {
+    match s.rfind('\n') {
+        Some(i) => s.len() - i - 1,
+        None => col + s.len(),
+    }
+}
+
+// `col` is where on its line the expression starts, so collections can tell
+// whether they still fit after whatever comes before them
+fn expr_str(expr: &Expr, indent: usize, col: usize) -> String {
+    match &expr.kind {
+        ExprKind::Identifier(name) => name.to_string(),
+        ExprKind::Int(i) => i.to_string(),
+        ExprKind::BigInt(b) => b.to_string(),
+        ExprKind::Number(n) => number_str(*n),
+        ExprKind::String(s) => quote_string(s),
+        ExprKind::Bool(b) => b.to_string(),
+        ExprKind::Nil => "nil".to_string(),
+        ExprKind::Include(inc) => {
+            let modules: Vec<_> = inc.modules.iter().map(|m| match &m.alias {
+                Some(alias) => format!("{} as {}", m.module, alias),
+                None => m.module.clone(),
+            }).collect();
+            if inc.grouped {
+                format!("include [{}]", modules.join(", "))
+            } else {
+                format!("include {}", modules.join(", "))
+            }
+        },
+
+        ExprKind::Call(c) => {
+            let name = match c.module {
+                Some(module) => format!("{}::{}", module, c.name),
+                None => c.name.to_string(),
+            };
+            format!("{}({})", name, args_str(&c.args, indent, col + name.len() + 1))
+        },
+        ExprKind::VarDecl(d) => {
+            let start = format!("val {} = ", d.name);
+            format!("{}{}", start, expr_str(&d.value, indent, col + start.len()))
+        },
+        ExprKind::Assignment(a) => {
+            let start = format!("{} = ", a.name);
+            format!("{}{}", start, expr_str(&a.assignee, indent, col + start.len()))
+        },
+
+        ExprKind::BinaryOp(b) => {
+            let prec = precedence(&b.op);
+            // everything is left associative, so the right side needs parens
+            // even at the same precedence
+            let left = operand_str(&b.left, prec, indent, col);
+            let op = op_str(&b.op);
+            let right = operand_str(&b.right, prec + 1, indent, after(col, &left) + op.len() + 2);
+            format!("{} {} {}", left, op, right)
+        },
+        ExprKind::UnaryOp(u) => {
+            let op = op_str(&u.op);
+            format!("{}{}", op, operand_str(&u.operand, UNARY, indent, col + op.len()))
+        },
+
+        ExprKind::Block(b) => block_str(b, indent),
+        ExprKind::If(i) => if_str(i, indent, col),
+        ExprKind::Collection(c) => collection_str(c, indent, col),
+
+        ExprKind::IndexAccess(ia) => {
+            let object = operand_str(&ia.object, POSTFIX, indent, col);
+            let index = expr_str(&ia.index, indent, after(col, &object) + 1);
+            format!("{}[{}]", object, index)
+        },
+        ExprKind::MethodCall(mc) => {
+            let object = operand_str(&mc.object, POSTFIX, indent, col);
+            let args = args_str(&mc.args, indent, after(col, &object) + mc.method.as_str().len() + 2);
+            format!("{}.{}({})", object, mc.method, args)
+        },
+        ExprKind::FieldAccess(fa) => format!("{}.{}", operand_str(&fa.object, POSTFIX, indent, col), fa.field),
+    }
+}
+
+// `expr` sitting somewhere that needs at least `min` binding strength
+fn operand_str(expr: &Expr, min: u8, indent: usize, col: usize) -> String {
+    if strength(expr) < min {
+        format!("({})", expr_str(expr, indent, col + 1))
+    } else {
+        expr_str(expr, indent, col)
+    }
+}
+
+fn strength(expr: &Expr) -> u8 {
+    match &expr.kind {
+        ExprKind::BinaryOp(b) => precedence(&b.op),
+        ExprKind::UnaryOp(_) => UNARY,
+        ExprKind::VarDecl(_) | ExprKind::Assignment(_) | ExprKind::If(_) |
+        ExprKind::Block(_) | ExprKind::Include(_) => LOOSE,
+        _ => POSTFIX,
+    }
+}
+
+// has to line up with the parser's precedence table
+fn precedence(op: &TokenType) -> u8 {
+    match op {
+        TokenType::DblEquals | TokenType::Lt | TokenType::Gt |
+        TokenType::Lte | TokenType::Gte | TokenType::Neq => 1,
+        TokenType::Add | TokenType::Sub => 2,
+        _ => 3,
+    }
+}
+
+fn op_str(op: &TokenType) -> &'static str {
+    match op {
+        TokenType::Add => "+",
+        TokenType::Sub => "-",
+        TokenType::Mul => "*",
+        TokenType::Div => "/",
+        TokenType::Mod => "%",
+        TokenType::DblEquals => "==",
+        TokenType::Neq => "!=",
+        TokenType::Lt => "<",
+        TokenType::Gt => ">",
+        TokenType::Lte => "<=",
+        TokenType::Gte => ">=",
+        _ => "?",
+    }
+}
+
+fn args_str(args: &[Expr], indent: usize, mut col: usize) -> String {
+    let mut strs = Vec::new();
+    for a in args {
+        let s = expr_str(a, indent, col);
+        col = after(col, &s) + 2;
+        strs.push(s);
+    }
+    strs.join(", ")
+}
+
+// whole floats still need their decimal point, `2` would come back as an int
+fn number_str(n: f64) -> String {
+    let s = n.to_string();
+    if s.contains('.') || !n.is_finite() {
+        s
+    } else {
+        format!("{}.0", s)
+    }
+}
+
+// there are no escapes, so the only choice is which quote to use
+pub(crate) fn quote_string(s: &str) -> String {
+    if s.contains('"') && !s.contains('\'') {
+        format!("'{}'", s)
+    } else {
+        format!("\"{}\"", s)
+    }
+}
+
+fn block_str(block: &Block, indent: usize) -> String {
+    if block.exprs.is_empty() {
+        return "{}".to_string();
+    }
+
+    let mut out = "{\n".to_string();
+    write_lines(&mut out, &block.exprs, indent + 1);
+    out.push_str(&INDENT.repeat(indent));
+    out.push('}');
+    out
+}
+
+fn if_str(i: &If, indent: usize, col: usize) -> String {
+    let mut out = format!("if {} {}", expr_str(&i.cond, indent, col + 3), block_str(&i.block, indent));
+
+    if let Some(else_block) = &i.else_block {
+        // the parser stores `else if` as an else block holding just the if
+        match else_block.exprs.as_slice() {
+            [Expr { kind: ExprKind::If(next), .. }] => {
+                out.push_str(" else ");
+                let next = if_str(next, indent, after(col, &out));
+                out.push_str(&next);
+            },
+            _ => {
+                out.push_str(" else ");
+                out.push_str(&block_str(else_block, indent));
+            },
+        }
+    }
+
+    out
+}
+
+fn collection_str(c: &Collection, indent: usize, col: usize) -> String {
+    if c.entries.is_empty() {
+        return "[]".to_string();
+    }
+
+    // laid out for their own lines, if they all end up on this one instead
+    // it's only because the whole thing fit
+    let entry_col = INDENT.len() * (indent + 1);
+    let entries: Vec<String> = c.entries.iter().map(|e| entry_str(e, indent + 1, entry_col)).collect();
+
+    // on one line if it fits, otherwise one entry per line
+    let inline = format!("[{}]", entries.join(", "));
+    if !inline.contains('\n') && col + inline.len() <= MAX_WIDTH {
+        return inline;
+    }
+
+    let mut out = "[\n".to_string();
+    for entry in entries {
+        out.push_str(&INDENT.repeat(indent + 1));
+        out.push_str(&entry);
+        out.push_str(",\n");
+    }
+    out.push_str(&INDENT.repeat(indent));
+    out.push(']');
+    out
+}
+
+fn entry_str(entry: &CEntry, indent: usize, col: usize) -> String {
+    let (key, e) = match entry {
+        // a bare `x = 1` would be read back as a key
+        CEntry::Indexed(e @ Expr { kind: ExprKind::Assignment(_), .. }) => return format!("({})", expr_str(e, indent, col + 1)),
+        CEntry::Indexed(e) => return expr_str(e, indent, col),
+        CEntry::Keyed(key, e) => (if is_bare_key(key) { key.to_string() } else { quote_string(key) }, e),
+        // the parser turns int keys into floats, so whole ones go back to ints
+        CEntry::NumKeyed(n, e) => (if n.fract() == 0.0 { format!("{}", *n as i64) } else { n.to_string() }, e),
+        CEntry::BoolKeyed(b, e) => (b.to_string(), e),
+        CEntry::NilKeyed(e) => ("nil".to_string(), e),
+    };
+    format!("{} = {}", key, expr_str(e, indent, col + key.len() + 3))
+}
+
+// can this key be written bare, or does it need quotes. true, false and nil
+// are quoted too so a string key never looks like a bool or nil one when printed
+pub(crate) fn is_bare_key(s: &str) -> bool {
+    let mut chars = s.chars();
+    let starts_ok = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_');
+    starts_ok
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && !matches!(s, "val" | "if" | "else" | "include" | "true" | "false" | "nil")
+}
@@ -1,253 +1,865 @@
-use crate::ast::{Array, Assignment, BinaryOp, Block, Call, Expr, FieldAccess, If, IndexAccess, MethodCall, Collection, UnaryOp, VarDecl, CEntry};
-use crate::stdlib::{REGISTRY_OPTIONAL, REGISTRY_STD};
+use crate::ast::{Assignment, BinaryOp, Block, Call, Expr, ExprKind, FieldAccess, If, Include, IndexAccess, MethodCall, Collection, UnaryOp, VarDecl, CEntry};
+use crate::bigint::BigInt;
+use crate::builder::InterpreterBuilder;
+use crate::environment::Environment;
+use crate::error::{ErrorKind, HexiError};
+use crate::format;
+use crate::number;
+use crate::stdlib::fs::{TempPaths, Watches};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::stdlib::log::LogConfig;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::stdlib::timer::Timers;
+use crate::stdlib::sys::Signals;
+use crate::stdlib::test::TestResults;
+use crate::stdlib::{bytes, Module, CTX_GLOBALS, GLOBALS, REGISTRY_OPTIONAL, REGISTRY_STD};
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use crate::lexer::TokenType;
-
-#[derive(Debug, Clone, PartialEq)]
+use crate::limits::Limits;
+use crate::native::{Ctx, HostFn, HostModule, Userdata};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::plugin::{self, Plugin};
+use crate::symbol::Symbol;
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
 pub enum Value {
-    Number(f64),
-    String(String),
+    Int(i64),
+    BigInt(Rc<BigInt>), // ints that outgrew i64, always normalized back down when they fit again
+    Number(f64),    // floats, anything with a decimal point or that doesn't fit an int
+    String(Rc<str>),    // strings are immutable, so reading one just bumps a refcount
+    Bytes(Rc<[u8]>),    // raw binary data, just as immutable
     Bool(bool),
-    Collection(CValue),
+    // collections are shared references, so `val b = a` points at the same collection
+    Collection(Rc<RefCell<CValue>>),
+    Userdata(Rc<Userdata>),  // some rust object from the host or a native module
     Nil,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+// like a lua table, indices 0..n live in a plain vec so arrays never touch
+// the hashmap, everything else (keys, sparse indices) goes in the map part.
+// map entries remember the order they were inserted in, so printing and
+// iterating a collection always comes out the same way
+#[derive(Debug, Clone)]
 pub struct CValue {
-    pub entries: HashMap<CKey, Value>,
-    pub size: usize,
+    array: Vec<Value>,
+    order: Vec<CKey>,
+    entries: HashMap<CKey, Value>,
+    size: usize,    // highest index + 1, holes included
+    frozen: bool,   // set by freeze(), the mutating collection natives refuse to touch it
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub enum CKey {
-    Index(usize),
-    String(String),
-    Number(String),
+// dropping a collection drops what's in it, so one nested thousands deep
+// (`x = [x]` in a loop) would recurse until the stack ran out. instead the
+// collections only this one holds on to get emptied onto a list and dropped
+// one at a time, each of them shallow by then
+impl Drop for CValue {
+    fn drop(&mut self) {
+        let mut pending = Vec::new();
+        self.take_collections(&mut pending);
+
+        while let Some(c) = pending.pop() {
+            if Rc::strong_count(&c) == 1 && let Ok(mut inner) = c.try_borrow_mut() {
+                inner.take_collections(&mut pending);
+            }
+        }
+    }
 }
 
-pub trait Method {
-    fn call_method(&mut self, method: &str, args: &[Value]) -> Result<Value, String>;
-    fn got_method(&self, method: &str) -> bool;
+impl CValue {
+    // moves out every collection held in a value or a key, leaving the rest to drop as usual
+    fn take_collections(&mut self, out: &mut Vec<Rc<RefCell<CValue>>>) {
+        let keys = self.order.drain(..).filter_map(|k| match k {
+            CKey::Ref(RefKey(v)) => Some(v),
+            _ => None,
+        });
+        let keyed = self.entries.drain().flat_map(|(k, v)| match k {
+            CKey::Ref(RefKey(k)) => vec![k, v],
+            _ => vec![v],
+        });
+
+        for v in self.array.drain(..).chain(keys).chain(keyed) {
+            if let Value::Collection(c) = v {
+                out.push(c);
+            }
+        }
+    }
 }
 
-impl Method for Value {
-    fn call_method(&mut self, method: &str, args: &[Value]) -> Result<Value, String> {
-        match self {
-            Value::Collection(c) => {
-                match method {
-                    "push" => {
-                        if args.len() != 1 {
-                            return Err(format!("push method on array expects 1 argument, got {}", args.len()));
-                        }
-
-                        c.push(args[0].clone());
-                        Ok(Value::Nil)
-                    },
-
-                    "pop" => {
-                        if args.len() != 0 {
-                            return Err(format!("pop method on array expects no argument, got {}", args.len()));
-                        }
-
-                        Ok(c.pop().unwrap_or(Value::Nil))
-                    },
-
-                    "size" => {
-                        if args.len() != 0 {
-                            return Err(format!("size method on array expects no argument, got {}", args.len()));
-                        }
-
-                        Ok(Value::Number(c.len() as f64))
-                    },
-
-                    "get" => {
-                        if args.len() != 1 {
-                            return Err(format!("get method expects 1 argument, got {}", args.len()));
-                        }
-
-                        let key = match &args[0] {
-                            Value::Number(n) => CKey::Index(*n as usize),
-                            Value::String(s) => CKey::String(s.clone()),
-                            _ => return Err("collection key must be a number or string".to_string()),
-                        };
-
-                        Ok(c.get(&key).cloned().unwrap_or(Value::Nil))
-                    },
+// two collections with the same entries are equal no matter how they were built
+impl PartialEq for CValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_nested(other, &mut Vec::new())
+    }
+}
 
-                    "insert" => {
-                        if args.len() != 2 {
-                            return Err(format!("insert method expects 2 arguments, got {}", args.len()));
-                        }
-
-                        let key = match &args[0] {
-                            Value::Number(n) => {
-                                let idx = *n as usize;
-                                if c.is_array_like() && idx > c.size {
-                                    return Err(format!("index {} is out of bounds", idx));
-                                }
-                                CKey::Index(idx)
-                            },
-
-                            Value::String(s) => CKey::String(s.clone()),
-                            _ => return Err("insert key must be a number or string".to_string()),
-                        };
-
-                        c.insert(key, args[1].clone());
-                        Ok(Value::Nil)
-                    },
+// any value can be a key except NaN, see Value::to_key for which one each
+// value turns into
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CKey {
+    Index(usize),
+    String(Rc<str>),
+    Number(String),     // negative, fractional or huge numbers, in their display form
+    Bool(bool),
+    Nil,
+    Bytes(Rc<[u8]>),    // by contents, bytes compare like strings do
+    Ref(RefKey),
+}
 
-                    _ => Err(format!("unknown method '{}' for array.", method))
-                }
-            },
+// collections and userdata used as keys go by identity like lua tables, two
+// different collections with the same contents are two different keys
+#[derive(Debug, Clone)]
+pub struct RefKey(Value);
+
+impl RefKey {
+    fn ptr(&self) -> *const () {
+        match &self.0 {
+            Value::Collection(c) => Rc::as_ptr(c) as *const (),
+            Value::Userdata(u) => Rc::as_ptr(u) as *const (),
+            _ => unreachable!(),
+        }
+    }
 
-            Value::String(s) => {
-                match method {
-                    "len" => {
-                        if args.len() != 0 {
-                            return Err(format!("len method on string expects no arguments, got {}", args.len()));
-                        }
+    pub fn value(&self) -> &Value {
+        &self.0
+    }
+}
 
-                        Ok(Value::Number(s.len() as f64))
-                    },
+impl PartialEq for RefKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr() == other.ptr()
+    }
+}
 
-                    _ => Err(format!("unknown method '{}' for string.", method))
-                }
-            },
+impl Eq for RefKey {}
 
-            _ => Err(format!("cannot call method '{}' on {:?}", method, self.type_name())),
-        }
+impl std::hash::Hash for RefKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.ptr().hash(state);
     }
+}
 
-    fn got_method(&self, method: &str) -> bool {
+impl CKey {
+    // the value this key was made from, give or take int vs float
+    pub fn to_value(&self) -> Value {
         match self {
-            Value::Collection(_) => matches!(method, "push" | "pop" | "size" | "get" | "insert"),
-            Value::String(_) => matches!(method, "len"),
-            _ => false
+            CKey::Index(i) => Value::Int(*i as i64),
+            CKey::String(s) => Value::String(s.clone()),
+            CKey::Number(n) => match n.parse::<i64>() {
+                Ok(i) => Value::Int(i),
+                Err(_) => BigInt::parse(n, 10).map_or_else(|| Value::Number(n.parse().unwrap_or(f64::NAN)), Value::from_bigint),
+            },
+            CKey::Bool(b) => Value::Bool(*b),
+            CKey::Nil => Value::Nil,
+            CKey::Bytes(b) => Value::Bytes(b.clone()),
+            CKey::Ref(r) => r.0.clone(),
         }
     }
 }
 
+// strings print as they are at the top level, but inside a collection they're
+// quoted so ["1", 1] and [a = "b c"] don't come out looking the same as
+// something else. collections that don't fit on a line (or that hold other
+// multi line ones) get one entry per line, indented like formatted source
 impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            Value::Number(n) => write!(f, "{}", n),
             Value::String(s) => write!(f, "{}", s),
-            Value::Nil => write!(f, "nil"),
-            Value::Bool(b) => write!(f, "{}", b),
+            _ => write!(f, "{}", self.display_nested(&mut Vec::new())),
+        }
+    }
+}
+
+// collections deeper than this print as [...]
+const MAX_DISPLAY_DEPTH: usize = 16;
+const DISPLAY_WIDTH: usize = 80;
+
+impl Value {
+    // path is the collections we're currently inside of, a collection that
+    // shows up in its own path contains itself and prints as [...]
+    fn display_nested(&self, path: &mut Vec<*const ()>) -> String {
+        match self {
+            Value::Int(i) => i.to_string(),
+            Value::BigInt(b) => b.to_string(),
+            Value::Number(n) => number::format_float(*n),
+            Value::String(s) => format::quote_string(s),
+            Value::Bytes(b) => bytes::display(b),
+            Value::Nil => "nil".to_string(),
+            Value::Userdata(u) => format!("<{}>", u.type_name()),
+            Value::Bool(b) => b.to_string(),
             Value::Collection(c) => {
-                if c.is_array_like() {
-                    write!(f, "[")?;
-                    let mut first = true;
-                    for i in 0..c.size {
-                        if !first { write!(f, ", ")?; }
-                        if let Some(val) = c.get_by_index(i) {
-                            write!(f, "{}", val)?;
-                        } else {
-                            write!(f, "nil")?;
-                        }
-                        first = false;
-                    }
-                    write!(f, "]")
-                } else {
-                    write!(f, "[")?;
-                    let mut first = true;
-                    for (key, value) in &c.entries {
-                        if !first { write!(f, ", ")?; }
-                        match key {
-                            CKey::String(s) => write!(f, "{} = {}", s, value)?,
-                            CKey::Number(n) => write!(f, "{} = {}", n, value)?,
-                            CKey::Index(i) => write!(f, "{} = {}", i, value)?,
-                        }
-                        first = false;
-                    }
-                    write!(f, "]")
+                let c_ref = c.borrow();
+                if c_ref.is_empty() {
+                    return "[]".to_string();
                 }
-            }
+
+                let ptr = c.as_ptr() as *const ();
+                if path.len() >= MAX_DISPLAY_DEPTH || path.contains(&ptr) {
+                    return "[...]".to_string();
+                }
+
+                path.push(ptr);
+                let out = c_ref.display_nested(path);
+                path.pop();
+                out
+            },
+        }
+    }
+}
+
+impl CValue {
+    fn display_nested(&self, path: &mut Vec<*const ()>) -> String {
+        let mut entries = vec![];
+
+        // the dense part bare and then every other entry with its key, like
+        // [1, 2, name = "x"] or [1, 5 = "sparse"]
+        for v in &self.array {
+            entries.push(v.display_nested(path));
         }
+        for key in &self.order {
+            let value = self.entries[key].display_nested(path);
+            let key = match key {
+                CKey::String(s) if format::is_bare_key(s) => s.to_string(),
+                CKey::String(s) => format::quote_string(s),
+                CKey::Number(n) => n.clone(),
+                CKey::Index(i) => i.to_string(),
+                CKey::Bool(b) => b.to_string(),
+                CKey::Nil => "nil".to_string(),
+                CKey::Bytes(b) => bytes::display(b),
+                CKey::Ref(r) => r.value().display_nested(path),
+            };
+            entries.push(format!("{} = {}", key, value));
+        }
+
+        let inline = format!("[{}]", entries.join(", "));
+        let indent = path.len().saturating_sub(1) * 4;
+        if !inline.contains('\n') && indent + inline.len() <= DISPLAY_WIDTH {
+            return inline;
+        }
+
+        let mut out = "[\n".to_string();
+        for entry in entries {
+            out.push_str("    ");
+            out.push_str(&entry.replace('\n', "\n    "));
+            out.push_str(",\n");
+        }
+        out.push(']');
+        out
     }
 }
 
-type Native = fn(&[Value]) -> Result<Value, String>;
+// how deep evaluate is allowed to recurse before we bail out with an error
+// instead of blowing the native stack, binary op chains don't count towards
+// this since they're walked with an explicit stack
+const MAX_DEPTH: usize = 512;
 
 pub struct Interpreter {
-    natives: HashMap<String, Native>,
-    vars: HashMap<String, Value>,
+    natives: HashMap<Symbol, HostFn>,
+    // value.method(...) looks here by the value's type name, the value itself
+    // is passed in as the first argument
+    methods: HashMap<(&'static str, Symbol), HostFn>,
+    constants: HashMap<Symbol, Value>,  // module constants by full name, math::PI
+    env: Rc<RefCell<Environment>>,
+    globals: Rc<RefCell<Environment>>,  // the outermost scope, env is somewhere below it
     loaded_modules: HashSet<String>,
+    module_funcs: HashMap<String, Vec<String>>, // every module whose functions are there to call, for modules()
+    escaped: Option<HexiError>,     // see escape()
+    aliases: HashMap<Symbol, Symbol>,   // from `include time as t`, t -> time
+    allowed_modules: Option<HashSet<String>>,   // what scripts may include, None for anything
+    depth: usize,
+    limits: Limits,
+    steps: u64,
+    started: Option<Instant>,   // when the first expression ran under the current limits
+    float_precision: Option<usize>, // see set_float_precision()
+    interrupt: Arc<AtomicBool>,
+    output: Box<dyn Write>,     // where io natives print to
+    error_output: Box<dyn Write>, // io::eprint and friends
+    input: Box<dyn BufRead>,    // and read from
+    script: Option<String>,     // the file being run, if it came from one
+    args: Vec<String>,          // whatever followed it on the command line
+    test_results: TestResults,  // cases test::case has run
+    temp_paths: TempPaths,      // from fs::temp_file and fs::temp_dir, removed when the interpreter goes
+    watches: Watches,           // for fs::watch_run
+    signals: Signals,           // code from sys::on_signal
+    #[cfg(not(target_arch = "wasm32"))]
+    log_config: LogConfig,  // log::set_level and log::set_format
+    #[cfg(not(target_arch = "wasm32"))]
+    timers: Timers,     // waiting for timer::run
+    #[cfg(not(target_arch = "wasm32"))]
+    plugin_dirs: Vec<PathBuf>,
+    #[cfg(not(target_arch = "wasm32"))]
+    plugins: Vec<Plugin>,   // after natives so the libraries outlive the functions pointing into them
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Interpreter::new()
+    }
 }
 
 impl Interpreter {
     pub fn new() -> Interpreter {
+        let mut i = Interpreter::bare();
+        i.load_std();
+        i
+    }
+
+    // to pick the preloaded modules, allowed includes, limits and io up front
+    pub fn builder() -> InterpreterBuilder {
+        InterpreterBuilder::new()
+    }
+
+    // no modules loaded at all, just the global functions
+    pub(crate) fn bare() -> Interpreter {
+        let globals = Rc::new(RefCell::new(Environment::new()));
         let mut i = Interpreter {
             natives: HashMap::new(),
-            vars: HashMap::new(),
+            methods: HashMap::new(),
+            constants: HashMap::new(),
+            env: globals.clone(),
+            globals,
             loaded_modules: HashSet::new(),
+            module_funcs: HashMap::new(),
+            escaped: None,
+            aliases: HashMap::new(),
+            allowed_modules: None,
+            depth: 0,
+            limits: Limits::default(),
+            steps: 0,
+            started: None,
+            float_precision: None,
+            interrupt: Arc::new(AtomicBool::new(false)),
+            output: Box::new(io::stdout()),
+            error_output: Box::new(io::stderr()),
+            input: Box::new(io::BufReader::new(io::stdin())),
+            script: None,
+            args: Vec::new(),
+            test_results: TestResults::default(),
+            temp_paths: TempPaths::default(),
+            watches: Watches::default(),
+            signals: Signals::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            log_config: LogConfig::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            timers: Timers::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            plugin_dirs: plugin::default_dirs(),
+            #[cfg(not(target_arch = "wasm32"))]
+            plugins: Vec::new(),
         };
 
-        i.load_std();
+        for (name, fptr) in GLOBALS {
+            let f = *fptr;
+            i.insert_native(name, Rc::new(move |_: &mut Ctx, args: &[Value]| Ok(f(args)?)));
+        }
+        for (name, fptr) in CTX_GLOBALS {
+            let f = *fptr;
+            i.insert_native(name, Rc::new(move |ctx: &mut Ctx, args: &[Value]| f(ctx, args).map_err(|m| ctx.interpreter().recover(m))));
+        }
+        i
+    }
+
+    pub fn with_limits(limits: Limits) -> Interpreter {
+        let mut i = Interpreter::new();
+        i.set_limits(limits);
         i
     }
 
-    fn load_std(&mut self) {
+    // swapping limits also starts the step count and the clock over
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+        self.reset_limits();
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    // floats this interpreter prints are rounded to at most `digits` decimal
+    // places, with trailing zeros dropped (Some(0) rounds to whole numbers).
+    // None, the default, goes back to the shortest exact form. values turned
+    // into text by the host after a run has finished don't see this
+    pub fn set_float_precision(&mut self, digits: Option<usize>) {
+        self.float_precision = digits.map(|d| d.min(number::MAX_PRECISION));
+    }
+
+    pub fn float_precision(&self) -> Option<usize> {
+        self.float_precision
+    }
+
+    // setting the returned flag (from a signal handler, another thread, ...) stops
+    // whatever is running at the next expression, the flag is cleared once it's seen
+    pub fn interrupt_handle(&self) -> Arc<AtomicBool> {
+        self.interrupt.clone()
+    }
+
+    // not from a native that's already inside a run though, or a script could
+    // get a fresh budget by having one run more code for it
+    fn start_run(&mut self) {
+        if self.depth == 0 {
+            self.reset_limits();
+        }
+    }
+
+    // starts the step count and the clock over. eval_str and call do this
+    // themselves, evaluate() doesn't since the cli runs a script through it
+    // one expression at a time
+    pub fn reset_limits(&mut self) {
+        self.steps = 0;
+        self.started = None;
+    }
+
+    // called once per evaluated expression
+    fn tick(&mut self) -> Result<(), HexiError> {
+        if self.interrupt.swap(false, Ordering::Relaxed) {
+            // a script that asked to clean up first gets to, then it stops all the same
+            if let Some((signal, code)) = self.signals.take_handler() {
+                self.signals.set_running(true);
+                let result = self.eval_with(&code, &[("signal", Value::String(signal.into()))]);
+                self.signals.set_running(false);
+                result?;
+            }
+            return Err(HexiError::new(ErrorKind::Interrupted, "execution was cancelled", None));
+        }
+
+        self.steps += 1;
+        if let Some(max) = self.limits.max_steps && self.steps > max {
+            return Err(HexiError::limit(format!("exceeded the maximum of {} evaluation steps", max)));
+        }
+
+        if let Some(timeout) = self.limits.timeout {
+            let started = *self.started.get_or_insert_with(Instant::now);
+            if started.elapsed() > timeout {
+                return Err(HexiError::limit(format!("script ran for longer than {:?}", timeout)));
+            }
+        }
+
+        Ok(())
+    }
+
+    // strings and collections only get checked where they're created or grown,
+    // so a value that's already in bounds never needs another look
+    fn check_size(&self, value: &Value) -> Result<(), HexiError> {
+        match value {
+            Value::String(s) => self.check_string_len(s.len()),
+            Value::Collection(c) => match self.limits.max_collection_size {
+                Some(max) if c.borrow().len() > max => Err(HexiError::limit(format!("collection of {} entries is bigger than the maximum of {}", c.borrow().len(), max))),
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn load_std(&mut self) {
         for module in REGISTRY_STD {
-            for (name, fptr) in module.funcs {
-                let realname = format!("{}_{}", module.name, name);
-                self.natives.insert(realname.clone(), *fptr);
-
-                // match (module.name, *name) {
-                //     ("io", "print") | ("io", "println") | ("io", "input") => {
-                //         self.natives.insert(name.to_string(), *fptr);
-                //     }
-                //     ("math", _) => {
-                //         self.natives.insert(name.to_string(), *fptr);
-                //     },
-                //     ("string", "len") => {
-                //         self.natives.insert(name.to_string(), *fptr);
-                //     },
-                //     _ => {}
-                // }
+            self.load_builtin(module);
+        }
+    }
+
+    // stdlib natives are plain fn pointers, most of which don't need the context
+    fn load_builtin(&mut self, module: &Module) {
+        let mut funcs: Vec<(&str, HostFn)> = vec![];
+        for (name, fptr) in module.funcs {
+            let f = *fptr;
+            funcs.push((name, Rc::new(move |_: &mut Ctx, args: &[Value]| Ok(f(args)?))));
+        }
+
+        for (name, fptr) in module.ctx_funcs {
+            let f = *fptr;
+            funcs.push((name, Rc::new(move |ctx: &mut Ctx, args: &[Value]| f(ctx, args).map_err(|m| ctx.interpreter().recover(m)))));
+        }
+
+        self.module_funcs.insert(module.name.to_string(), funcs.iter().map(|(name, _)| name.to_string()).collect());
+        for (name, f) in funcs {
+            for type_name in module.method_types {
+                self.methods.insert((type_name, Symbol::intern(name)), f.clone());
+            }
+            self.insert_native(&format!("{}_{}", module.name, name), f);
+        }
+
+        for (name, value) in module.consts {
+            self.constants.insert(Symbol::intern(&format!("{}::{}", module.name, name)), value.to_value());
+        }
+    }
+
+    // send everything io prints somewhere other than stdout
+    pub fn set_output(&mut self, output: impl Write + 'static) {
+        self.output = Box::new(output);
+    }
+
+    // same for io::eprint and io::eprintln, which go to stderr otherwise
+    pub fn set_error_output(&mut self, output: impl Write + 'static) {
+        self.error_output = Box::new(output);
+    }
+
+    // read io::input from somewhere other than stdin
+    pub fn set_input(&mut self, input: impl BufRead + 'static) {
+        self.input = Box::new(input);
+    }
+
+    // what sys::script_path() and sys::args() give scripts, the cli passes the
+    // file it runs and everything after it
+    pub fn set_script(&mut self, path: impl Into<String>, args: Vec<String>) {
+        self.script = Some(path.into());
+        self.args = args;
+    }
+
+    pub fn script_path(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    pub fn script_args(&self) -> &[String] {
+        &self.args
+    }
+
+    // what test::case has counted so far, `hexi test` reads it after each file
+    pub fn test_results(&self) -> TestResults {
+        self.test_results
+    }
+
+    pub(crate) fn test_results_mut(&mut self) -> &mut TestResults {
+        &mut self.test_results
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn log_config(&mut self) -> &mut LogConfig {
+        &mut self.log_config
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn timers(&mut self) -> &mut Timers {
+        &mut self.timers
+    }
+
+    pub(crate) fn watches(&mut self) -> &mut Watches {
+        &mut self.watches
+    }
+
+    pub(crate) fn signals(&mut self) -> &mut Signals {
+        &mut self.signals
+    }
+
+    pub(crate) fn add_temp_path(&mut self, path: PathBuf) {
+        self.temp_paths.add(path);
+    }
+
+    pub fn output(&mut self) -> &mut dyn Write {
+        &mut *self.output
+    }
+
+    pub fn error_output(&mut self) -> &mut dyn Write {
+        &mut *self.error_output
+    }
+
+    pub fn input(&mut self) -> &mut dyn BufRead {
+        &mut *self.input
+    }
+
+    fn insert_native(&mut self, signature: &str, f: HostFn) {
+        self.natives.insert(Symbol::intern(signature), f);
+    }
+
+    // a global function callable as name(...), replacing any native already called that
+    pub fn register_fn<F>(&mut self, name: &str, f: F)
+    where
+        F: Fn(&mut Ctx, &[Value]) -> Result<Value, HexiError> + 'static,
+    {
+        self.insert_native(name, Rc::new(f));
+    }
+
+    // a method callable as value.name(...) on every value whose type_name is
+    // `type_name`, the value goes in as the first argument. it's looked up after
+    // a userdata's own methods, and replaces any method already there
+    pub fn register_method<F>(&mut self, type_name: &'static str, name: &str, f: F)
+    where
+        F: Fn(&mut Ctx, &[Value]) -> Result<Value, HexiError> + 'static,
+    {
+        self.methods.insert((type_name, Symbol::intern(name)), Rc::new(f));
+    }
+
+    // host modules are usable straight away, an `include` of one is a no-op
+    pub fn register_module(&mut self, module: HostModule) {
+        self.module_funcs.insert(module.name.clone(), module.funcs.iter().map(|(name, _)| name.clone()).collect());
+        for (name, f) in module.funcs {
+            for type_name in &module.method_types {
+                self.methods.insert((type_name, Symbol::intern(&name)), f.clone());
             }
+            self.insert_native(&format!("{}_{}", module.name, name), f);
+        }
+
+        for (name, value) in module.consts {
+            self.constants.insert(Symbol::intern(&format!("{}::{}", module.name, name)), value);
         }
+
+        self.loaded_modules.insert(module.name);
+    }
+
+    // the modules loaded so far, sorted
+    pub fn module_names(&self) -> Vec<&str> {
+        let mut names: Vec<_> = self.module_funcs.keys().map(String::as_str).collect();
+        names.sort();
+        names
+    }
+
+    // the functions in a loaded module, sorted. None if it isn't loaded
+    pub fn module_functions(&self, module: &str) -> Option<Vec<&str>> {
+        let mut names: Vec<_> = self.module_funcs.get(module)?.iter().map(String::as_str).collect();
+        names.sort();
+        Some(names)
     }
 
-    fn load_module(&mut self, mod_name: &str) -> Result<Value, String> {
+    pub(crate) fn set_allowed_modules(&mut self, modules: Option<Vec<String>>) {
+        self.allowed_modules = modules.map(|m| m.into_iter().collect());
+    }
+
+    pub(crate) fn load_module(&mut self, mod_name: &str) -> Result<Value, HexiError> {
         if self.loaded_modules.contains(mod_name) {
             return Ok(Value::Nil);
         }
 
-        if let Some(module) = REGISTRY_OPTIONAL.iter().find(|m| m.name == mod_name) {
-            for (name, fptr) in module.funcs {
-                let realname = format!("{}_{}", module.name, name);
-                self.natives.insert(realname.clone(), *fptr);
+        if let Some(allowed) = &self.allowed_modules && !allowed.contains(mod_name) {
+            return Err(HexiError::runtime(format!("module '{}' is not allowed here", mod_name)));
+        }
+
+        // std modules can be left out of the preload by a builder, so they're
+        // includable like the optional ones
+        let builtin = REGISTRY_STD.iter().chain(REGISTRY_OPTIONAL).find(|m| m.name == mod_name);
+        if let Some(module) = builtin {
+            self.load_builtin(module);
+        } else if !self.load_plugin(mod_name)? {
+            return Err(HexiError::runtime(format!("module '{}' not found", mod_name)));
+        }
+
+        self.loaded_modules.insert(mod_name.to_string());
+        Ok(Value::Nil)
+    }
+
+    fn exec_include(&mut self, include: &Include) -> Result<Value, HexiError> {
+        for item in &include.modules {
+            self.load_module(&item.module)?;
+            if let Some(alias) = &item.alias {
+                self.add_alias(alias, &item.module)?;
             }
+        }
+        Ok(Value::Nil)
+    }
 
-            self.loaded_modules.insert(mod_name.to_string());
-            Ok(Value::Nil)
-        } else {
-            Err(format!("module '{}' not found", mod_name))
+    // an alias is just another name for calls and constants, the module's own
+    // name keeps working too. including the same thing under the same alias
+    // again is fine, pointing an alias somewhere else isn't
+    fn add_alias(&mut self, alias: &str, module: &str) -> Result<(), HexiError> {
+        let (alias, module) = (Symbol::intern(alias), Symbol::intern(module));
+        match self.aliases.get(&alias) {
+            Some(existing) if *existing == module => return Ok(()),
+            Some(existing) => return Err(HexiError::runtime(format!("'{}' is already an alias for '{}'", alias, existing))),
+            None => {},
+        }
+        if alias != module && self.loaded_modules.contains(alias.as_str()) {
+            return Err(HexiError::runtime(format!("can't use '{}' as an alias, it's a module already", alias)));
+        }
+
+        self.aliases.insert(alias, module);
+        Ok(())
+    }
+
+    // the native an aliased call like t::now() stands for
+    fn aliased_native(&self, call: &Call) -> Option<&HostFn> {
+        let module = self.aliases.get(&call.module?)?;
+        self.natives.get(&Symbol::lookup(&format!("{}_{}", module, call.name))?)
+    }
+
+    // same for constants, t::PI
+    fn aliased_constant(&self, name: Symbol) -> Option<Value> {
+        let (alias, rest) = name.as_str().split_once("::")?;
+        let module = self.aliases.get(&Symbol::lookup(alias)?)?;
+        self.constants.get(&Symbol::lookup(&format!("{}::{}", module, rest))?).cloned()
+    }
+
+    // false if there's no plugin by that name
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_plugin(&mut self, mod_name: &str) -> Result<bool, HexiError> {
+        let Some(path) = plugin::find(mod_name, &self.plugin_dirs) else {
+            return Ok(false);
+        };
+
+        let p = plugin::load(&path)?;
+        self.load_builtin(&p.module);
+        self.plugins.push(p);
+        Ok(true)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn load_plugin(&mut self, _mod_name: &str) -> Result<bool, HexiError> {
+        Ok(false)
+    }
+
+    // where `include` looks for native plugins, checked after the ones already there
+    // for natives that can tell how long a string will be before building it,
+    // one too big to even allocate would abort long before check_size saw it
+    pub(crate) fn check_string_len(&self, len: usize) -> Result<(), HexiError> {
+        match self.limits.max_string_len {
+            Some(max) if len > max => Err(HexiError::limit(format!("string of {} bytes is longer than the maximum of {}", len, max))),
+            _ if len > isize::MAX as usize => Err(HexiError::runtime(format!("string of {} bytes is too long to make", len))),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_plugin_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.plugin_dirs.push(dir.into());
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.globals.borrow().get(Symbol::lookup(name)?)
+    }
+
+    // defines the global if it doesn't exist yet, otherwise overwrites it
+    pub fn set_global(&mut self, name: &str, value: Value) {
+        let name = Symbol::intern(name);
+        let mut globals = self.globals.borrow_mut();
+        if globals.assign(name, value.clone()).is_err() {
+            let _ = globals.define(name, value);
+        }
+    }
+
+    // call a native the way hexi code would, `name` can be `module::func` or a
+    // plain name. limits start over like they do for eval_str
+    pub fn call(&mut self, name: &str, args: &[Value]) -> Result<Value, HexiError> {
+        self.start_run();
+        let sig = name.replacen("::", "_", 1);
+        let f = Symbol::lookup(&sig)
+            .and_then(|sig| self.natives.get(&sig))
+            .cloned()
+            .ok_or_else(|| HexiError::runtime(format!("undefined function '{}'", name)))?;
+
+        let digits = self.float_precision;
+        number::with_precision(digits, || f(&mut Ctx::new(self), args))
+    }
+
+    // parse and run a whole script, handing back whatever the last expression
+    // evaluated to (nil for an empty one), variables stick around between calls.
+    // each one gets the full step count and timeout, except from a native
+    // that's already inside a run
+    pub fn eval_str(&mut self, source: &str) -> Result<Value, HexiError> {
+        self.start_run();
+        let mut result = Value::Nil;
+        for expr in crate::parse(source)? {
+            result = self.evaluate(&expr)?;
+        }
+
+        Ok(result)
+    }
+
+    // builtin natives hand back their errors as plain strings, which would
+    // turn an interrupt or a limit hit in code they ran into an ordinary
+    // runtime error. so those wait here while their message goes up through
+    // the native, and recover() swaps them back in once it's returned
+    pub(crate) fn escape(&mut self, e: HexiError) -> String {
+        if !matches!(e.kind, ErrorKind::Limit | ErrorKind::Interrupted) {
+            return e.message;
         }
+        let message = e.message.clone();
+        self.escaped = Some(e);
+        message
     }
 
-    pub fn evaluate(&mut self, expr: &Expr) -> Result<Value, String> {
-        match expr {
-            Expr::Number(n) => Ok(Value::Number(*n)),
-            Expr::String(s) => Ok(Value::String(s.to_string())),
-            Expr::Identifier(name) => self.vars.get(name).cloned().ok_or_else(|| format!("undefined variable or reference '{}'", name)),
-            Expr::Call(c) => self.exec_call(c),
-            Expr::Collection(c) => self.exec_collection(c),
-            Expr::IndexAccess(ia) => self.exec_idx_access(ia),
-            Expr::MethodCall(mc) => self.exec_method_call(mc),
-            Expr::VarDecl(v) => self.exec_var_decl(v),
-            Expr::Assignment(a) => self.exec_assignment(a),
-            Expr::BinaryOp(b) => self.exec_binary_op(b),
-            Expr::UnaryOp(u) => self.exec_unary_op(u),
-            Expr::If(i) => self.exec_if(i),
-            Expr::Block(b) => self.exec_block(b),
-            Expr::Include(i) => self.load_module(&i.module),
-            Expr::FieldAccess(fa) => self.exec_fa(fa),
+    // the span pointed into the code the native ran, the call site gets
+    // filled in instead
+    fn recover(&mut self, message: String) -> HexiError {
+        match self.escaped.take() {
+            // the native may have put some context in front
+            Some(mut e) if message.ends_with(&e.message) => {
+                e.span = None;
+                e
+            },
+            _ => HexiError::runtime(message),
+        }
+    }
+
+    // runs already parsed code in a new scope under the current one with some
+    // names defined up front, for natives that run a bit of code per element
+    pub fn eval_with(&mut self, exprs: &[Expr], bindings: &[(&str, Value)]) -> Result<Value, HexiError> {
+        let mut scope = Environment::with_parent(self.env.clone());
+        for (name, value) in bindings {
+            scope.define(Symbol::intern(name), value.clone()).map_err(HexiError::runtime)?;
+        }
+        let previous = std::mem::replace(&mut self.env, Rc::new(RefCell::new(scope)));
+
+        let mut result = Ok(Value::Nil);
+        for e in exprs {
+            result = self.evaluate(e);
+            if result.is_err() {
+                break;
+            }
+        }
+
+        self.env = previous;
+        result
+    }
+
+    pub fn evaluate(&mut self, expr: &Expr) -> Result<Value, HexiError> {
+        // the outermost expression sets up what lasts for the whole run
+        if self.depth == 0 {
+            let digits = self.float_precision;
+            return number::with_precision(digits, || self.evaluate_expr(expr));
+        }
+        self.evaluate_expr(expr)
+    }
+
+    fn evaluate_expr(&mut self, expr: &Expr) -> Result<Value, HexiError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(HexiError::runtime("expression nested too deeply").with_span(expr.span));
+        }
+
+        self.tick().map_err(|e| e.with_span(expr.span))?;
+
+        self.depth += 1;
+        // errors bubbling up without a location get pinned to this node
+        let result = self.eval_kind(&expr.kind).map_err(|e| e.with_span(expr.span));
+        self.depth -= 1;
+        result
+    }
+
+    fn eval_kind(&mut self, kind: &ExprKind) -> Result<Value, HexiError> {
+        match kind {
+            ExprKind::Int(i) => Ok(Value::Int(*i)),
+            ExprKind::BigInt(b) => Ok(Value::BigInt(Rc::new(b.clone()))),
+            ExprKind::Number(n) => Ok(Value::Number(*n)),
+            ExprKind::String(s) => Ok(Value::String(s.clone())),
+            ExprKind::Bool(b) => Ok(Value::Bool(*b)),
+            ExprKind::Nil => Ok(Value::Nil),
+            ExprKind::Identifier(name) => self.env.borrow().get(*name)
+                .or_else(|| self.constants.get(name).cloned())
+                .or_else(|| self.aliased_constant(*name))
+                .ok_or_else(|| match name.as_str().split_once("::") {
+                    Some((module, _)) if !self.loaded_modules.contains(module) && !self.aliases.contains_key(&Symbol::intern(module)) => {
+                        HexiError::runtime(format!("undefined constant '{}', module '{}' isn't included", name, module))
+                    },
+                    Some(_) => HexiError::runtime(format!("undefined constant '{}'", name)),
+                    None => HexiError::runtime(format!("undefined variable or reference '{}'", name)),
+                }),
+            ExprKind::Call(c) => self.exec_call(c),
+            ExprKind::Collection(c) => self.exec_collection(c),
+            ExprKind::IndexAccess(ia) => self.exec_idx_access(ia),
+            ExprKind::MethodCall(mc) => self.exec_method_call(mc),
+            ExprKind::VarDecl(v) => self.exec_var_decl(v),
+            ExprKind::Assignment(a) => self.exec_assignment(a),
+            ExprKind::BinaryOp(b) => self.exec_binary_op(b),
+            ExprKind::UnaryOp(u) => self.exec_unary_op(u),
+            ExprKind::If(i) => self.exec_if(i),
+            ExprKind::Block(b) => self.exec_block(b),
+            ExprKind::Include(i) => self.exec_include(i),
+            ExprKind::FieldAccess(fa) => self.exec_fa(fa),
         }
     }
 
-    fn exec_collection(&mut self, co: &Collection) -> Result<Value, String> {
+    fn exec_collection(&mut self, co: &Collection) -> Result<Value, HexiError> {
         let mut c = CValue::new();
         let mut idx = 0;
 
@@ -266,27 +878,38 @@ impl Interpreter {
 
                 CEntry::NumKeyed(n, ex) => {
                     let v = self.evaluate(ex)?;
-                    c.insert(CKey::Number(n.to_string()), v);
+                    // [1 = x] has to land in the same slot c[1] reads from
+                    c.insert(Value::Number(*n).to_key().unwrap(), v);
+                },
+
+                CEntry::BoolKeyed(b, ex) => {
+                    let v = self.evaluate(ex)?;
+                    c.insert(CKey::Bool(*b), v);
+                },
+
+                CEntry::NilKeyed(ex) => {
+                    let v = self.evaluate(ex)?;
+                    c.insert(CKey::Nil, v);
                 },
             }
         }
 
-        if idx > 0 { c.size = idx; }
-
-        Ok(Value::Collection(c))
+        let c = Value::collection(c);
+        self.check_size(&c)?;
+        Ok(c)
     }
 
-    fn exec_fa(&mut self, fa: &FieldAccess) -> Result<Value, String> {
+    fn exec_fa(&mut self, fa: &FieldAccess) -> Result<Value, HexiError> {
         let ovalue = self.evaluate(&fa.object)?;
         match ovalue {
             Value::Collection(c) => {
-                c.get_by_string(&fa.field).cloned().ok_or_else(|| format!("undefined field '{}'", fa.field))
+                c.borrow().get(&CKey::String(fa.field.clone())).cloned().ok_or_else(|| HexiError::runtime(format!("undefined field '{}'", fa.field)))
             },
-            _ => Err(format!("cannot access field '{}' on non object", fa.field))
+            _ => Err(HexiError::runtime(format!("cannot access field '{}' on non object", fa.field)))
         }
     }
 
-    fn exec_if(&mut self, i: &If) -> Result<Value, String> {
+    fn exec_if(&mut self, i: &If) -> Result<Value, HexiError> {
         let cond = self.evaluate(&i.cond)?;
         // if statements should only allow conditions which are truthy
         if cond.is_truthy() {
@@ -301,23 +924,31 @@ impl Interpreter {
         }
     }
 
-    fn exec_block(&mut self, b: &Block) -> Result<Value, String> {
-        let mut last = Value::Nil;
+    // every block gets a fresh scope on top of the current one
+    fn exec_block(&mut self, b: &Block) -> Result<Value, HexiError> {
+        let scope = Rc::new(RefCell::new(Environment::with_parent(self.env.clone())));
+        self.exec_block_in(b, scope)
+    }
+
+    // run a block inside the given scope, restoring the previous one afterwards
+    // (even if the block errors out), functions and loops will want this too
+    fn exec_block_in(&mut self, b: &Block, env: Rc<RefCell<Environment>>) -> Result<Value, HexiError> {
+        let previous = std::mem::replace(&mut self.env, env);
+
+        let mut result = Ok(Value::Nil);
         // these are basically statements but im too lazy to refactor
         for e in &b.exprs {
-            last = self.evaluate(e)?;
+            result = self.evaluate(e);
+            if result.is_err() {
+                break;
+            }
         }
 
-        Ok(last)
-    }
-
-    fn exec_array(&mut self, a: &Array) -> Result<Value, String> {
-        let mut values = Vec::new();
-        for v in &a.values { values.push(self.evaluate(v)?); }
-        Ok(Value::Collection(CValue::from_array(values)))
+        self.env = previous;
+        result
     }
 
-    fn exec_idx_access(&mut self, ia: &IndexAccess) -> Result<Value, String> {
+    fn exec_idx_access(&mut self, ia: &IndexAccess) -> Result<Value, HexiError> {
         // let arr = self.evaluate(&ia.object)?;
         // let idx = self.evaluate(&ia.index)?;
         // match (arr, idx) {
@@ -338,19 +969,20 @@ impl Interpreter {
 
         match col {
             Value::Collection(c) => {
-                let key = match idx {
-                    Value::Number(n) => CKey::Index(n as usize),
-                    Value::String(s) => CKey::String(s),
-                    _ => return Err("collection index must be a number or string".to_string()),
-                };
+                let key = idx.to_key().ok_or_else(|| HexiError::runtime("NaN can't be used as a collection key"))?;
 
-                Ok(c.get(&key).cloned().unwrap_or(Value::Nil))
+                Ok(c.borrow().get(&key).cloned().unwrap_or(Value::Nil))
             }
-            _ => Err(format!("cannot index into {}", col.type_name()))
+            // the byte as an int, nil past either end like a collection
+            Value::Bytes(b) => match idx {
+                Value::Int(i) => Ok(usize::try_from(i).ok().and_then(|i| b.get(i)).map_or(Value::Nil, |b| Value::Int(*b as i64))),
+                _ => Err(HexiError::runtime(format!("bytes can only be indexed by an int, got {}", idx.type_name()))),
+            },
+            _ => Err(HexiError::runtime(format!("cannot index into {}", col.type_name())))
         }
     }
 
-    fn exec_method_call(&mut self, mc: &MethodCall) -> Result<Value, String> {
+    fn exec_method_call(&mut self, mc: &MethodCall) -> Result<Value, HexiError> {
         // let obj = self.evaluate(&mc.object);
         // let mut args = Vec::new();
         // for a in &mc.args { args.push(self.evaluate(a)?); }
@@ -383,130 +1015,92 @@ impl Interpreter {
         let mut args = Vec::new();
         for a in &mc.args { args.push(self.evaluate(a)?); }
 
-        // calling method on an identifier?
-        // some_arr.size()
-        if let Expr::Identifier(id) = &*mc.object {
-            if let Some(mut val) = self.vars.get(id).cloned() {
-                let meth_result = val.call_method(&mc.method, &args)?;
-                self.vars.insert(id.clone(), val);  // we wanna update incase the method mutates the obj
-                return Ok(meth_result);
-            } else {
-                return Err(format!("undefined variable '{}'", id));
-            }
+        // collections are shared, so mutating methods like push just work on
+        // whatever the object evaluates to, be it some_arr.push(1), person.pets.push("cat")
+        // or [ 1, 2, 3, 4 ].size()
+        let o = self.evaluate(&mc.object)?;
+        if let Value::Userdata(u) = &o && let Some(m) = u.method(&mc.method) {
+            return m(&mut Ctx::new(self), u, &args);
         }
 
-        // and then handle method calls on exprs
-        // val v = [ 1, 2, 3, 4 ].size()
-        let mut o = self.evaluate(&mc.object)?;
-        o.call_method(&mc.method, &args)
+        let m = match self.methods.get(&(o.type_name(), mc.method)) {
+            Some(m) => m.clone(),
+            None => return Err(HexiError::runtime(format!("unknown method '{}' for {}", mc.method, o.type_name()))),
+        };
+
+        args.insert(0, o.clone());
+        let result = m(&mut Ctx::new(self), &args)?;
+        // push and insert grow the object itself
+        self.check_size(&o)?;
+        self.check_size(&result)?;
+        Ok(result)
     }
 
-    fn exec_unary_op(&mut self, u: &UnaryOp) -> Result<Value, String> {
+    fn exec_unary_op(&mut self, u: &UnaryOp) -> Result<Value, HexiError> {
         let operand = self.evaluate(&u.operand)?;
         match u.op {
             TokenType::Sub => match operand {
+                Value::Int(i) => Ok(i.checked_neg().map(Value::Int).unwrap_or_else(|| Value::from_bigint(BigInt::from_i64(i).neg()))),
+                Value::BigInt(b) => Ok(Value::from_bigint(b.neg())),
                 Value::Number(n) => Ok(Value::Number(-n)),  // negate numbers
-                _ => Err("negate unary operator only supported on numbers".to_string())
+                _ => Err(HexiError::runtime("negate unary operator only supported on numbers"))
             },
-            _ => Err(format!("unsupported unary operator {:?}", u.op))
+            _ => Err(HexiError::runtime(format!("unsupported unary operator {:?}", u.op)))
         }
     }
 
-    fn exec_call(&mut self, call: &Call) -> Result<Value, String> {
+    fn exec_call(&mut self, call: &Call) -> Result<Value, HexiError> {
         let mut args = Vec::new();
         for a in &call.args { args.push(self.evaluate(a)?); }
 
         let sig = call.signature();   // get the signature of the function (full name of the function)
-        if let Some(f) = self.natives.get(&sig) {
-            f(&args)
-        } else {
-            if let Some(f) = self.natives.get(&call.name) {
-                f(&args)
-            } else {
-                Err(format!("undefined function '{}'", call.name))
-            }
-        }
-    }
-
-    fn exec_binary_op(&mut self, b: &BinaryOp) -> Result<Value, String> {
-        let left = self.evaluate(&b.left)?;
-        let right = self.evaluate(&b.right)?;
-
-        match b.op {
-            TokenType::DblEquals => {
-                Ok(Value::Bool(left == right))
-            },
-
-            TokenType::Lt => {
-                Ok(Value::Bool(left < right))
-            },
-
-            TokenType::Gt => {
-                Ok(Value::Bool(left > right))
-            },
-
-            TokenType::Lte => {
-                Ok(Value::Bool(left <= right))
-            },
+        // the native is cloned out first so it can borrow the interpreter through the context
+        let f = match self.natives.get(&sig).or_else(|| self.aliased_native(call)).or_else(|| self.natives.get(&call.name)) {
+            Some(f) => f.clone(),
+            None => return Err(HexiError::runtime(format!("undefined function '{}'", call.name))),
+        };
 
-            TokenType::Gte => {
-                Ok(Value::Bool(left >= right))
-            },
+        let result = f(&mut Ctx::new(self), &args)?;
 
-            TokenType::Neq => {
-                Ok(Value::Bool(left != right))
-            },
+        self.check_size(&result)?;
+        Ok(result)
+    }
 
-            TokenType::Add | TokenType::Sub | TokenType::Mul | TokenType::Div | TokenType::Mod => {
-                match (left, right) {
-                    (Value::Number(l), Value::Number(r)) => {
-                        let result = match b.op {
-                            TokenType::Add => l + r,
-                            TokenType::Sub => l - r,
-                            TokenType::Mul => l * r,
-                            TokenType::Div => {
-                                if r == 0.0 {
-                                    return Err("division by zero".to_string());
-                                }
-                                l / r
-                            },
-                            TokenType::Mod => {
-                                if r == 0.0 {
-                                    return Err("modulo by zero".to_string());
-                                }
-                                l % r
-                            },
-                            _ => unreachable!(), // done
-                        };
-                        Ok(Value::Number(result))
-                    },
-                    _ => Err("arithmetic operations can only be performed on numbers".to_string())
-                }
-            },
+    // `a + b + c + ...` parses into a tree that leans left, as deep as the chain
+    // is long, so walk down the left side with our own stack and fold the
+    // results back up instead of recursing once per operator
+    fn exec_binary_op(&mut self, b: &BinaryOp) -> Result<Value, HexiError> {
+        let mut chain = vec![b];
+        let mut leftmost = &b.left;
+        while let ExprKind::BinaryOp(inner) = &leftmost.kind {
+            chain.push(inner);
+            leftmost = &inner.left;
+        }
 
-            _ => Err(format!("unsupported binary operator {:?}", b.op))
+        let mut left = self.evaluate(leftmost)?;
+        for (i, op) in chain.iter().enumerate().rev() {
+            let right = self.evaluate(&op.right)?;
+            // the first op in the chain is this node, evaluate pins that one already
+            left = binary_op(&op.op, left, right).map_err(|e| if i == 0 { e } else { e.with_span(chain[i - 1].left.span) })?;
         }
+
+        Ok(left)
     }
 
-    fn exec_var_decl(&mut self, var: &VarDecl) -> Result<Value, String> {
-        if self.vars.contains_key(&var.name) {
-            Err(format!("variable '{}' already defined!", var.name))
-        } else {
-            let value = self.evaluate(var.value.as_ref())?;
-            self.vars.insert(var.name.clone(), value);
-            Ok(Value::Nil)
-        }
+    fn exec_var_decl(&mut self, var: &VarDecl) -> Result<Value, HexiError> {
+        let value = self.evaluate(var.value.as_ref())?;
+        self.env.borrow_mut().define(var.name, value)?;
+        Ok(Value::Nil)
     }
 
-    fn exec_assignment(&mut self, assignment: &Assignment) -> Result<Value, String> {
-        if self.vars.contains_key(&assignment.name) {
-            // referenced https://doc.rust-lang.org/book/ch08-03-hash-maps.html
-            let avalue = self.evaluate(assignment.assignee.as_ref())?;
-            self.vars.entry(assignment.name.clone()).and_modify(|v| *v = avalue);
-            Ok(Value::Nil)
-        } else {
-            Err(format!("variable '{}' not defined!", assignment.name))
+    fn exec_assignment(&mut self, assignment: &Assignment) -> Result<Value, HexiError> {
+        if !self.env.borrow().contains(assignment.name) {
+            return Err(HexiError::runtime(format!("variable '{}' not defined!", assignment.name)));
         }
+
+        let avalue = self.evaluate(assignment.assignee.as_ref())?;
+        self.env.borrow_mut().assign(assignment.name, avalue)?;
+        Ok(Value::Nil)
     }
     // not used/
     // fn exec_mul(&mut self, exprs: &[Expr]) -> Result<Vec<Value>, String> {
@@ -515,18 +1109,210 @@ impl Interpreter {
     //     Ok(results)
     // }
 
-    pub fn dbg_print_variables(&self) {
-        for (name, value) in self.vars.clone().into_iter() {
-            println!("{} = {}", name, value);
+    // every variable visible from the current scope sorted by name, where an
+    // inner one hides any outer one with the same name
+    pub fn visible_variables(&self) -> Vec<(Symbol, Value)> {
+        let mut vars: HashMap<Symbol, Value> = HashMap::new();
+        let mut scope = Some(self.env.clone());
+        while let Some(env) = scope {
+            for (name, value) in env.borrow().locals() {
+                vars.entry(*name).or_insert_with(|| value.clone());
+            }
+            scope = env.borrow().parent();
         }
+
+        let mut vars: Vec<_> = vars.into_iter().collect();
+        vars.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        vars
+    }
+}
+
+// comparisons and arithmetic on two already evaluated operands
+fn binary_op(op: &TokenType, left: Value, right: Value) -> Result<Value, HexiError> {
+    match op {
+        TokenType::DblEquals => {
+            Ok(Value::Bool(left == right))
+        },
+
+        TokenType::Lt => {
+            Ok(Value::Bool(left < right))
+        },
+
+        TokenType::Gt => {
+            Ok(Value::Bool(left > right))
+        },
+
+        TokenType::Lte => {
+            Ok(Value::Bool(left <= right))
+        },
+
+        TokenType::Gte => {
+            Ok(Value::Bool(left >= right))
+        },
+
+        TokenType::Neq => {
+            Ok(Value::Bool(left != right))
+        },
+
+        TokenType::Add | TokenType::Sub | TokenType::Mul | TokenType::Div | TokenType::Mod => {
+            match (&left, &right) {
+                // ints stay ints as long as the result is exact
+                (Value::Int(l), Value::Int(r)) => int_arith(op, *l, *r),
+                (Value::Int(_) | Value::BigInt(_), Value::Int(_) | Value::BigInt(_)) => {
+                    big_arith(op, &left.as_bigint().unwrap(), &right.as_bigint().unwrap())
+                },
+                // anything else mixing in a float becomes a float
+                _ => match (left.as_f64(), right.as_f64()) {
+                    (Some(l), Some(r)) => float_arith(op, l, r),
+                    _ => Err(HexiError::runtime("arithmetic operations can only be performed on numbers")),
+                },
+            }
+        },
+
+        _ => Err(HexiError::runtime(format!("unsupported binary operator {:?}", op)))
+    }
+}
+
+// int <op> int promotion rules:
+// + - * % stay ints, and quietly move over to a bigint if they overflow
+// / stays an int only if it divides exactly, otherwise it becomes a float (7 / 2 = 3.5)
+fn int_arith(op: &TokenType, l: i64, r: i64) -> Result<Value, HexiError> {
+    let result = match op {
+        TokenType::Add => l.checked_add(r),
+        TokenType::Sub => l.checked_sub(r),
+        TokenType::Mul => l.checked_mul(r),
+        TokenType::Div => {
+            if r == 0 {
+                return Err(HexiError::runtime("division by zero"));
+            }
+            if l % r != 0 {
+                return Ok(Value::Number(l as f64 / r as f64));
+            }
+            l.checked_div(r)
+        },
+        TokenType::Mod => {
+            if r == 0 {
+                return Err(HexiError::runtime("modulo by zero"));
+            }
+            l.checked_rem(r)
+        },
+        _ => unreachable!(),
+    };
+
+    match result {
+        Some(i) => Ok(Value::Int(i)),
+        None => big_arith(op, &BigInt::from_i64(l), &BigInt::from_i64(r)),
+    }
+}
+
+// same rules as int_arith, just for when at least one side is too big for an i64
+fn big_arith(op: &TokenType, l: &BigInt, r: &BigInt) -> Result<Value, HexiError> {
+    let result = match op {
+        TokenType::Add => l.add(r),
+        TokenType::Sub => l.sub(r),
+        TokenType::Mul => l.mul(r),
+        TokenType::Div => {
+            let (q, rem) = l.div_rem(r).ok_or_else(|| HexiError::runtime("division by zero"))?;
+            if !rem.is_zero() {
+                return Ok(Value::Number(l.to_f64() / r.to_f64()));
+            }
+            q
+        },
+        TokenType::Mod => l.div_rem(r).ok_or_else(|| HexiError::runtime("modulo by zero"))?.1,
+        _ => unreachable!(),
+    };
+
+    Ok(Value::from_bigint(result))
+}
+
+fn float_arith(op: &TokenType, l: f64, r: f64) -> Result<Value, HexiError> {
+    let result = match op {
+        TokenType::Add => l + r,
+        TokenType::Sub => l - r,
+        TokenType::Mul => l * r,
+        TokenType::Div => {
+            if r == 0.0 {
+                return Err(HexiError::runtime("division by zero"));
+            }
+            l / r
+        },
+        TokenType::Mod => {
+            if r == 0.0 {
+                return Err(HexiError::runtime("modulo by zero"));
+            }
+            l % r
+        },
+        _ => unreachable!(), // done
+    };
+
+    Ok(Value::Number(result))
+}
+
+// ints and floats compare by value, so 1 == 1.0
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_nested(other, &mut Vec::new())
+    }
+}
+
+impl Value {
+    // path holds the pairs of collections being compared further up, running
+    // into the same pair again means both sides loop back on themselves and
+    // the comparison would never finish, so that counts as not equal
+    fn eq_nested(&self, other: &Value, path: &mut Vec<(*const (), *const ())>) -> bool {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
+            // bigints are normalized, so one can never equal an i64
+            (Value::Int(_), Value::BigInt(_)) | (Value::BigInt(_), Value::Int(_)) => false,
+            (Value::Int(_) | Value::BigInt(_), Value::Number(_)) | (Value::Number(_), Value::Int(_) | Value::BigInt(_)) => self.as_f64() == other.as_f64(),
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Collection(a), Value::Collection(b)) => {
+                if Rc::ptr_eq(a, b) {
+                    return true;
+                }
+
+                let pair = (a.as_ptr() as *const (), b.as_ptr() as *const ());
+                if path.contains(&pair) {
+                    return false;
+                }
+
+                path.push(pair);
+                let eq = a.borrow().eq_nested(&b.borrow(), path);
+                path.pop();
+                eq
+            },
+            (Value::Nil, Value::Nil) => true,
+            // the same object, not just the same type
+            (Value::Userdata(a), Value::Userdata(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl CValue {
+    fn eq_nested(&self, other: &CValue, path: &mut Vec<(*const (), *const ())>) -> bool {
+        self.size == other.size
+            && self.array.len() == other.array.len()
+            && self.entries.len() == other.entries.len()
+            && self.array.iter().zip(&other.array).all(|(a, b)| a.eq_nested(b, path))
+            && self.entries.iter().all(|(k, a)| other.entries.get(k).is_some_and(|b| a.eq_nested(b, path)))
     }
 }
 
 impl PartialOrd for Value {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         match (self, other) {
-            (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+            (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
+            (Value::Int(_) | Value::BigInt(_), Value::Int(_) | Value::BigInt(_)) => self.as_bigint()?.partial_cmp(&other.as_bigint()?),
+            (Value::Int(_) | Value::BigInt(_) | Value::Number(_), Value::Int(_) | Value::BigInt(_) | Value::Number(_)) => {
+                self.as_f64()?.partial_cmp(&other.as_f64()?)
+            },
             (Value::String(a), Value::String(b)) => a.partial_cmp(b),
+            (Value::Bytes(a), Value::Bytes(b)) => a.partial_cmp(b),
             (Value::Bool(a), Value::Bool(b)) => a.partial_cmp(b),
             _ => None,
         }
@@ -537,30 +1323,124 @@ impl PartialOrd for Value {
 impl Value {
     pub fn type_name(&self) -> &'static str {
         match self {
-            Value::Number(_) => "number",
+            Value::Int(_) | Value::BigInt(_) => "int",
+            Value::Number(_) => "float",
             Value::String(_) => "string",
+            Value::Bytes(_) => "bytes",
             Value::Bool(_) => "bool",
             Value::Collection(_) => "collection",
+            Value::Userdata(u) => u.type_name(),
             Value::Nil => "nil",
         }
     }
 
+    // ints and floats both count as numbers
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(i) => Some(*i as f64),
+            Value::BigInt(b) => Some(b.to_f64()),
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bigint(&self) -> Option<BigInt> {
+        match self {
+            Value::Int(i) => Some(BigInt::from_i64(*i)),
+            Value::BigInt(b) => Some(b.as_ref().clone()),
+            _ => None,
+        }
+    }
+
+    // ints that fit go back to being plain ints
+    pub fn from_bigint(b: BigInt) -> Value {
+        match b.to_i64() {
+            Some(i) => Value::Int(i),
+            None => Value::BigInt(Rc::new(b)),
+        }
+    }
+
+    // whole non negative numbers are indices whether they're ints or floats, so
+    // c[1] and c[1.0] are the same slot. every other number is a Number key
+    // named by how it prints, so -1 and -1.0 match too. NaN is never equal to
+    // itself, so it can't be a key at all
+    pub fn to_key(&self) -> Option<CKey> {
+        match self {
+            Value::Int(i) if *i >= 0 => Some(CKey::Index(*i as usize)),
+            Value::Int(i) => Some(CKey::Number(i.to_string())),
+            Value::BigInt(b) => Some(CKey::Number(b.to_string())),
+            Value::Number(n) if n.is_nan() => None,
+            Value::Number(n) if n.fract() == 0.0 && *n >= 0.0 && *n <= i64::MAX as f64 => Some(CKey::Index(*n as usize)),
+            Value::Number(n) => Some(CKey::Number(n.to_string())),
+            Value::String(s) => Some(CKey::String(s.clone())),
+            Value::Bool(b) => Some(CKey::Bool(*b)),
+            Value::Nil => Some(CKey::Nil),
+            Value::Bytes(b) => Some(CKey::Bytes(b.clone())),
+            Value::Collection(_) | Value::Userdata(_) => Some(CKey::Ref(RefKey(self.clone()))),
+        }
+    }
+
+    pub fn userdata(u: Userdata) -> Value {
+        Value::Userdata(Rc::new(u))
+    }
+
+    pub fn as_userdata(&self) -> Option<&Userdata> {
+        match self {
+            Value::Userdata(u) => Some(u),
+            _ => None,
+        }
+    }
+
+    // collections all the way down get copied, keeping any sharing and cycles
+    // between them the same in the copy. everything else is immutable (or
+    // userdata, which can't be copied) so it's shared as it is, keys included
+    pub fn deep_copy(&self) -> Value {
+        self.deep_copy_with(&mut HashMap::new())
+    }
+
+    fn deep_copy_with(&self, copies: &mut HashMap<*const (), Value>) -> Value {
+        let Value::Collection(c) = self else {
+            return self.clone();
+        };
+
+        let ptr = c.as_ptr() as *const ();
+        if let Some(copy) = copies.get(&ptr) {
+            return copy.clone();
+        }
+
+        let copy = Rc::new(RefCell::new(CValue::new()));
+        copies.insert(ptr, Value::Collection(copy.clone()));
+
+        let entries: Vec<(CKey, Value)> = c.borrow().iter().map(|(k, v)| (k, v.clone())).collect();
+        for (k, v) in entries {
+            let v = v.deep_copy_with(copies);
+            copy.borrow_mut().insert(k, v);
+        }
+        Value::Collection(copy)
+    }
+
     pub fn new_collection() -> Value {
-        Value::Collection(CValue::new())
+        Value::collection(CValue::new())
     }
 
+    // wrap a collection up so it can be shared around
+    pub fn collection(c: CValue) -> Value {
+        Value::Collection(Rc::new(RefCell::new(c)))
+    }
+
+    // keeps the pairs in the order they're given
     pub fn from_pairs(pairs: Vec<(String, Value)>) -> Value {
-        let mut obj = HashMap::new();
-        for (key, value) in pairs {
-            obj.insert(key, value);
+        let mut c = CValue::new();
+        for (k, val) in pairs {
+            c.insert(CKey::String(k.into()), val);
         }
-
-        Value::Collection(CValue::from_object(obj))
+        Value::collection(c)
     }
 
+    #[allow(clippy::wrong_self_convention)]
     pub fn as_string(self) -> Result<String, String> {
         match self {
-            Value::String(s) => Ok(s),
+            Value::String(s) => Ok(s.to_string()),
             _ => Err(format!("{:?} is not a string", self)),
         }
     }
@@ -594,61 +1474,103 @@ impl Value {
     pub fn is_truthy(&self) -> bool {
         match self {
             Value::Bool(false) | Value::Nil => false,
-            Value::Collection(c) if c.entries.is_empty() => false,
+            Value::Collection(c) if c.borrow().is_empty() => false,
             _ => true,
         }
     }
 }
 
+impl Default for CValue {
+    fn default() -> Self {
+        CValue::new()
+    }
+}
+
 impl CValue {
     pub fn new() -> Self {
         Self {
+            array: Vec::new(),
+            order: Vec::new(),
             entries: HashMap::new(),
-            size: 0
+            size: 0,
+            frozen: false,
         }
     }
 
     pub fn from_array(values: Vec<Value>) -> Self {
-        let mut entries = HashMap::new();
-        for (i, val) in values.iter().enumerate() {
-            entries.insert(CKey::Index(i), val.clone());
-        }
-
         Self {
-            entries,
-            size: values.len()
+            size: values.len(),
+            array: values,
+            order: Vec::new(),
+            entries: HashMap::new(),
+            frozen: false,
         }
     }
 
+    // hashmaps have no order of their own, so the keys go in sorted to keep
+    // printing the same from run to run
     pub fn from_object(obj: HashMap<String, Value>) -> Self {
-        let mut entries = HashMap::new();
-        for (k, val) in obj {
-            entries.insert(CKey::String(k), val.clone());
-        }
+        let mut pairs: Vec<_> = obj.into_iter().collect();
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
 
-        Self {
-            entries,
-            size: 0
+        let mut c = CValue::new();
+        for (k, val) in pairs {
+            c.insert(CKey::String(k.into()), val);
         }
+
+        c
     }
 
     pub fn get(&self, key: &CKey) -> Option<&Value> {
-        self.entries.get(key)
+        match key {
+            CKey::Index(i) if *i < self.array.len() => self.array.get(*i),
+            _ => self.entries.get(key),
+        }
     }
 
     pub fn insert(&mut self, key: CKey, value: Value) {
-        if let CKey::Index(i) = &key {
-            if *i >= self.size {
-                self.size = *i + 1;
+        if let CKey::Index(i) = key {
+            if i >= self.size {
+                self.size = i + 1;
+            }
+
+            if i < self.array.len() {
+                self.array[i] = value;
+                return;
             }
+
+            if i == self.array.len() {
+                self.array.push(value);
+                self.absorb();
+                return;
+            }
+        }
+
+        // only new keys get a spot at the end, overwriting keeps the old position
+        if self.entries.insert(key.clone(), value).is_none() {
+            self.order.push(key);
+        }
+    }
+
+    // after the array part grows, pull any indices that now continue it out of the map
+    fn absorb(&mut self) {
+        while let Some(v) = self.remove_entry(&CKey::Index(self.array.len())) {
+            self.array.push(v);
+        }
+    }
+
+    fn remove_entry(&mut self, key: &CKey) -> Option<Value> {
+        let value = self.entries.remove(key)?;
+        // recently added keys are the likeliest to be removed, so search from the back
+        if let Some(pos) = self.order.iter().rposition(|k| k == key) {
+            self.order.remove(pos);
         }
 
-        self.entries.insert(key, value);
+        Some(value)
     }
 
     pub fn push(&mut self, value: Value) {
-        self.entries.insert(CKey::Index(self.size), value);
-        self.size += 1;
+        self.insert(CKey::Index(self.size), value);
     }
 
     pub fn pop(&mut self) -> Option<Value> {
@@ -657,26 +1579,103 @@ impl CValue {
         }
 
         self.size -= 1;
-        self.entries.remove(&CKey::Index(self.size))
+        self.remove(&CKey::Index(self.size))
+    }
+
+    pub fn remove(&mut self, key: &CKey) -> Option<Value> {
+        match key {
+            CKey::Index(i) if *i < self.array.len() => {
+                // anything after the hole can't stay in the dense part
+                let tail = self.array.split_off(*i + 1);
+                let value = self.array.pop();
+                for (offset, v) in tail.into_iter().enumerate() {
+                    let k = CKey::Index(*i + 1 + offset);
+                    self.order.push(k.clone());
+                    self.entries.insert(k, v);
+                }
+                value
+            },
+            _ => self.remove_entry(key),
+        }
+    }
+
+    // for a plain list, takes index i out and moves everything after it down one
+    // instead of leaving a hole. None if it isn't a list or i is past the end
+    pub fn remove_shift(&mut self, i: usize) -> Option<Value> {
+        if !self.is_sequence() || i >= self.array.len() {
+            return None;
+        }
+
+        self.size -= 1;
+        Some(self.array.remove(i))
+    }
+
+    pub fn clear(&mut self) {
+        self.array.clear();
+        self.order.clear();
+        self.entries.clear();
+        self.size = 0;
+    }
+
+    // there's no unfreezing, clone() gives a copy that isn't frozen instead
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    // for rebuilding a collection whose holes run past its last entry
+    pub(crate) fn extend_size(&mut self, size: usize) {
+        self.size = self.size.max(size);
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
     }
 
     pub fn len(&self) -> usize {
         if self.is_array_like() {
             self.size
         } else {
-            self.entries.len()
+            self.array.len() + self.entries.len()
         }
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty() && self.entries.is_empty()
+    }
+
+    // the dense part first in index order, then the rest in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (CKey, &Value)> {
+        let dense = self.array.iter().enumerate().map(|(i, v)| (CKey::Index(i), v));
+        dense.chain(self.order.iter().map(|k| (k.clone(), &self.entries[k])))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = CKey> + '_ {
+        self.iter().map(|(k, _)| k)
+    }
+
+    // nothing but indices 0..n with no holes, a plain list
+    pub fn is_sequence(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // the dense part (the whole thing for a sequence) to rearrange in place
+    pub fn dense_mut(&mut self) -> &mut [Value] {
+        &mut self.array
+    }
+
     pub fn is_array_like(&self) -> bool {
         self.size > 0 || self.entries.keys().all(|k| matches!(k, CKey::Index(_)))
     }
 
     pub fn get_by_index(&self, index: usize) -> Option<&Value> {
-        self.entries.get(&CKey::Index(index))
+        self.get(&CKey::Index(index))
     }
 
     pub fn get_by_string(&self, key: &str) -> Option<&Value> {
-        self.entries.get(&CKey::String(key.to_string()))
+        self.entries.get(&CKey::String(key.into()))
     }
-}
\ No newline at end of file
+}
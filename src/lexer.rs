@@ -1,6 +1,9 @@
+use crate::error::{HexiError, Span};
 use std::collections::HashMap;
+use std::fmt;
+use serde::Serialize;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum TokenType {
     Ident,
     Number,
@@ -32,6 +35,9 @@ pub enum TokenType {
     Mod,
     If,
     Else,
+    True,
+    False,
+    Nil,
     Eof,
 }
 
@@ -39,15 +45,41 @@ pub enum TokenType {
 pub struct Token {
     pub token_type: TokenType,
     pub lexeme: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for LexError {}
+
+// bad tokens are reported the same way as bad syntax
+impl From<LexError> for HexiError {
+    fn from(e: LexError) -> Self {
+        HexiError::parse(e.message, e.span)
+    }
 }
 
+// the span gets filled in by next() once the whole token has been lexed
 fn make_token(token_type: TokenType, lexeme: String) -> Token {
-    Token { token_type, lexeme }
+    Token { token_type, lexeme, span: Span::default() }
 }
 
 pub struct Lexer<'a> {
     source: &'a str,
-    pos: usize,
+    pos: usize,     // byte offset of the current char
+    line: usize,
+    col: usize,
+    start: (usize, usize),  // line and col of the token we're currently lexing
     keywords: HashMap<&'a str, TokenType>,
 }
 
@@ -59,14 +91,31 @@ impl<'a> Lexer<'a> {
         keywords.insert("if", TokenType::If);
         keywords.insert("else", TokenType::Else);
         keywords.insert("include", TokenType::Include);
+        keywords.insert("true", TokenType::True);
+        keywords.insert("false", TokenType::False);
+        keywords.insert("nil", TokenType::Nil);
+
+        // a #! line at the very top is for the shell, so scripts can be run
+        // directly. the newline is left for skip_ws to count
+        let pos = if source.starts_with("#!") { source.find('\n').unwrap_or(source.len()) } else { 0 };
 
         Lexer {
             source,
-            pos: 0,
+            pos,
+            line: 1,
+            col: 1,
+            start: (1, 1),
             keywords,
         }
     }
 
+    // span of the last token handed out by next(), or where we are if we hit eof
+    pub fn token_span(&self) -> Span {
+        let (line, col) = self.start;
+        let len = if self.line == line { self.col - col } else { 1 };
+        Span::new(line, col, len)
+    }
+
     fn skip_ws(&mut self) {
         while let Some(c) = self.current() {
             if c.is_whitespace() {
@@ -97,19 +146,28 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    // the char after the current one
     pub fn peek(&self) -> Option<char> {
-        self.source.chars().nth(self.pos + 1)
+        let mut rest = self.source[self.pos..].chars();
+        rest.next();
+        rest.next()
+    }
+
+    // error covering everything from the start of the current token up to here
+    fn error(&self, message: impl Into<String>) -> LexError {
+        LexError { message: message.into(), span: self.token_span() }
     }
 
-    pub fn next(self: &mut Lexer<'a>) -> Option<Token> {
+    fn lex_token(&mut self) -> Option<Result<Token, LexError>> {
         self.skip_ws();
+        self.start = (self.line, self.col);
 
         let c = self.current()?;
 
-        match c {
+        let token = match c {
             c if c.is_alphabetic() || c == '_' => Some(self.process_identifier()),
-            c if c.is_numeric() => Some(self.process_number()),
-            '"' | '\'' => Some(self.process_string()),
+            c if c.is_ascii_digit() => return Some(self.process_number()),
+            '"' | '\'' => return Some(self.process_string()),
 
             ';' => self.stoken(TokenType::Semi),
             '(' => self.stoken(TokenType::LParen),
@@ -131,7 +189,7 @@ impl<'a> Lexer<'a> {
             '<' => self.dtoken('=', TokenType::Lte, TokenType::Lt),
             '>' => self.dtoken('=', TokenType::Gte, TokenType::Gt),
             // for double tokens which have two different chars in them, but there is no character ! by it
-            // self, so make sure to skip the illegal character if it's by itself
+            // self, so on its own it's an error
             '!' => {
                 if self.peek() == Some('=') {
                     self.advance();
@@ -139,27 +197,43 @@ impl<'a> Lexer<'a> {
                     Some(make_token(TokenType::Neq, "!=".to_string()))
                 } else {
                     self.advance();
-                    self.next()
+                    return Some(Err(self.error("unexpected character '!'")));
                 }
             }
 
+            // skip over it so the next call picks up after the bad char
             _ => {
                 self.advance();
-                self.next()
+                return Some(Err(self.error(format!("unexpected character '{}'", c))));
             }
-        }
+        };
+
+        token.map(Ok)
     }
 
+    // pos is a byte offset into the source, so this is O(1) no matter how far in we are
     fn current(&self) -> Option<char> {
-        self.source.chars().nth(self.pos)
+        self.source[self.pos..].chars().next()
     }
 
     fn advance(&mut self) {
-        self.pos += 1;
+        let Some(c) = self.current() else {
+            return;
+        };
+
+        if c == '\n' {
+            self.line += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
+        }
+
+        self.pos += c.len_utf8();
     }
 
-    fn process_string(&mut self) -> Token {
+    fn process_string(&mut self) -> Result<Token, LexError> {
         let opening = self.current().unwrap();
+        let quote = self.pos;
         self.advance();
 
         let start = self.pos;
@@ -173,18 +247,24 @@ impl<'a> Lexer<'a> {
             self.advance();
         }
 
-        let strval = (start <= self.pos)
-            .then(|| self.source[start..self.pos].to_string())
-            .unwrap_or_default();
-
-        self.current()
-            .filter(|&c| c == opening)
-            .map(|_| self.advance());
+        let strval = self.source[start..self.pos].to_string();
+
+        if self.current() != Some(opening) {
+            // underline the rest of the line the string started on, that's
+            // where the missing quote most likely belongs
+            let (line, col) = self.start;
+            let len = self.source[quote..].lines().next().map_or(1, |l| l.chars().count());
+            return Err(LexError {
+                message: format!("unterminated string, expected a closing {}", opening),
+                span: Span::new(line, col, len),
+            });
+        }
 
-        make_token(TokenType::String, strval)
+        self.advance();
+        Ok(make_token(TokenType::String, strval))
     }
 
-    fn process_number(&mut self) -> Token {
+    fn process_number(&mut self) -> Result<Token, LexError> {
         let start = self.pos;
         let mut float = false; // flag for processing floating point numbers
 
@@ -200,9 +280,9 @@ impl<'a> Lexer<'a> {
         while let Some(c) = self.current() {
             match c {
                 // is a number? advance if so
-                f if f.is_numeric() => self.advance(),
+                f if f.is_ascii_digit() => self.advance(),
                 // if we encounter dot, and after it is a number, then process float
-                '.' if !float && self.peek().map_or(false, |n| n.is_numeric()) => {
+                '.' if !float && self.peek().is_some_and(|n| n.is_ascii_digit()) => {
                     float = true;
                     self.advance();
                 }
@@ -210,7 +290,18 @@ impl<'a> Lexer<'a> {
             }
         }
 
-        make_token(TokenType::Number, self.source[start..self.pos].to_string())
+        // stuff glued onto the end like 12abc or 1.2.3 means it was never a number,
+        // eat the whole thing so the error covers all of it
+        let glued = |c: char| c.is_alphanumeric() || c == '_';
+        let second_dot = float && self.current() == Some('.') && self.peek().is_some_and(|n| n.is_ascii_digit());
+        if second_dot || self.current().is_some_and(glued) {
+            while self.current().is_some_and(|c| glued(c) || c == '.') {
+                self.advance();
+            }
+            return Err(self.error(format!("malformed number '{}'", &self.source[start..self.pos])));
+        }
+
+        Ok(make_token(TokenType::Number, self.source[start..self.pos].to_string()))
     }
 
     fn process_identifier(&mut self) -> Token {
@@ -234,3 +325,17 @@ impl<'a> Lexer<'a> {
         }
     }
 }
+
+// tokens until the end of the source, an error doesn't stop the stream so
+// tools like highlighters can keep going past a bad char
+impl Iterator for Lexer<'_> {
+    type Item = Result<Token, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.lex_token()?;
+        Some(token.map(|mut t| {
+            t.span = self.token_span();
+            t
+        }))
+    }
+}
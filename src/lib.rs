@@ -0,0 +1,71 @@
+// hexi as a library, for running hexi scripts from inside another rust program
+//
+// let mut hexi = hexi::Interpreter::new();
+// let value = hexi.eval_str("val x = 20\nx + 2")?;
+// assert_eq!(value, hexi::Value::Int(22));
+
+pub mod ast;
+pub mod bigint;
+pub mod builder;
+pub mod convert;
+pub mod environment;
+pub mod error;
+pub mod format;
+pub mod interpreter;
+pub mod lexer;
+pub mod limits;
+pub mod native;
+pub mod number;
+pub mod output;
+pub mod parser;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod plugin;
+pub mod serialize;
+pub mod stdlib;
+pub mod symbol;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod thread;
+pub mod visit;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
+
+pub use builder::InterpreterBuilder;
+pub use error::{ErrorKind, HexiError, Span};
+pub use interpreter::{Interpreter, Value};
+pub use limits::Limits;
+pub use native::{Ctx, HostModule, Userdata};
+pub use output::Capture;
+#[cfg(not(target_arch = "wasm32"))]
+pub use thread::{InterpreterThread, SendValue};
+
+use ast::Expr;
+use lexer::{Lexer, Token};
+use parser::Parser;
+
+// source code to the top level expressions in it
+pub fn parse(source: &str) -> Result<Vec<Expr>, HexiError> {
+    Parser::new(Lexer::new(source)).parse()
+}
+
+// source code to the tokens the parser would see, for debugging the lexer
+pub fn tokenize(source: &str) -> Result<Vec<Token>, HexiError> {
+    Ok(Lexer::new(source).collect::<Result<_, _>>()?)
+}
+
+// every syntax error in the source rather than just the first, see
+// Parser::parse_all. empty if it parses
+pub fn syntax_errors(source: &str) -> Vec<HexiError> {
+    Parser::new(Lexer::new(source)).parse_all().err().unwrap_or_default()
+}
+
+// the parsed tree as pretty printed json, for editors and other tools. every
+// node is {"kind": {"<Variant>": ...}, "span": {"line", "col", "len"}}
+pub fn parse_to_json(source: &str) -> Result<String, HexiError> {
+    let exprs = parse(source)?;
+    serde_json::to_string_pretty(&exprs).map_err(|e| HexiError::runtime(format!("could not serialize ast: {}", e)))
+}
+
+// source back out in the canonical layout, see format.rs
+pub fn format_source(source: &str) -> Result<String, HexiError> {
+    Ok(format::format_exprs(&parse(source)?))
+}
@@ -0,0 +1,14 @@
+use std::time::Duration;
+
+// caps on what a script is allowed to do, anything left as None is unlimited.
+// meant for embedders running snippets they don't trust, the cli never sets any
+// steps and the timeout count per eval_str or call, see Interpreter::reset_limits
+//
+// let limits = Limits { max_steps: Some(10_000), ..Limits::default() };
+#[derive(Debug, Clone, Default)]
+pub struct Limits {
+    pub max_steps: Option<u64>,               // every evaluated expression is one step
+    pub max_collection_size: Option<usize>,
+    pub max_string_len: Option<usize>,        // in bytes
+    pub timeout: Option<Duration>,
+}
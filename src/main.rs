@@ -1,50 +1,359 @@
-use crate::interpreter::{Interpreter, Value};
-use crate::lexer::Lexer;
-use crate::parser::Parser;
+use hexi::{ErrorKind, HexiError, Interpreter, Value};
 use std::io::{self, Write};
 use std::env;
 use std::fs;
-
-mod lexer;
-mod parser;
-mod ast;
-mod interpreter;
-mod stdlib;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 const HEX_BUILD: &str = "hexi 0.2.4";
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let (args, force) = split_force(env::args().collect());
 
-    if args.len() > 1 {
-        let filename = &args[1];
-        if !filename.ends_with(".hx") {
-            eprintln!("[hexi::error] file must have .hx extension");
-            std::process::exit(1);
+    match args.get(1).map(String::as_str) {
+        Some("--tokens") => match args.get(2) {
+            Some(filename) => dump_tokens(filename, force),
+            None => {
+                eprintln!("[hexi::error] usage: hexi --tokens <file.hx>");
+                exit(1);
+            }
+        },
+        Some("--ast") => match (args.get(2).map(String::as_str), args.get(3)) {
+            (Some("--json"), Some(filename)) => dump_ast_json(filename, force),
+            (Some(filename), None) if filename != "--json" => dump_ast(filename, force),
+            _ => {
+                eprintln!("[hexi::error] usage: hexi --ast [--json] <file.hx>");
+                exit(1);
+            }
+        },
+        Some("--ast-json") => match args.get(2) {
+            Some(filename) => dump_ast_json(filename, force),
+            None => {
+                eprintln!("[hexi::error] usage: hexi --ast-json <file.hx>");
+                exit(1);
+            }
+        },
+        Some("-e" | "--eval") => match args.get(2) {
+            Some(code) => run_inline(code, args[3..].to_vec()),
+            None => {
+                eprintln!("[hexi::error] usage: hexi -e <code> [args...]");
+                exit(1);
+            }
+        },
+        Some(filename) if Path::new(filename).is_file() => run_file(filename, args[2..].to_vec(), force),
+        Some("test") => run_tests(&args[2..], force),
+        Some("check") => run_check(&args[2..], force),
+        Some("fmt") => match args.get(2).map(String::as_str) {
+            Some("--check") => run_fmt(&args[3..], true, force),
+            _ => run_fmt(&args[2..], false, force),
+        },
+        Some(filename) => run_file(filename, args[2..].to_vec(), force),
+        None => run_repl(),
+    }
+}
+
+// --force runs files without a .hx extension quietly, without it they still
+// run but get a warning first. it can go anywhere in hexi's own arguments, but
+// everything after a script's name (or -e's code) is left for the script
+fn split_force(mut args: Vec<String>) -> (Vec<String>, bool) {
+    let mut force = false;
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "--force" {
+            args.remove(i);
+            force = true;
+            continue;
         }
 
-        run_file(filename);
-    } else {
-        run_repl();
+        let command = args[1].as_str();
+        let script = i == 1 && !command.starts_with('-') && !matches!(command, "test" | "check" | "fmt");
+        if script || Path::new(command).is_file() || matches!(command, "-e" | "--eval") {
+            break;
+        }
+        i += 1;
     }
+    (args, force)
 }
 
-fn run_file(filename: &str) {
-    let contents = match fs::read_to_string(filename) {
+// any readable file is run, scripts are often kept as .hexi, as extensionless
+// executables with a #! line or as temp files
+fn read_source(filename: &str, force: bool) -> String {
+    let content = match fs::read_to_string(filename) {
         Ok(content) => content,
         Err(e) => {
             eprintln!("[hexi::error] reading file '{}': {}", filename, e);
-            std::process::exit(1);
+            exit(1);
         }
     };
 
+    if !force && !filename.ends_with(".hx") {
+        eprintln!("[hexi::warning] '{}' doesn't have a .hx extension, running it anyway (--force to hide this)", filename);
+    }
+    content
+}
+
+fn run_file(filename: &str, args: Vec<String>, force: bool) {
+    let contents = read_source(filename, force);
+
+    let mut interpreter = Interpreter::new();
+    interpreter.set_script(filename, args);
+    catch_interrupts(&interpreter);
+    // so a script run from the shell (or with #!) can tell it that it failed
+    if let Err(kind) = execute(&mut interpreter, &contents, filename) {
+        exit(if kind == ErrorKind::Interrupted { 130 } else { 1 });
+    }
+}
+
+// hexi -e 'string::upper("hi")' runs the code and prints what the last expression
+// came to (nothing if that's nil), for one-liners in the shell and makefiles.
+// an error exits with 1
+fn run_inline(code: &str, args: Vec<String>) {
     let mut interpreter = Interpreter::new();
-    execute(&mut interpreter, &contents);
+    interpreter.set_script("<eval>", args);
+    catch_interrupts(&interpreter);
+
+    RUNNING.store(true, Ordering::Relaxed);
+    let result = hexi::parse(code).and_then(|exprs| {
+        let mut last = Value::Nil;
+        for expr in exprs {
+            last = interpreter.evaluate(&expr)?;
+        }
+        Ok(last)
+    });
+    RUNNING.store(false, Ordering::Relaxed);
+    match result {
+        Ok(Value::Nil) => {},
+        Ok(value) => println!("{}", value),
+        Err(e) => {
+            eprintln!("{}", e.report(code, "<eval>"));
+            exit(1);
+        }
+    }
+}
+
+// hexi test [dirs or files...] runs every *_test.hx under them (the current
+// directory if none are given) and exits with 1 if anything failed
+fn run_tests(paths: &[String], force: bool) {
+    let files = collect_files(paths, "_test.hx");
+
+    let (mut passed, mut failed) = (0, 0);
+    for file in &files {
+        let filename = file.to_string_lossy();
+        println!("{}", filename);
+        let contents = read_source(&filename, force);
+
+        let mut interpreter = Interpreter::new();
+        interpreter.set_script(filename.as_ref(), Vec::new());
+
+        // an error outside of any case still fails the file
+        if let Err(e) = run_quietly(&mut interpreter, &contents) {
+            eprintln!("{}", e.report(&contents, &filename));
+            failed += 1;
+        }
+
+        let results = interpreter.test_results();
+        passed += results.passed;
+        failed += results.failed;
+    }
+
+    println!("\n{} passed, {} failed", passed, failed);
+    if failed > 0 {
+        exit(1);
+    }
+}
+
+// hexi check [dirs or files...] parses every *.hx under them (the current
+// directory if none are given) without running anything, reports every
+// syntax error and exits with 1 if there were any. for editor save hooks and
+// pre-commit checks
+fn run_check(paths: &[String], force: bool) {
+    let files = collect_files(paths, ".hx");
+
+    let (mut failed, mut errors) = (0, 0);
+    for file in &files {
+        let filename = file.to_string_lossy();
+        let contents = read_source(&filename, force);
+        let found = hexi::syntax_errors(&contents);
+        for e in &found {
+            eprintln!("{}", e.report(&contents, &filename));
+        }
+        if !found.is_empty() {
+            failed += 1;
+            errors += found.len();
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("\n{} syntax errors in {} of {} files", errors, failed, files.len());
+        exit(1);
+    }
+    println!("{} files ok", files.len());
+}
+
+// hexi fmt [--check] [dirs or files...] rewrites every *.hx under them (the
+// current directory if none are given) in the canonical layout from format.rs.
+// with --check nothing is written, the files that would change are listed and
+// it exits with 1 if there were any
+fn run_fmt(paths: &[String], check: bool, force: bool) {
+    let files = collect_files(paths, ".hx");
+
+    let (mut changed, mut failed) = (0, 0);
+    for file in &files {
+        let filename = file.to_string_lossy();
+        let contents = read_source(&filename, force);
+
+        // the lexer skips a #! line, so it has to be put back by hand
+        let (shebang, code) = match contents.strip_prefix("#!") {
+            Some(_) => contents.split_at(contents.find('\n').map_or(contents.len(), |i| i + 1)),
+            None => ("", contents.as_str()),
+        };
+        let formatted = match hexi::format_source(code) {
+            Ok(formatted) => format!("{}{}", shebang, formatted),
+            Err(e) => {
+                eprintln!("{}", e.report(&contents, &filename));
+                failed += 1;
+                continue;
+            }
+        };
+        if formatted == contents {
+            continue;
+        }
+
+        changed += 1;
+        println!("{}", filename);
+        if !check && let Err(e) = fs::write(file, formatted) {
+            eprintln!("[hexi::error] writing file '{}': {}", filename, e);
+            failed += 1;
+        }
+    }
+
+    if failed > 0 || (check && changed > 0) {
+        exit(1);
+    }
+}
+
+// files given as they are, directories searched for names ending in suffix
+fn collect_files(paths: &[String], suffix: &str) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let roots = if paths.is_empty() { vec![".".to_string()] } else { paths.to_vec() };
+    for root in &roots {
+        let path = Path::new(root);
+        if path.is_dir() {
+            if let Err(e) = find_files(path, suffix, &mut files) {
+                eprintln!("[hexi::error] reading directory '{}': {}", root, e);
+                exit(1);
+            }
+        } else {
+            files.push(path.to_path_buf());
+        }
+    }
+    files
+}
+
+// sorted so runs are always in the same order, hidden directories (.git) and
+// target are skipped
+fn find_files(dir: &Path, suffix: &str, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            if !name.starts_with('.') && name != "target" {
+                find_files(&path, suffix, files)?;
+            }
+        } else if name.ends_with(suffix) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// like execute without echoing results. no ctrl+c handler here, an interrupt
+// just ends the whole run
+fn run_quietly(interpreter: &mut Interpreter, code: &str) -> Result<(), HexiError> {
+    for expr in hexi::parse(code)? {
+        interpreter.evaluate(&expr)?;
+    }
+    Ok(())
+}
+
+// print the tokens one to a line, line:col then the type and the text
+fn dump_tokens(filename: &str, force: bool) {
+    let contents = read_source(filename, force);
+
+    match hexi::tokenize(&contents) {
+        Ok(tokens) => {
+            for token in tokens {
+                println!("{}:{}\t{:?}\t{:?}", token.span.line, token.span.col, token.token_type, token.lexeme);
+            }
+        },
+        Err(e) => {
+            eprintln!("{}", e.report(&contents, filename));
+            exit(1);
+        }
+    }
+}
+
+// print the parsed tree rust's debug way, easier on the eyes than the json
+fn dump_ast(filename: &str, force: bool) {
+    let contents = read_source(filename, force);
+
+    match hexi::parse(&contents) {
+        Ok(exprs) => println!("{:#?}", exprs),
+        Err(e) => {
+            eprintln!("{}", e.report(&contents, filename));
+            exit(1);
+        }
+    }
+}
+
+// print the parsed tree as json without running anything
+fn dump_ast_json(filename: &str, force: bool) {
+    let contents = read_source(filename, force);
+
+    match hexi::parse_to_json(&contents) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("{}", e.report(&contents, filename));
+            exit(1);
+        }
+    }
+}
+
+// like std::process::exit, but temp files from fs::temp_file and the like
+// are cleaned up first, there's no dropping the interpreter on the way out
+fn exit(code: i32) -> ! {
+    hexi::stdlib::fs::remove_temp_paths();
+    std::process::exit(code)
+}
+
+// while code is running ctrl+c stops it and hands control back (to the repl),
+// anywhere else it quits like it normally would
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn catch_interrupts(interpreter: &Interpreter) {
+    let interrupt = interpreter.interrupt_handle();
+    let result = ctrlc::set_handler(move || {
+        if RUNNING.load(Ordering::Relaxed) {
+            interrupt.store(true, Ordering::Relaxed);
+        } else {
+            println!();
+            exit(130);
+        }
+    });
+
+    if let Err(e) = result {
+        eprintln!("[hexi::error] could not install ctrl+c handler: {}", e);
+    }
 }
 
 fn run_repl() {
-    println!("{}", format!("{}. enter 'exit' or 'quit' to leave.", HEX_BUILD));
+    println!("{}. enter 'exit' or 'quit' to leave.", HEX_BUILD);
     let mut interpreter = Interpreter::new();
+    catch_interrupts(&interpreter);
 
     loop {
         print!(">> ");
@@ -70,26 +379,30 @@ fn run_repl() {
             continue;
         }
         
-        execute(&mut interpreter, input);
+        // the repl carries on after an error, it's been printed already
+        let _ = execute(&mut interpreter, input, "<repl>");
     }
 }
 
-fn execute(interpreter: &mut Interpreter, code: &str) {
-    let lexer = Lexer::new(code);
-    let mut parser = Parser::new(lexer);
-
-    let exprs = match parser.parse() {
+// the kind of error it stopped on, if it did, the error itself is already printed
+fn execute(interpreter: &mut Interpreter, code: &str, filename: &str) -> Result<(), ErrorKind> {
+    let exprs = match hexi::parse(code) {
         Ok(e) => e,
         Err(e) => {
-            println!("parser error: {}", e);
-            return;
+            eprintln!("{}", e.report(code, filename));
+            return Err(e.kind);
         }
     };
 
+    // a ctrl+c that landed right as the last run finished shouldn't cancel this one
+    interpreter.interrupt_handle().store(false, Ordering::Relaxed);
+    RUNNING.store(true, Ordering::Relaxed);
+    let mut result = Ok(());
     for expr in exprs {
         match interpreter.evaluate(&expr) {
             Err(e) => {
-                println!("runtime error: {}", e);
+                eprintln!("{}", e.report(code, filename));
+                result = Err(e.kind);
                 break;
             },
             Ok(result) => {
@@ -99,4 +412,6 @@ fn execute(interpreter: &mut Interpreter, code: &str) {
             },
         }
     }
+    RUNNING.store(false, Ordering::Relaxed);
+    result
 }
\ No newline at end of file
@@ -0,0 +1,157 @@
+use crate::error::HexiError;
+use crate::interpreter::{Interpreter, Value};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::rc::Rc;
+
+// a function hexi code can call that's implemented in rust. unlike the stdlib's
+// plain fn pointers these can be closures, so a host can hand scripts access
+// to its own state
+pub type HostFn = Rc<dyn Fn(&mut Ctx, &[Value]) -> Result<Value, HexiError>>;
+
+// what a host function gets handed besides its arguments, a way back into the
+// interpreter that called it, for callbacks like http::serve(handler)
+pub struct Ctx<'a> {
+    interpreter: &'a mut Interpreter,
+}
+
+impl<'a> Ctx<'a> {
+    pub(crate) fn new(interpreter: &'a mut Interpreter) -> Self {
+        Ctx { interpreter }
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.interpreter.get_global(name)
+    }
+
+    pub fn set_global(&mut self, name: &str, value: Value) {
+        self.interpreter.set_global(name, value);
+    }
+
+    // call another native, `module::func` or a plain name
+    pub fn call(&mut self, name: &str, args: &[Value]) -> Result<Value, HexiError> {
+        self.interpreter.call(name, args)
+    }
+
+    // run some hexi source in the scope the native was called from, getting the last value back
+    pub fn eval_str(&mut self, source: &str) -> Result<Value, HexiError> {
+        self.interpreter.eval_str(source)
+    }
+
+    pub fn output(&mut self) -> &mut dyn Write {
+        self.interpreter.output()
+    }
+
+    pub fn error_output(&mut self) -> &mut dyn Write {
+        self.interpreter.error_output()
+    }
+
+    pub fn input(&mut self) -> &mut dyn BufRead {
+        self.interpreter.input()
+    }
+
+    pub fn new_collection(&self) -> Value {
+        Value::new_collection()
+    }
+
+    pub fn new_array(&self, values: Vec<Value>) -> Value {
+        Value::from(values)
+    }
+
+    // for anything the helpers above don't cover
+    pub fn interpreter(&mut self) -> &mut Interpreter {
+        self.interpreter
+    }
+}
+
+// a group of host functions called as name::func(...) from hexi
+//
+// let app = HostModule::new("app")
+//     .func("version", |_, _| Ok("1.0".into()))
+//     .constant("MAX_USERS", 50);
+// interpreter.register_module(app);
+pub struct HostModule {
+    pub(crate) name: String,
+    pub(crate) funcs: Vec<(String, HostFn)>,
+    pub(crate) method_types: Vec<&'static str>,
+    pub(crate) consts: Vec<(String, Value)>,
+}
+
+impl HostModule {
+    pub fn new(name: &str) -> Self {
+        HostModule { name: name.to_string(), funcs: Vec::new(), method_types: Vec::new(), consts: Vec::new() }
+    }
+
+    // a value scripts read as name::NAME
+    pub fn constant(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.consts.push((name.to_string(), value.into()));
+        self
+    }
+
+    // makes every func a method on values of `type_name` as well, they get the
+    // value they're called on as the first argument
+    pub fn methods_of(mut self, type_name: &'static str) -> Self {
+        self.method_types.push(type_name);
+        self
+    }
+
+    pub fn func<F>(mut self, name: &str, f: F) -> Self
+    where
+        F: Fn(&mut Ctx, &[Value]) -> Result<Value, HexiError> + 'static,
+    {
+        self.funcs.push((name.to_string(), Rc::new(f)));
+        self
+    }
+}
+
+// a method on a userdata value, gets the userdata it was called on
+pub type UserMethod = Rc<dyn Fn(&mut Ctx, &Userdata, &[Value]) -> Result<Value, HexiError>>;
+
+pub type MethodTable = HashMap<&'static str, UserMethod>;
+
+// an opaque rust object handed to scripts, hexi can pass it around and call
+// its methods but never look inside. anything that needs to change after
+// creation should sit behind a RefCell
+//
+// let file = Userdata::new("File", RefCell::new(handle)).with_methods(file_methods.clone());
+// Value::userdata(file)
+pub struct Userdata {
+    type_name: &'static str,
+    data: Rc<dyn Any>,
+    methods: Option<Rc<MethodTable>>,   // shared by every value of the same type
+}
+
+impl Userdata {
+    pub fn new<T: Any>(type_name: &'static str, data: T) -> Self {
+        Userdata { type_name, data: Rc::new(data), methods: None }
+    }
+
+    pub fn with_methods(mut self, methods: Rc<MethodTable>) -> Self {
+        self.methods = Some(methods);
+        self
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn downcast<T: Any>(&self) -> Option<&T> {
+        self.data.downcast_ref()
+    }
+
+    pub fn is<T: Any>(&self) -> bool {
+        self.data.is::<T>()
+    }
+
+    pub fn method(&self, name: &str) -> Option<UserMethod> {
+        self.methods.as_ref()?.get(name).cloned()
+    }
+}
+
+impl fmt::Debug for Userdata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Userdata({})", self.type_name)
+    }
+}
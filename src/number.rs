@@ -0,0 +1,62 @@
+use std::cell::Cell;
+
+// the one place floats get turned into text for printing, so io::print,
+// collections and string::fmt all agree. whole floats print like ints (3.0 is
+// "3"), huge ones switch to exponents, and everything else uses the shortest
+// form that reads back as the same float unless a precision is set
+
+// an f64 never has more decimal places than this, the smallest one is 2^-1074
+pub(crate) const MAX_PRECISION: usize = 1074;
+
+// display has no interpreter to ask, so whichever one is running puts its
+// precision here for as long as it runs (see Interpreter::set_float_precision)
+thread_local! {
+    static PRECISION: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+pub(crate) fn with_precision<R>(digits: Option<usize>, f: impl FnOnce() -> R) -> R {
+    // puts the previous one back even if f panics
+    struct Restore(Option<usize>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            PRECISION.set(self.0);
+        }
+    }
+
+    let _restore = Restore(PRECISION.replace(digits));
+    f()
+}
+
+pub fn format_float(n: f64) -> String {
+    if !n.is_finite() {
+        return n.to_string();
+    }
+
+    let precision = PRECISION.get();
+    let s = if n.abs() >= 1e21 {
+        match precision {
+            Some(d) => {
+                let s = format!("{:.*e}", d, n);
+                let (mantissa, exp) = s.split_once('e').unwrap();
+                format!("{}e{}", trim_zeros(mantissa), exp)
+            },
+            None => format!("{:e}", n),
+        }
+    } else {
+        match precision {
+            Some(d) => trim_zeros(&format!("{:.*}", d, n)).to_string(),
+            None => n.to_string(),
+        }
+    };
+
+    // -0.0, or something tiny and negative rounded down to nothing
+    if s == "-0" { "0".to_string() } else { s }
+}
+
+fn trim_zeros(s: &str) -> &str {
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        s
+    }
+}
@@ -0,0 +1,43 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+// an output sink that keeps everything written to it, hand a clone to the
+// interpreter and read what the script printed back out of the other one
+//
+// let out = Capture::new();
+// interpreter.set_output(out.clone());
+// interpreter.eval_str("io::println(1)")?;
+// assert_eq!(out.take(), "1\n");
+#[derive(Debug, Clone, Default)]
+pub struct Capture {
+    buf: Rc<RefCell<Vec<u8>>>,
+}
+
+impl Capture {
+    pub fn new() -> Self {
+        Capture::default()
+    }
+
+    // everything written so far, invalid utf-8 gets replaced
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buf.borrow()).into_owned()
+    }
+
+    // like contents, but empties the buffer too
+    pub fn take(&self) -> String {
+        let bytes = std::mem::take(&mut *self.buf.borrow_mut());
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+impl Write for Capture {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buf.borrow_mut().extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
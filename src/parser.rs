@@ -1,19 +1,66 @@
-use crate::ast::{Assignment, BinaryOp, Block, Call, Expr, If, IndexAccess, MethodCall, UnaryOp, VarDecl, Include, FieldAccess, Collection, CEntry};
+use crate::ast::{Assignment, BinaryOp, Block, Call, Expr, ExprKind, If, IndexAccess, MethodCall, UnaryOp, VarDecl, Include, IncludeItem, FieldAccess, Collection, CEntry};
+use crate::bigint::BigInt;
+use crate::error::{HexiError, Span};
 use crate::lexer::{Lexer, Token, TokenType};
+use crate::symbol::Symbol;
+
+// how many collections/blocks/parens/etc can be nested inside each other,
+// every level is a few native stack frames so this keeps us from overflowing.
+// every operator in a chain like 1 + 1 + 1 and every link in a.b[0].c counts
+// as well, each one makes the tree a level deeper and dropping, cloning and
+// printing it all recurse down that
+const MAX_NESTING: usize = 256;
 
 pub struct Parser<'a> {
     lexer: Lexer<'a>,
-    current: Option<Token>
+    current: Option<Token>,
+    span: Span,    // where the current token is, or the end of the source at eof
+    depth: usize,
+    lex_error: Option<HexiError>,   // a bad token stops the stream, this is why
+    open: usize,        // brackets and braces passed that haven't been closed yet
+    last_line: usize,   // the line the token before the current one was on
 }
 
 impl<'a> Parser<'a> {
-    pub fn new(mut lexer: Lexer<'a>) -> Self {
-        let current = lexer.next();
-        Parser { lexer, current }
+    pub fn new(lexer: Lexer<'a>) -> Self {
+        let mut parser = Parser { lexer, current: None, span: Span::default(), depth: 0, lex_error: None, open: 0, last_line: 0 };
+        parser.advance();
+        parser
     }
 
+    // a bad token ends the token stream like eof would, whatever error that
+    // causes gets swapped out for the lexer's one
     fn advance(&mut self) {
-        self.current = self.lexer.next();
+        if self.lex_error.is_some() {
+            return;
+        }
+
+        if let Some(t) = &self.current {
+            match t.token_type {
+                TokenType::LParen | TokenType::LBracket | TokenType::LBrace => self.open += 1,
+                TokenType::RParen | TokenType::RBracket | TokenType::RBrace => self.open = self.open.saturating_sub(1),
+                _ => {},
+            }
+            self.last_line = t.span.line;
+        }
+
+        self.current = match self.lexer.next() {
+            Some(Ok(t)) => Some(t),
+            Some(Err(e)) => {
+                self.lex_error.get_or_insert(e.into());
+                None
+            },
+            None => None,
+        };
+        self.span = self.current.as_ref().map_or_else(|| self.lexer.token_span(), |t| t.span);
+    }
+
+    // parse error pointing at the current token
+    fn error(&self, message: impl Into<String>) -> HexiError {
+        match &self.lex_error {
+            Some(e) => e.clone(),
+            None => HexiError::parse(message, self.span),
+        }
     }
 
     fn check(&self, target_type: &TokenType) -> bool {
@@ -24,13 +71,13 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn consume(&mut self, expect: TokenType) -> Result<Token, String> {
+    fn consume(&mut self, expect: TokenType) -> Result<Token, HexiError> {
         if self.check(&expect) {
             let t = self.current.clone();
             self.advance();
-            t.ok_or_else(|| "unexpected eof".to_string())
+            t.ok_or_else(|| self.error("unexpected eof"))
         } else {
-            Err(format!("expected {:?} but found {:?}", expect, self.current.as_ref().map(|t| &t.token_type)))
+            Err(self.error(format!("expected {:?} but found {:?}", expect, self.current.as_ref().map(|t| &t.token_type))))
         }
     }
 
@@ -61,7 +108,7 @@ impl<'a> Parser<'a> {
         self.current.as_ref().map(|t| &t.lexeme)
     }
 
-    pub fn parse(&mut self) -> Result<Vec<Expr>, String> {
+    pub fn parse(&mut self) -> Result<Vec<Expr>, HexiError> {
         let mut exprs = Vec::new();
 
         while !self.check(&TokenType::Eof) {
@@ -73,10 +120,79 @@ impl<'a> Parser<'a> {
             }
         }
 
-        Ok(exprs)
+        // the source might have only looked finished because of a bad token
+        match self.lex_error.take() {
+            Some(e) => Err(e),
+            None => Ok(exprs),
+        }
+    }
+
+    // like parse, but after an error it skips ahead to where the next statement
+    // most likely starts and carries on, so every mistake in the source turns
+    // up in one go. an error can throw off what comes after it, so the
+    // expressions are only good for telling whether there were any errors
+    pub fn parse_all(&mut self) -> Result<Vec<Expr>, Vec<HexiError>> {
+        let mut exprs = Vec::new();
+        let mut errors = Vec::new();
+
+        loop {
+            // a bad token ends the stream, the lexer itself has already moved past it
+            if let Some(e) = self.lex_error.take() {
+                errors.push(e);
+                self.advance();
+                continue;
+            }
+            if self.check(&TokenType::Eof) {
+                break;
+            }
+
+            let (start, open) = (self.span, self.open);
+            match self.parse_expr() {
+                Ok(e) => {
+                    exprs.push(e);
+                    if self.check(&TokenType::Semi) {
+                        self.advance();
+                    }
+                },
+                Err(e) => {
+                    if self.lex_error.take().is_some() {
+                        self.advance();
+                    }
+                    errors.push(e);
+                    self.synchronize(start, open);
+                },
+            }
+        }
+
+        if errors.is_empty() { Ok(exprs) } else { Err(errors) }
+    }
+
+    // skips to the next statement after an error: past the brackets the
+    // broken one opened, then to a `;`, a `val` or `include`, or a new line
+    fn synchronize(&mut self, start: Span, open: usize) {
+        self.depth = 0;
+        // the statement didn't get anywhere, so this token is the problem
+        if self.span == start {
+            self.advance();
+        }
+
+        while let Some(t) = &self.current {
+            if self.open <= open {
+                match t.token_type {
+                    TokenType::Semi => {
+                        self.advance();
+                        break;
+                    },
+                    TokenType::Val | TokenType::Include => break,
+                    _ if t.span.line > self.last_line => break,
+                    _ => {},
+                }
+            }
+            self.advance();
+        }
     }
 
-    pub fn parse_expr(&mut self) -> Result<Expr, String> {
+    pub fn parse_expr(&mut self) -> Result<Expr, HexiError> {
         // match &self.current {
         //     Some(t) => match t.token_type {
         //         TokenType::Val => self.parse_var_decl(),
@@ -91,9 +207,14 @@ impl<'a> Parser<'a> {
         self.parse_bin_expr(0)
     }
 
-    fn parse_bin_expr(&mut self, precedence: u8) -> Result<Expr, String> {
-        let mut left = self.parse_postfix()?;
+    fn parse_bin_expr(&mut self, precedence: u8) -> Result<Expr, HexiError> {
+        let left = self.parse_postfix()?;
+        self.parse_bin_rest(left, precedence)
+    }
 
+    // the operator loop of parse_bin_expr, for when the left side's already parsed
+    fn parse_bin_rest(&mut self, mut left: Expr, precedence: u8) -> Result<Expr, HexiError> {
+        let mut links = 0;
         while let Some(ref t) = self.current {
             if !self.is_binop(t.clone().token_type) {
                 break;
@@ -105,17 +226,39 @@ impl<'a> Parser<'a> {
             }
 
             let op = t.token_type.clone();
+            let span = self.span;   // point at the operator
+            self.deepen(1, "too many operators chained together, split the expression up")?;
+            links += 1;
             self.advance();
 
             let right = self.parse_bin_expr(prec + 1)?;
-            left = Expr::BinaryOp(BinaryOp::new(left, right, op));
+            left = Expr::new(ExprKind::BinaryOp(BinaryOp::new(left, right, op)), span);
         }
 
+        self.depth -= links;
         Ok(left)
     }
 
+    // `room` is how many more levels whatever comes next needs, a chain link
+    // still has its operand to go so it can't take the very last one
+    fn deepen(&mut self, room: usize, message: &str) -> Result<(), HexiError> {
+        if self.depth + room >= MAX_NESTING {
+            return Err(self.error(message));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
     // move to parse_prim, parsing exprs "atoms"
-    fn parse_prim(&mut self) -> Result<Expr, String> {
+    // every nested expression comes back through here, so this is where we count depth
+    fn parse_prim(&mut self) -> Result<Expr, HexiError> {
+        self.deepen(0, "expression nested too deeply")?;
+        let result = self.parse_atom();
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_atom(&mut self) -> Result<Expr, HexiError> {
         match &self.current {
             Some(t) => match t.token_type {
                 TokenType::Include => self.parse_include(),
@@ -124,80 +267,128 @@ impl<'a> Parser<'a> {
                 TokenType::Ident => self.parse_identifier(),
                 TokenType::String => self.parse_string(),
                 TokenType::Number => self.parse_number(),
+                TokenType::True | TokenType::False | TokenType::Nil => self.parse_literal(),
                 TokenType::LParen => self.parse_grouped(),
                 TokenType::LBracket => self.parse_collection(),
-                TokenType::LBrace => Ok(Expr::Block(self.parse_block()?)),
+                TokenType::LBrace => {
+                    let span = self.span;
+                    Ok(Expr::new(ExprKind::Block(self.parse_block()?), span))
+                },
                 TokenType::If => self.parse_if(),
-                _ => Err(format!("unexpected token {:?}", t))
+                _ => Err(self.error(format!("unexpected '{}'", t.lexeme)))
             }
-            None => Err("unexpected eof".to_string())
+            None => Err(self.error("unexpected eof"))
         }
     }
 
-    fn parse_include(&mut self) -> Result<Expr, String> {
+    fn parse_include(&mut self) -> Result<Expr, HexiError> {
         self.consume(TokenType::Include)?;  // consume 'include' keyword
 
-        // expect identifier
-        let module_name = if self.check(&TokenType::Ident) {
-            let name = self.current_lex().unwrap().clone();
+        let span = self.span;
+        if !self.check(&TokenType::LBracket) {
+            let item = self.parse_include_item()?;
+            return Ok(Expr::new(ExprKind::Include(Include { modules: vec![item], grouped: false }), span));
+        }
+
+        // include [json, time as t]
+        self.consume(TokenType::LBracket)?;
+        let mut modules = Vec::new();
+        while !self.check(&TokenType::RBracket) {
+            modules.push(self.parse_include_item()?);
+            if !self.check(&TokenType::Comma) {
+                break;
+            }
             self.advance();
-            name
+        }
+        self.consume(TokenType::RBracket)?;
+        if modules.is_empty() {
+            return Err(HexiError::parse("expected at least one module in 'include [...]'", span));
+        }
+
+        Ok(Expr::new(ExprKind::Include(Include { modules, grouped: true }), span))
+    }
+
+    // a module name and maybe `as alias`. `as` isn't a keyword, so it only
+    // counts on the same line, a variable called `as` on the next one is fine
+    fn parse_include_item(&mut self) -> Result<IncludeItem, HexiError> {
+        let name = self.current.clone().filter(|t| t.token_type == TokenType::Ident)
+            .ok_or_else(|| self.error("expected a module name after 'include'"))?;
+        self.advance();
+
+        let has_alias = self.current.as_ref().is_some_and(|t| t.token_type == TokenType::Ident && t.lexeme == "as" && t.span.line == name.span.line);
+        let alias = if has_alias {
+            self.advance();
+            let alias = self.current.clone().filter(|t| t.token_type == TokenType::Ident)
+                .ok_or_else(|| self.error(format!("expected a name after '{} as'", name.lexeme)))?;
+            self.advance();
+            Some(alias.lexeme)
         } else {
-            return Err("expected identifier after 'include'".to_string());
+            None
         };
 
-        Ok(Expr::Include(Include::new(module_name)))
+        Ok(IncludeItem { module: name.lexeme, alias })
     }
 
     // postfix => some_array[0] or some_array.empty()
-    fn parse_postfix(&mut self) -> Result<Expr, String> {
-        let mut e = self.parse_prim()?;
+    fn parse_postfix(&mut self) -> Result<Expr, HexiError> {
+        let e = self.parse_prim()?;
+        self.parse_postfix_rest(e)
+    }
 
-        loop {
-            match &self.current {
-                Some(t) => match t.token_type {
-                    TokenType::LBracket => {
-                        // some_array[idx]
-                        self.consume(TokenType::LBracket)?; // get past [
-                        let idx = self.parse_expr()?;
-                        self.consume(TokenType::RBracket)?; // get pas ]
-                        // at this post we've parsed [idx]
-                        // so set the current expr to this index access
-                        e = Expr::IndexAccess(IndexAccess::new(e, idx));
-                    },
-                    TokenType::Dot => {
-                        // some_obj.func(args...)
-                        self.consume(TokenType::Dot)?;  // get past .
-                        // now get method from obj
-                        let meth = self.consume(TokenType::Ident)?.lexeme;
-                        if self.check(&TokenType::LParen) { // we calling it?
-                            self.consume(TokenType::LParen)?;   // get past (
-                            // if we're not an empty () call parse_args, if we are empty, just create an empty vec
-                            let args = if self.check(&TokenType::RParen) { Vec::new() } else { self.parse_args()? };
-                            self.consume(TokenType::RParen)?;   // get past )
-                            e = Expr::MethodCall(MethodCall::new(e, meth, args));
-                        } else {
-                            // for shit like, person.name
-                            e = Expr::FieldAccess(FieldAccess::new(e, meth))
-                        }
-                    },
-                    _ => break,
-                },
-                None => break
+    // any number of [idx], .field and .method(args) after `e`, so calls,
+    // indexing and field access chain as far as they like: f(x).get("id")[0].name
+    fn parse_postfix_rest(&mut self, mut e: Expr) -> Result<Expr, HexiError> {
+        let mut links = 0;
+        while let Some(t) = &self.current {
+            let token_type = t.token_type.clone();
+            if !matches!(token_type, TokenType::LBracket | TokenType::Dot) {
+                break;
+            }
+            self.deepen(1, "too many calls, fields or indexes chained together, split the chain up")?;
+            links += 1;
+
+            if token_type == TokenType::LBracket {
+                // some_array[idx]
+                let span = self.span;
+                self.consume(TokenType::LBracket)?; // get past [
+                let idx = self.parse_expr()?;
+                self.consume(TokenType::RBracket)?; // get pas ]
+                // at this post we've parsed [idx]
+                // so set the current expr to this index access
+                e = Expr::new(ExprKind::IndexAccess(IndexAccess::new(e, idx)), span);
+            } else {
+                // some_obj.func(args...)
+                self.consume(TokenType::Dot)?;  // get past .
+                // now get method from obj
+                let meth_tok = self.consume(TokenType::Ident)?;
+                let span = meth_tok.span;
+                if self.check(&TokenType::LParen) { // we calling it?
+                    let meth = Symbol::intern(&meth_tok.lexeme);
+                    self.consume(TokenType::LParen)?;   // get past (
+                    // if we're not an empty () call parse_args, if we are empty, just create an empty vec
+                    let args = if self.check(&TokenType::RParen) { Vec::new() } else { self.parse_args()? };
+                    self.consume(TokenType::RParen)?;   // get past )
+                    e = Expr::new(ExprKind::MethodCall(MethodCall::new(e, meth, args)), span);
+                } else {
+                    // for shit like, person.name
+                    e = Expr::new(ExprKind::FieldAccess(FieldAccess::new(e, meth_tok.lexeme.into())), span)
+                }
             }
         }
 
+        self.depth -= links;
         Ok(e)
     }
 
-    fn parse_unary(&mut self) -> Result<Expr, String> {
+    fn parse_unary(&mut self) -> Result<Expr, HexiError> {
         let op = self.current.clone().unwrap().token_type.clone();
+        let span = self.span;
         self.advance();
         let operand = self.parse_postfix()?;
-        Ok(Expr::UnaryOp(UnaryOp::new(operand, op)))
+        Ok(Expr::new(ExprKind::UnaryOp(UnaryOp::new(operand, op)), span))
     }
 
-    fn parse_grouped(&mut self) -> Result<Expr, String> {
+    fn parse_grouped(&mut self) -> Result<Expr, HexiError> {
         self.consume(TokenType::LParen)?;
         let expr = self.parse_bin_expr(0)?;
         self.consume(TokenType::RParen)?;
@@ -249,10 +440,11 @@ impl<'a> Parser<'a> {
     //     Ok(Expr::Array(Array::new(values)))
     // }
 
-    fn parse_collection(&mut self) -> Result<Expr, String> {
+    fn parse_collection(&mut self) -> Result<Expr, HexiError> {
         // since a collection is an array and object
         // in one, we need to keep this in mind
         // so we need to conditionally parse this structure
+        let span = self.span;
         self.consume(TokenType::LBracket)?;
 
         // allow empty
@@ -261,18 +453,18 @@ impl<'a> Parser<'a> {
         // initially set to empty
         if self.check(&TokenType::RBracket) {
             self.consume(TokenType::RBracket)?;
-            return Ok(Expr::Collection(Collection::empty()));   // use the handy empty constructor
+            return Ok(Expr::new(ExprKind::Collection(Collection::empty()), span));   // use the handy empty constructor
         }
 
         let mut entries = vec![];
-        let mut idx = 0;    // index in the collection, default to 0
 
         loop {
             // we need to specially handle the case where
             // an identifier is followed by an equals
             // carefully do this so we dont invoke a var decl
             if self.check(&TokenType::Ident) {
-                let key = self.current_lex().unwrap().clone();
+                let key = Symbol::intern(self.current_lex().unwrap());
+                let key_span = self.span;
                 self.advance(); // eat identifier
 
                 if self.check(&TokenType::Equals) {
@@ -280,13 +472,14 @@ impl<'a> Parser<'a> {
                     // e.g [a = 1]
                     self.consume(TokenType::Equals)?;
                     let val = self.parse_expr()?;
-                    entries.push(CEntry::Keyed(key, val));
+                    entries.push(CEntry::Keyed(key.as_str().into(), val));
                 } else {
-                    // this is an ordinary identifier reference which needs to be evaluated
-                    // so just go back and parse it as a normal expr
-                    let first = Expr::Identifier(key);  // since we've lost the previous identifier by eating it, simply create a new one
-                    entries.push(CEntry::Indexed(first));
-                    idx += 1;
+                    // not a key after all but the start of an ordinary expression,
+                    // like x, x.y, f(1) or x + 1. the identifier's already been eaten
+                    // so pick the expression up from right after it
+                    let first = self.parse_identifier_rest(key, key_span)?;
+                    let first = self.parse_postfix_rest(first)?;
+                    entries.push(CEntry::Indexed(self.parse_bin_rest(first, 0)?));
                 }
             } else {
                 // parse the first entry in the collection
@@ -304,7 +497,7 @@ impl<'a> Parser<'a> {
                     //     NumKeyed(f64, Expr),                // [1 = "first", 2 = "second"] - num -> value
                     // }
                     // we need to differentriate the key
-                    match first {
+                    match first.kind {
                         // map, key -> value
                         // Expr::Identifier(k) => {
                         //     println!("{}", k);
@@ -312,17 +505,19 @@ impl<'a> Parser<'a> {
                         // },
                         // lets also allow for string literals to be keys
                         // map, string -> value
-                        Expr::String(s) => entries.push(CEntry::Keyed(s, value)),
+                        ExprKind::String(s) => entries.push(CEntry::Keyed(s, value)),
                         // indexed map (i guess lol?), num -> value
-                        Expr::Number(n) => entries.push(CEntry::NumKeyed(n, value)),
+                        ExprKind::Number(n) => entries.push(CEntry::NumKeyed(n, value)),
+                        ExprKind::Int(i) => entries.push(CEntry::NumKeyed(i as f64, value)),
+                        ExprKind::Bool(b) => entries.push(CEntry::BoolKeyed(b, value)),
+                        ExprKind::Nil => entries.push(CEntry::NilKeyed(value)),
                         // to be safe
-                        _ => return Err("invalid key usage type for collection structure entry.".to_string()),
+                        _ => return Err(self.error("invalid key usage type for collection structure entry.")),
                     }
                 } else {
                     // okay this is an ordinary indexed entry,
                     // so automatically increment the index
                     entries.push(CEntry::Indexed(first));
-                    idx += 1;
                 }
             }
 
@@ -339,15 +534,16 @@ impl<'a> Parser<'a> {
             } else if self.check(&TokenType::RBracket) {
                 break;
             } else {
-                return Err("expected ',' or ']' to terminate collection definition.".to_string())
+                return Err(self.error("expected ',' or ']' to terminate collection definition."))
             }
         }
 
         self.consume(TokenType::RBracket)?;
-        Ok(Expr::Collection(Collection::new(entries)))
+        Ok(Expr::new(ExprKind::Collection(Collection::new(entries)), span))
     }
 
-    fn parse_if(&mut self) -> Result<Expr, String> {
+    fn parse_if(&mut self) -> Result<Expr, HexiError> {
+        let span = self.span;
         self.consume(TokenType::If)?;
 
         let cond = self.parse_expr()?;
@@ -368,10 +564,10 @@ impl<'a> Parser<'a> {
             None
         };
 
-        Ok(Expr::If(If::new(cond, block, else_block)))
+        Ok(Expr::new(ExprKind::If(If::new(cond, block, else_block)), span))
     }
 
-    fn parse_block(&mut self) -> Result<Block, String> {
+    fn parse_block(&mut self) -> Result<Block, HexiError> {
         self.consume(TokenType::LBrace)?;
 
         // these really should be statements, but whatever
@@ -390,8 +586,9 @@ impl<'a> Parser<'a> {
         Ok(Block::new(exprs))
     }
 
-    fn parse_identifier(&mut self) -> Result<Expr, String> {
-        let name = self.current_lex().unwrap().clone();
+    fn parse_identifier(&mut self) -> Result<Expr, HexiError> {
+        let name = Symbol::intern(self.current_lex().unwrap());
+        let span = self.span;
 
         // explicitly check if it's a print call
         // if name == "print" {
@@ -403,41 +600,48 @@ impl<'a> Parser<'a> {
 
         // advance to the next token
         self.advance();
+        self.parse_identifier_rest(name, span)
+    }
 
+    // whatever follows an identifier that's already been eaten: a call, a
+    // module access, an assignment or nothing
+    fn parse_identifier_rest(&mut self, name: Symbol, span: Span) -> Result<Expr, HexiError> {
         // check if we encounter a double colon '::' for module access, do this first
         if self.check(&TokenType::DblColon) {
             self.consume(TokenType::DblColon)?;
 
             // ident after ::
-            let fn_name = self.consume(TokenType::Ident)?.lexeme;
+            let fn_tok = self.consume(TokenType::Ident)?;
+            let fn_name = Symbol::intern(&fn_tok.lexeme);
+            let span = span.to(fn_tok.span);  // cover the whole module::name
 
             // function call?
             return if self.check(&TokenType::LParen) {
-                self.parse_mod_call(name, fn_name)
+                self.parse_mod_call(name, fn_name, span)
             } else {
                 // no module call but a reference to const perhaps?
                 // math::PI for example
-                Ok(Expr::Identifier(format!("{}::{}", name, fn_name)))
+                Ok(Expr::new(ExprKind::Identifier(Symbol::intern(&format!("{}::{}", name, fn_name))), span))
             }
         }
 
         // if the next token is a '(' then treat it as a function call
         if self.check(&TokenType::LParen) {
             // pass the name of the function
-            self.parse_call(name)
+            self.parse_call(name, span)
             // // if the next token is a '=' then treat it as a variable declaration
             // } else if self.check(&TokenType::Equals) {
             //     self.parse_var_decl(name)
 
         // we are now expecting this: `ident = ...` , assignment
         } else if self.check(&TokenType::Equals) {
-            self.parse_assignment(name)
+            self.parse_assignment(name, span)
         } else {
-            Ok(Expr::Identifier(name))
+            Ok(Expr::new(ExprKind::Identifier(name), span))
         }
     }
 
-    fn parse_call(&mut self, name: String) -> Result<Expr, String> {
+    fn parse_call(&mut self, name: Symbol, span: Span) -> Result<Expr, HexiError> {
         // self.advance();
         // let mut args: Vec<Expr> = Vec::new();
 
@@ -461,32 +665,33 @@ impl<'a> Parser<'a> {
         self.consume(TokenType::RParen)?;
         // self.consume(TokenType::Semi)?;
 
-        Ok(Expr::Call(Call::new( name, args )))
+        Ok(Expr::new(ExprKind::Call(Call::new( name, args )), span))
     }
 
-    fn parse_mod_call(&mut self, module: String, name: String) -> Result<Expr, String> {
+    fn parse_mod_call(&mut self, module: Symbol, name: Symbol, span: Span) -> Result<Expr, HexiError> {
         self.consume(TokenType::LParen)?;
         let args = if self.check(&TokenType::RParen) { Vec::new() } else { self.parse_args()? };
         self.consume(TokenType::RParen)?;
         // self.consume(TokenType::Semi)?;
 
-        Ok(Expr::Call(Call::new_from_module(module, name, args)))
+        Ok(Expr::new(ExprKind::Call(Call::new_from_module(module, name, args)), span))
     }
 
-    fn parse_assignment(&mut self, name: String) -> Result<Expr, String> {
+    fn parse_assignment(&mut self, name: Symbol, span: Span) -> Result<Expr, HexiError> {
         self.consume(TokenType::Equals)?;
 
         let assignee = self.parse_bin_expr(0)?;
 
         // self.consume(TokenType::Semi)?;
 
-        Ok(Expr::Assignment(Assignment::new(name, assignee)))
+        Ok(Expr::new(ExprKind::Assignment(Assignment::new(name, assignee)), span))
     }
 
-    fn parse_var_decl(&mut self) -> Result<Expr, String> {
+    fn parse_var_decl(&mut self) -> Result<Expr, HexiError> {
         self.consume(TokenType::Val)?;
 
-        let name = self.consume(TokenType::Ident)?.lexeme;
+        let name_tok = self.consume(TokenType::Ident)?;
+        let (name, span) = (Symbol::intern(&name_tok.lexeme), name_tok.span);
 
         self.consume(TokenType::Equals)?;
 
@@ -494,10 +699,10 @@ impl<'a> Parser<'a> {
 
         // self.consume(TokenType::Semi)?;
 
-        Ok(Expr::VarDecl(VarDecl::new(name, value)))
+        Ok(Expr::new(ExprKind::VarDecl(VarDecl::new(name, value)), span))
     }
 
-    fn parse_args(&mut self) -> Result<Vec<Expr>, String> {
+    fn parse_args(&mut self) -> Result<Vec<Expr>, HexiError> {
         let mut args = Vec::new();
 
         // first arg be pused
@@ -520,16 +725,129 @@ impl<'a> Parser<'a> {
         Ok(args)
     }
 
-    fn parse_number(&mut self) -> Result<Expr, String> {
+    fn parse_number(&mut self) -> Result<Expr, HexiError> {
         let num = self.current_lex().unwrap().clone();
+        let span = self.span;
+
+        // no decimal point means it's an int, which turns into a bigint if it's too big to fit in one
+        let kind = match num.parse::<i64>() {
+            Ok(i) => ExprKind::Int(i),
+            Err(_) => match BigInt::parse(&num, 10) {
+                Some(b) => ExprKind::BigInt(b),
+                None => match num.parse() {
+                    Ok(n) => ExprKind::Number(n),
+                    Err(_) => return Err(self.error(format!("malformed number '{}'", num))),
+                },
+            },
+        };
+
+        self.advance();
+        Ok(Expr::new(kind, span))
+    }
+
+    // true, false and nil
+    fn parse_literal(&mut self) -> Result<Expr, HexiError> {
+        let kind = match self.current.as_ref().map(|t| &t.token_type) {
+            Some(TokenType::True) => ExprKind::Bool(true),
+            Some(TokenType::False) => ExprKind::Bool(false),
+            _ => ExprKind::Nil,
+        };
+        let span = self.span;
         self.advance();
-        Ok(Expr::Number(num.parse().unwrap()))
+
+        Ok(Expr::new(kind, span))
     }
 
-    fn parse_string(&mut self) -> Result<Expr, String> {
+    fn parse_string(&mut self) -> Result<Expr, HexiError> {
         let strr = self.current_lex().unwrap().clone();
+        let span = self.span;
         self.advance();
 
-        Ok(Expr::String(strr))
+        Ok(Expr::new(ExprKind::String(strr.into()), span))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::ast::{Expr, ExprKind};
+    use crate::parse;
+
+    // how many postfix links sit on top of whatever the chain started from
+    fn chain_len(mut e: &Expr) -> usize {
+        let mut len = 0;
+        loop {
+            e = match &e.kind {
+                ExprKind::FieldAccess(f) => &f.object,
+                ExprKind::MethodCall(m) => &m.object,
+                ExprKind::IndexAccess(i) => &i.object,
+                _ => return len,
+            };
+            len += 1;
+        }
+    }
+
+    fn parse_one(source: &str) -> Expr {
+        let mut exprs = parse(source).unwrap();
+        assert_eq!(exprs.len(), 1, "{}", source);
+        exprs.remove(0)
+    }
+
+    #[test]
+    fn call_results_take_postfix() {
+        let e = parse_one("json::parse(s).get(\"id\")[0].name");
+        assert_eq!(chain_len(&e), 3);
+        let ExprKind::FieldAccess(f) = &e.kind else { panic!("expected a field access, got {:?}", e.kind) };
+        assert_eq!(&*f.field, "name");
+
+        let e = parse_one("f(1)[0]");
+        assert!(matches!(&e.kind, ExprKind::IndexAccess(i) if matches!(i.object.kind, ExprKind::Call(_))));
+    }
+
+    #[test]
+    fn long_chains() {
+        let fields = format!("a{}", ".b".repeat(200));
+        assert_eq!(chain_len(&parse_one(&fields)), 200);
+
+        let calls = format!("f(){}", ".g()".repeat(200));
+        assert_eq!(chain_len(&parse_one(&calls)), 200);
+
+        let mixed = format!("f(x){}", ".get(\"id\")[0].name".repeat(60));
+        assert_eq!(chain_len(&parse_one(&mixed)), 180);
+    }
+
+    #[test]
+    fn chains_in_collections() {
+        let e = parse_one("[a.b.c, f(x).y, g()[0].z()]");
+        let ExprKind::Collection(c) = &e.kind else { panic!("expected a collection, got {:?}", e.kind) };
+        assert_eq!(c.entries.len(), 3);
+    }
+
+    #[test]
+    fn chains_past_the_depth_limit() {
+        let e = parse(&format!("a{}", ".b".repeat(1000))).unwrap_err();
+        assert!(e.message.contains("chained together"), "{}", e.message);
+
+        let e = parse(&format!("f(){}", "[0].g()".repeat(500))).unwrap_err();
+        assert!(e.message.contains("chained together"), "{}", e.message);
+
+        let e = parse(&vec!["1"; 1000].join(" + ")).unwrap_err();
+        assert!(e.message.contains("operators chained together"), "{}", e.message);
+    }
+
+    #[test]
+    fn every_error_is_collected() {
+        let errors = crate::syntax_errors("val = 1\nio::println(1)\nval y = (1 + ]\nif y {\n    val = 3\n}\nval z = 2 $ 3\n]");
+        let lines: Vec<_> = errors.iter().map(|e| e.span.unwrap().line).collect();
+        assert_eq!(lines, [1, 3, 5, 7, 8]);
+
+        assert!(crate::syntax_errors("val x = 1\nx + 2").is_empty());
+    }
+
+    #[test]
+    fn nesting_past_the_depth_limit() {
+        let e = parse(&format!("{}1{}", "(".repeat(1000), ")".repeat(1000))).unwrap_err();
+        assert_eq!(e.message, "expression nested too deeply");
+
+        let e = parse(&format!("{}1{}", "{".repeat(1000), "}".repeat(1000))).unwrap_err();
+        assert_eq!(e.message, "expression nested too deeply");
+    }
+}
@@ -0,0 +1,51 @@
+use crate::stdlib::Module;
+use libloading::Library;
+use std::env;
+use std::path::{Path, PathBuf};
+
+// native modules shipped as shared libraries. `include foo` looks for libfoo.so
+// (foo.dll, libfoo.dylib) in the plugin dirs and calls the `hexi_module` it
+// exports to find out what functions it has:
+//
+// #[unsafe(no_mangle)]
+// pub fn hexi_module() -> hexi::stdlib::Module {
+//     Module { name: "foo", funcs: &[("bar", bar_nfn)], ctx_funcs: &[], method_types: &[], consts: &[] }
+// }
+//
+// there's no stable rust abi, so a plugin has to be built with the same
+// compiler and hexi version as the interpreter loading it
+
+pub type PluginEntry = fn() -> Module;
+
+const ENTRY: &[u8] = b"hexi_module";
+
+// a loaded library, its functions are only valid while this is alive
+pub struct Plugin {
+    _lib: Library,
+    pub module: Module,
+}
+
+// the working directory, then anything in HEXI_PLUGIN_PATH
+pub fn default_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from(".")];
+    if let Some(paths) = env::var_os("HEXI_PLUGIN_PATH") {
+        dirs.extend(env::split_paths(&paths));
+    }
+    dirs
+}
+
+pub fn find(name: &str, dirs: &[PathBuf]) -> Option<PathBuf> {
+    let file = format!("{}{}{}", env::consts::DLL_PREFIX, name, env::consts::DLL_SUFFIX);
+    dirs.iter().map(|d| d.join(&file)).find(|p| p.is_file())
+}
+
+pub fn load(path: &Path) -> Result<Plugin, String> {
+    // loading runs the library's initializers and calling the entry point trusts
+    // it to have the right signature, nothing we can check from here
+    unsafe {
+        let lib = Library::new(path).map_err(|e| format!("could not load plugin '{}': {}", path.display(), e))?;
+        let entry = lib.get::<PluginEntry>(ENTRY).map_err(|e| format!("plugin '{}' has no hexi_module(): {}", path.display(), e))?;
+        let module = entry();
+        Ok(Plugin { _lib: lib, module })
+    }
+}
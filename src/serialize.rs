@@ -0,0 +1,162 @@
+use crate::bigint::BigInt;
+use crate::interpreter::{CKey, CValue, Value};
+use crate::stdlib::bytes;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, SerializeMap, SerializeSeq, Serializer};
+use std::cell::RefCell;
+use std::fmt;
+
+// serde support so hexi values can go to and from json, toml, msgpack or whatever
+// else the host is using. plain arrays (no holes, no keys) become sequences,
+// everything else becomes a map (bool and nil keys as their names, bytes keys
+// as hex, collection keys can't be written). bigints don't fit in
+// most formats so they're written as strings
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Int(i) => serializer.serialize_i64(*i),
+            Value::BigInt(b) => b.serialize(serializer),
+            Value::Number(n) => serializer.serialize_f64(*n),
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Bytes(b) => serializer.serialize_bytes(b),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Collection(c) => {
+                // serde has nowhere to carry state, so the collections on the
+                // current path live here. one that shows up again contains itself
+                // and would recurse until the stack runs out
+                let ptr = c.as_ptr() as *const ();
+                if SERIALIZING.with(|p| p.borrow().contains(&ptr)) {
+                    return Err(ser::Error::custom("cannot serialize a collection that contains itself"));
+                }
+                SERIALIZING.with(|p| p.borrow_mut().push(ptr));
+                let _leave = Leave;
+                c.borrow().serialize(serializer)
+            },
+            Value::Nil => serializer.serialize_unit(),
+            Value::Userdata(u) => Err(ser::Error::custom(format!("cannot serialize a {}", u.type_name()))),
+        }
+    }
+}
+
+thread_local! {
+    static SERIALIZING: RefCell<Vec<*const ()>> = const { RefCell::new(Vec::new()) };
+}
+
+// pops the collection pushed above, even when serializing it fails part way
+struct Leave;
+
+impl Drop for Leave {
+    fn drop(&mut self) {
+        SERIALIZING.with(|p| p.borrow_mut().pop());
+    }
+}
+
+// decimal string, same as values
+impl Serialize for BigInt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl Serialize for CValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.is_sequence() {
+            let mut seq = serializer.serialize_seq(Some(self.size()))?;
+            for (_, value) in self.iter() {
+                seq.serialize_element(value)?;
+            }
+            return seq.end();
+        }
+
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self.iter() {
+            match key {
+                // formats with string-only keys like json turn these into strings themselves
+                CKey::Index(i) => map.serialize_entry(&i, value)?,
+                CKey::String(s) => map.serialize_entry(&*s, value)?,
+                CKey::Number(n) => map.serialize_entry(&n, value)?,
+                CKey::Bool(b) => map.serialize_entry(&b.to_string(), value)?,
+                CKey::Nil => map.serialize_entry("nil", value)?,
+                CKey::Bytes(b) => map.serialize_entry(&bytes::to_hex(&b), value)?,
+                CKey::Ref(r) => return Err(ser::Error::custom(format!("cannot serialize a {} key", r.value().type_name()))),
+            }
+        }
+        map.end()
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a value hexi can represent")
+    }
+
+    fn visit_bool<E: de::Error>(self, b: bool) -> Result<Value, E> {
+        Ok(Value::Bool(b))
+    }
+
+    fn visit_i64<E: de::Error>(self, i: i64) -> Result<Value, E> {
+        Ok(Value::Int(i))
+    }
+
+    // too big for an i64 means it has to be a bigint
+    fn visit_u64<E: de::Error>(self, u: u64) -> Result<Value, E> {
+        match i64::try_from(u) {
+            Ok(i) => Ok(Value::Int(i)),
+            Err(_) => Ok(Value::from_bigint(BigInt::parse(&u.to_string(), 10).unwrap())),
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, n: f64) -> Result<Value, E> {
+        Ok(Value::Number(n))
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Value, E> {
+        Ok(Value::String(s.into()))
+    }
+
+    fn visit_bytes<E: de::Error>(self, b: &[u8]) -> Result<Value, E> {
+        Ok(Value::Bytes(b.into()))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Nil)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Nil)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut c = CValue::new();
+        while let Some(v) = seq.next_element()? {
+            c.push(v);
+        }
+        Ok(Value::collection(c))
+    }
+
+    // json keys are always strings, but yaml and friends can have ints, bools
+    // and so on as keys, those keep their type
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut c = CValue::new();
+        while let Some((k, v)) = map.next_entry::<Value, Value>()? {
+            let key = k.to_key().ok_or_else(|| de::Error::custom("NaN can't be used as a collection key"))?;
+            c.insert(key, v);
+        }
+        Ok(Value::collection(c))
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
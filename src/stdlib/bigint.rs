@@ -0,0 +1,134 @@
+use crate::bigint::BigInt;
+use crate::interpreter::Value;
+use super::Module;
+
+// ints overflow into bigints on their own, this module is for building them
+// from strings and the number theory helpers that would be too slow in hexi
+
+fn int_arg(v: &Value, func: &str) -> Result<BigInt, String> {
+    v.as_bigint().ok_or_else(|| format!("not an int in bigint::{}, got {}", func, v))
+}
+
+fn from_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("too many arguments or too little for function bigint::from, got {}, want 1 or 2", args.len()));
+    }
+
+    let radix = match args.get(1) {
+        Some(Value::Int(r)) if (2..=36).contains(r) => *r as u32,
+        Some(r) => return Err(format!("bigint::from radix must be an int between 2 and 36, got {}", r)),
+        None => 10,
+    };
+
+    match &args[0] {
+        Value::String(s) => BigInt::parse(s.trim(), radix)
+            .map(Value::from_bigint)
+            .ok_or_else(|| format!("bigint::from could not parse '{}' in base {}", s, radix)),
+        Value::Int(_) | Value::BigInt(_) => Ok(args[0].clone()),
+        Value::Number(n) if n.is_finite() => {
+            BigInt::parse(&format!("{:.0}", n.trunc()), 10).map(Value::from_bigint).ok_or_else(|| format!("bigint::from could not convert {}", n))
+        },
+        _ => Err(format!("bigint::from expects a string or number, got {}", args[0])),
+    }
+}
+
+fn pow_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("too many arguments or too little for function bigint::pow, got {}, want 2", args.len()));
+    }
+
+    let base = int_arg(&args[0], "pow")?;
+    let exp = match &args[1] {
+        Value::Int(e) if *e >= 0 => *e as u64,
+        _ => return Err(format!("bigint::pow exponent must be a non negative int, got {}", args[1])),
+    };
+
+    Ok(Value::from_bigint(base.pow(exp)))
+}
+
+// (base ^ exp) % modulus without building the giant intermediate
+fn modpow_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 3 {
+        return Err(format!("too many arguments or too little for function bigint::modpow, got {}, want 3", args.len()));
+    }
+
+    let base = int_arg(&args[0], "modpow")?;
+    let mut exp = int_arg(&args[1], "modpow")?;
+    let modulus = int_arg(&args[2], "modpow")?;
+
+    if exp.is_negative() {
+        return Err("bigint::modpow exponent must be non negative".to_string());
+    }
+    if modulus.is_zero() {
+        return Err("bigint::modpow modulus must not be zero".to_string());
+    }
+
+    // div_rem truncates, so a negative base would leave a negative remainder.
+    // the result always lands in 0..|modulus|, -2 to the 1 mod 5 is 3
+    let modulus = modulus.abs();
+    let reduce = |x: BigInt| {
+        let r = x.div_rem(&modulus).unwrap().1;
+        if r.is_negative() { r.add(&modulus) } else { r }
+    };
+
+    let two = BigInt::from_i64(2);
+    let mut result = reduce(BigInt::from_i64(1));
+    let mut b = reduce(base);
+
+    while !exp.is_zero() {
+        let (q, r) = exp.div_rem(&two).unwrap();
+        if !r.is_zero() {
+            result = reduce(result.mul(&b));
+        }
+        b = reduce(b.mul(&b));
+        exp = q;
+    }
+
+    Ok(Value::from_bigint(result))
+}
+
+fn gcd_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("too many arguments or too little for function bigint::gcd, got {}, want 2", args.len()));
+    }
+
+    let mut a = int_arg(&args[0], "gcd")?.abs();
+    let mut b = int_arg(&args[1], "gcd")?.abs();
+
+    while !b.is_zero() {
+        let r = a.div_rem(&b).unwrap().1;
+        a = b;
+        b = r;
+    }
+
+    Ok(Value::from_bigint(a))
+}
+
+fn to_string_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("too many arguments or too little for function bigint::to_string, got {}, want 1 or 2", args.len()));
+    }
+
+    let n = int_arg(&args[0], "to_string")?;
+    let radix = match args.get(1) {
+        Some(Value::Int(r)) if (2..=36).contains(r) => *r as u32,
+        Some(r) => return Err(format!("bigint::to_string radix must be an int between 2 and 36, got {}", r)),
+        None => 10,
+    };
+
+    Ok(Value::String(n.to_string_radix(radix).into()))
+}
+
+pub const BIGINT_MOD: Module = Module {
+    name: "bigint",
+    funcs: &[
+        ("from", from_nfn),
+        ("pow", pow_nfn),
+        ("modpow", modpow_nfn),
+        ("gcd", gcd_nfn),
+        ("to_string", to_string_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
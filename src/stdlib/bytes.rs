@@ -0,0 +1,146 @@
+use crate::interpreter::{CValue, Value};
+use crate::stdlib::Module;
+use std::rc::Rc;
+
+// bytes are raw binary data, what fs::read_bytes gives and fs::write_bytes
+// takes. they're immutable like strings, b[0] is the first byte as an int and
+// everything else is a function that also works as a method:
+//
+// val b = bytes::from_hex("cafe")
+// b.len()          -> 2
+// b.slice(1)       -> <bytes fe>
+// b.to_list()      -> [202, 254]
+
+pub(crate) fn to_hex(b: &[u8]) -> String {
+    b.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// how bytes print, hex so they never get mistaken for a string
+pub(crate) fn display(b: &[u8]) -> String {
+    if b.is_empty() {
+        "<bytes>".to_string()
+    } else {
+        format!("<bytes {}>", to_hex(b))
+    }
+}
+
+fn bytes_arg<'a>(args: &'a [Value], name: &str, min: usize, max: usize) -> Result<&'a Rc<[u8]>, String> {
+    if args.len() < min || args.len() > max {
+        let want = if min == max { min.to_string() } else { format!("{} to {}", min, max) };
+        return Err(format!("bytes::{} expects {} arguments including the bytes, got {}", name, want, args.len()));
+    }
+
+    match &args[0] {
+        Value::Bytes(b) => Ok(b),
+        other => Err(format!("bytes::{} expects bytes, got {}", name, other.type_name())),
+    }
+}
+
+fn string_arg<'a>(args: &'a [Value], name: &str) -> Result<&'a str, String> {
+    if args.len() != 1 {
+        return Err(format!("bytes::{} expects 1 argument, got {}", name, args.len()));
+    }
+
+    match &args[0] {
+        Value::String(s) => Ok(s),
+        other => Err(format!("bytes::{} expects a string, got {}", name, other.type_name())),
+    }
+}
+
+// upper or lower case, no separators
+fn from_hex_nfn(args: &[Value]) -> Result<Value, String> {
+    let s = string_arg(args, "from_hex")?;
+    if s.len() % 2 != 0 {
+        return Err(format!("bytes::from_hex expects an even number of hex digits, got {}", s.len()));
+    }
+
+    let bytes = (0..s.len()).step_by(2)
+        .map(|i| s.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| format!("'{}' isn't valid hex", s))?;
+    Ok(Value::Bytes(bytes.into()))
+}
+
+// the string's utf-8 encoding
+fn from_string_nfn(args: &[Value]) -> Result<Value, String> {
+    let s = string_arg(args, "from_string")?;
+    Ok(Value::Bytes(s.as_bytes().into()))
+}
+
+// a collection of ints from 0 to 255
+fn from_list_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("bytes::from_list expects 1 argument, got {}", args.len()));
+    }
+
+    let Value::Collection(c) = &args[0] else {
+        return Err(format!("bytes::from_list expects a collection, got {}", args[0].type_name()));
+    };
+
+    let bytes = c.borrow().iter()
+        .map(|(_, v)| match v {
+            Value::Int(i) => u8::try_from(*i).map_err(|_| format!("{} doesn't fit in a byte", i)),
+            other => Err(format!("bytes::from_list expects ints, got {}", other.type_name())),
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+    Ok(Value::Bytes(bytes.into()))
+}
+
+fn len_nfn(args: &[Value]) -> Result<Value, String> {
+    let b = bytes_arg(args, "len", 1, 1)?;
+    Ok(Value::Int(b.len() as i64))
+}
+
+// slice(b, start) or slice(b, start, end), end not included
+fn slice_nfn(args: &[Value]) -> Result<Value, String> {
+    let b = bytes_arg(args, "slice", 2, 3)?;
+
+    let index = |v: &Value| match v {
+        Value::Int(i) => usize::try_from(*i).map_err(|_| format!("bytes::slice index {} is negative", i)),
+        other => Err(format!("bytes::slice expects int indices, got {}", other.type_name())),
+    };
+    let start = index(&args[1])?;
+    let end = match args.get(2) {
+        Some(v) => index(v)?,
+        None => b.len(),
+    };
+
+    if start > end || end > b.len() {
+        return Err(format!("bytes::slice range {}..{} is out of bounds for {} bytes", start, end, b.len()));
+    }
+    Ok(Value::Bytes(b[start..end].into()))
+}
+
+fn to_hex_nfn(args: &[Value]) -> Result<Value, String> {
+    let b = bytes_arg(args, "to_hex", 1, 1)?;
+    Ok(Value::String(to_hex(b).into()))
+}
+
+// fails on anything that isn't valid utf-8 rather than guessing
+fn to_string_nfn(args: &[Value]) -> Result<Value, String> {
+    let b = bytes_arg(args, "to_string", 1, 1)?;
+    let s = std::str::from_utf8(b).map_err(|e| format!("bytes::to_string got bytes that aren't valid utf-8: {}", e))?;
+    Ok(Value::String(s.into()))
+}
+
+fn to_list_nfn(args: &[Value]) -> Result<Value, String> {
+    let b = bytes_arg(args, "to_list", 1, 1)?;
+    Ok(Value::collection(CValue::from_array(b.iter().map(|byte| Value::Int(*byte as i64)).collect())))
+}
+
+pub const BYTES_MOD: Module = Module {
+    name: "bytes",
+    funcs: &[
+        ("from_hex", from_hex_nfn),
+        ("from_string", from_string_nfn),
+        ("from_list", from_list_nfn),
+        ("len", len_nfn),
+        ("slice", slice_nfn),
+        ("to_hex", to_hex_nfn),
+        ("to_string", to_string_nfn),
+        ("to_list", to_list_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &["bytes"],
+    consts: &[],
+};
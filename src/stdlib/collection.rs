@@ -0,0 +1,661 @@
+use crate::ast::Expr;
+use crate::bigint::BigInt;
+use crate::interpreter::{CKey, CValue, Value};
+use crate::native::Ctx;
+use super::{parse_callback, run_callback, Module};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+// every function here takes the collection first, so they all work as
+// methods too: collection::push(c, 1) is the same call as c.push(1)
+
+fn collection_arg<'a>(args: &'a [Value], name: &str, want: usize) -> Result<&'a Rc<RefCell<CValue>>, String> {
+    if args.len() != want {
+        return Err(format!("collection::{} expects {} arguments including the collection, got {}", name, want, args.len()));
+    }
+
+    match &args[0] {
+        Value::Collection(c) => Ok(c),
+        other => Err(format!("collection::{} expects a collection, got {}", name, other.type_name())),
+    }
+}
+
+// for the natives that change the collection, frozen ones are off limits
+fn mutable_arg<'a>(args: &'a [Value], name: &str, want: usize) -> Result<&'a Rc<RefCell<CValue>>, String> {
+    let c = collection_arg(args, name, want)?;
+    if c.borrow().is_frozen() {
+        return Err(format!("collection::{} can't change a frozen collection", name));
+    }
+
+    Ok(c)
+}
+
+fn key_arg(v: &Value) -> Result<CKey, String> {
+    v.to_key().ok_or_else(|| "NaN can't be used as a collection key".to_string())
+}
+
+fn push_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = mutable_arg(args, "push", 2)?;
+    c.borrow_mut().push(args[1].clone());
+    Ok(Value::Nil)
+}
+
+fn pop_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = mutable_arg(args, "pop", 1)?;
+    Ok(c.borrow_mut().pop().unwrap_or(Value::Nil))
+}
+
+fn size_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = collection_arg(args, "size", 1)?;
+    Ok(Value::Int(c.borrow().len() as i64))
+}
+
+fn get_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = collection_arg(args, "get", 2)?;
+    let key = key_arg(&args[1])?;
+    Ok(c.borrow().get(&key).cloned().unwrap_or(Value::Nil))
+}
+
+fn insert_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = mutable_arg(args, "insert", 3)?;
+    let key = key_arg(&args[1])?;
+
+    let mut c = c.borrow_mut();
+    if let CKey::Index(idx) = key && c.is_array_like() && idx > c.size() {
+        return Err(format!("index {} is out of bounds", idx));
+    }
+
+    c.insert(key, args[2].clone());
+    Ok(Value::Nil)
+}
+
+// gives back the removed value, or nil if nothing was there. lists close the
+// gap, so after [a, b, c].remove(0) b is at 0. anything else just loses the key
+fn remove_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = mutable_arg(args, "remove", 2)?;
+    let key = key_arg(&args[1])?;
+
+    let mut c = c.borrow_mut();
+    let removed = match key {
+        CKey::Index(i) if c.is_sequence() => c.remove_shift(i),
+        key => c.remove(&key),
+    };
+    Ok(removed.unwrap_or(Value::Nil))
+}
+
+fn clear_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = mutable_arg(args, "clear", 1)?;
+    c.borrow_mut().clear();
+    Ok(Value::Nil)
+}
+
+fn is_empty_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = collection_arg(args, "is_empty", 1)?;
+    Ok(Value::Bool(c.borrow().is_empty()))
+}
+
+// whether the key is there at all, unlike get it tells a stored nil from a missing one
+fn has_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = collection_arg(args, "has", 2)?;
+    let key = key_arg(&args[1])?;
+    Ok(Value::Bool(c.borrow().get(&key).is_some()))
+}
+
+// the values of a plain list, for the functions that only make sense on one
+fn list_arg(args: &[Value], name: &str, want: usize) -> Result<Vec<Value>, String> {
+    let c = collection_arg(args, name, want)?;
+    let c = c.borrow();
+    if !c.is_sequence() {
+        return Err(format!("collection::{} only works on lists, this one has keys or holes", name));
+    }
+
+    Ok(c.iter().map(|(_, v)| v.clone()).collect())
+}
+
+fn count_arg(v: &Value, name: &str) -> Result<usize, String> {
+    match v {
+        Value::Int(n) if *n >= 0 => Ok(*n as usize),
+        other => Err(format!("collection::{} expects a non negative int, got {}", name, other)),
+    }
+}
+
+fn list(values: Vec<Value>) -> Value {
+    Value::collection(CValue::from_array(values))
+}
+
+// slice, concat, reverse, take and drop all give back a new list and leave
+// the one they're called on alone
+
+// slice(c, start) or slice(c, start, end), end not included
+fn slice_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() == 2 {
+        let values = list_arg(args, "slice", 2)?;
+        return slice_range(values, &args[1], &Value::Nil);
+    }
+
+    let values = list_arg(args, "slice", 3)?;
+    slice_range(values, &args[1], &args[2])
+}
+
+fn slice_range(values: Vec<Value>, start: &Value, end: &Value) -> Result<Value, String> {
+    let start = count_arg(start, "slice")?;
+    let end = match end {
+        Value::Nil => values.len(),
+        end => count_arg(end, "slice")?,
+    };
+
+    if start > end || end > values.len() {
+        return Err(format!("collection::slice range {}..{} is out of bounds for {} values", start, end, values.len()));
+    }
+    Ok(list(values[start..end].to_vec()))
+}
+
+fn concat_nfn(args: &[Value]) -> Result<Value, String> {
+    let mut values = list_arg(args, "concat", 2)?;
+    values.extend(list_arg(&args[1..], "concat", 1)?);
+    Ok(list(values))
+}
+
+fn reverse_nfn(args: &[Value]) -> Result<Value, String> {
+    let mut values = list_arg(args, "reverse", 1)?;
+    values.reverse();
+    Ok(list(values))
+}
+
+// nil for an empty list
+fn first_nfn(args: &[Value]) -> Result<Value, String> {
+    let values = list_arg(args, "first", 1)?;
+    Ok(values.into_iter().next().unwrap_or(Value::Nil))
+}
+
+fn last_nfn(args: &[Value]) -> Result<Value, String> {
+    let values = list_arg(args, "last", 1)?;
+    Ok(values.into_iter().last().unwrap_or(Value::Nil))
+}
+
+// the first n, or all of them if there are fewer
+fn take_nfn(args: &[Value]) -> Result<Value, String> {
+    let values = list_arg(args, "take", 2)?;
+    let n = count_arg(&args[1], "take")?;
+    Ok(list(values.into_iter().take(n).collect()))
+}
+
+// everything after the first n
+fn drop_nfn(args: &[Value]) -> Result<Value, String> {
+    let values = list_arg(args, "drop", 2)?;
+    let n = count_arg(&args[1], "drop")?;
+    Ok(list(values.into_iter().skip(n).collect()))
+}
+
+// a new collection with every entry of the first and then every entry of the
+// second on top, so the second one wins where they share a key
+fn merge_nfn(args: &[Value]) -> Result<Value, String> {
+    let a = collection_arg(args, "merge", 2)?;
+    let b = collection_arg(&args[1..], "merge", 1)?;
+
+    let mut out = CValue::new();
+    for (k, v) in a.borrow().iter().chain(b.borrow().iter()) {
+        out.insert(k, v.clone());
+    }
+    Ok(Value::collection(out))
+}
+
+fn deep_merge_into(out: &mut CValue, b: &Rc<RefCell<CValue>>, path: &mut Vec<*const ()>) -> Result<(), String> {
+    let ptr = b.as_ptr() as *const ();
+    if path.contains(&ptr) {
+        return Err("collection::deep_merge can't merge a collection that contains itself".to_string());
+    }
+    path.push(ptr);
+
+    for (k, v) in b.borrow().iter() {
+        let merged = match (out.get(&k), v) {
+            // keyed on both sides, merge those too. lists get replaced like any other value
+            (Some(Value::Collection(old)), Value::Collection(new)) if !old.borrow().is_sequence() && !new.borrow().is_sequence() => {
+                let mut inner = CValue::new();
+                deep_merge_into(&mut inner, old, path)?;
+                deep_merge_into(&mut inner, new, path)?;
+                Value::collection(inner)
+            },
+            _ => v.clone(),
+        };
+        out.insert(k, merged);
+    }
+
+    path.pop();
+    Ok(())
+}
+
+// like merge, except keyed collections found under the same key in both get
+// merged as well instead of the second replacing the first. handy for laying
+// a parsed config over the defaults. nested collections that get merged are
+// new ones, anything else is shared with a or b
+fn deep_merge_nfn(args: &[Value]) -> Result<Value, String> {
+    let a = collection_arg(args, "deep_merge", 2)?;
+    let b = collection_arg(&args[1..], "deep_merge", 1)?;
+
+    let mut out = CValue::new();
+    let mut path = Vec::new();
+    deep_merge_into(&mut out, a, &mut path)?;
+    deep_merge_into(&mut out, b, &mut path)?;
+    Ok(Value::collection(out))
+}
+
+// [[a0, b0], [a1, b1], ...] for as long as the shorter one goes
+fn zip_nfn(args: &[Value]) -> Result<Value, String> {
+    let a = list_arg(args, "zip", 2)?;
+    let b = list_arg(&args[1..], "zip", 1)?;
+    Ok(list(a.into_iter().zip(b).map(|(x, y)| list(vec![x, y])).collect()))
+}
+
+// [[0, first], [1, second], ...], counting along in the order the values print
+fn enumerate_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = collection_arg(args, "enumerate", 1)?;
+    let pairs = c.borrow().iter().enumerate()
+        .map(|(i, (_, v))| list(vec![Value::Int(i as i64), v.clone()]))
+        .collect();
+    Ok(list(pairs))
+}
+
+fn flatten_into(out: &mut Vec<Value>, values: &[Value], depth: usize, path: &mut Vec<*const ()>) -> Result<(), String> {
+    for v in values {
+        match v {
+            Value::Collection(c) if depth > 0 && c.borrow().is_sequence() => {
+                let ptr = c.as_ptr() as *const ();
+                if path.contains(&ptr) {
+                    return Err("collection::flatten can't flatten a list that contains itself".to_string());
+                }
+
+                path.push(ptr);
+                let inner: Vec<Value> = c.borrow().iter().map(|(_, v)| v.clone()).collect();
+                flatten_into(out, &inner, depth - 1, path)?;
+                path.pop();
+            },
+            v => out.push(v.clone()),
+        }
+    }
+    Ok(())
+}
+
+// lists inside the list get spliced into it, one level deep or `depth` levels.
+// keyed collections are left as they are
+fn flatten_nfn(args: &[Value]) -> Result<Value, String> {
+    let want = args.len().clamp(1, 2);
+    let values = list_arg(args, "flatten", want)?;
+    let depth = match args.get(1) {
+        Some(d) => count_arg(d, "flatten")?,
+        None => 1,
+    };
+
+    let mut out = Vec::new();
+    let mut path = vec![collection_arg(args, "flatten", want)?.as_ptr() as *const ()];
+    flatten_into(&mut out, &values, depth, &mut path)?;
+    Ok(list(out))
+}
+
+// the list without repeats, keeping the first of each. equal by ==, so 1 and
+// 1.0 are the same and so are two collections with the same contents
+fn unique_nfn(args: &[Value]) -> Result<Value, String> {
+    let values = list_arg(args, "unique", 1)?;
+
+    // anything that makes a plain key gets looked up as one, collections and
+    // such key by identity so they get compared one by one instead
+    let mut seen_keys = CValue::new();
+    let mut seen_refs: Vec<Value> = Vec::new();
+    let mut out = Vec::new();
+    for v in values {
+        let new = match v.to_key() {
+            Some(CKey::Ref(_)) if seen_refs.contains(&v) => false,
+            Some(CKey::Ref(_)) => {
+                seen_refs.push(v.clone());
+                true
+            },
+            Some(key) if seen_keys.get(&key).is_some() => false,
+            Some(key) => {
+                seen_keys.insert(key, Value::Nil);
+                true
+            },
+            // NaN isn't equal to anything, not even another NaN
+            None => true,
+        };
+        if new {
+            out.push(v);
+        }
+    }
+    Ok(list(out))
+}
+
+// keys, values and entries are lists in the order the collection prints in,
+// indices first and then the other keys in the order they were added
+fn keys_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = collection_arg(args, "keys", 1)?;
+    let keys = c.borrow().keys().map(|k| k.to_value()).collect();
+    Ok(Value::collection(CValue::from_array(keys)))
+}
+
+fn values_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = collection_arg(args, "values", 1)?;
+    let values = c.borrow().iter().map(|(_, v)| v.clone()).collect();
+    Ok(Value::collection(CValue::from_array(values)))
+}
+
+// [[key, value], ...]
+fn entries_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = collection_arg(args, "entries", 1)?;
+    let entries = c.borrow().iter()
+        .map(|(k, v)| Value::collection(CValue::from_array(vec![k.to_value(), v.clone()])))
+        .collect();
+    Ok(Value::collection(CValue::from_array(entries)))
+}
+
+// by ==, so [1, 2] counts as containing 2.0 and [[1]] as containing [1]
+fn contains_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = collection_arg(args, "contains", 2)?;
+    Ok(Value::Bool(c.borrow().iter().any(|(_, v)| *v == args[1])))
+}
+
+// the key of the first entry equal to the value, nil if there isn't one
+fn index_of_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = collection_arg(args, "index_of", 2)?;
+    let key = c.borrow().iter().find(|(_, v)| **v == args[1]).map(|(k, _)| k.to_value());
+    Ok(key.unwrap_or(Value::Nil))
+}
+
+// sum and avg want numbers, min and max anything that sorts. on an empty
+// collection sum is 0 and the rest are nil
+fn numbers(args: &[Value], name: &str) -> Result<Vec<Value>, String> {
+    let c = collection_arg(args, name, 1)?;
+    c.borrow().iter().map(|(_, v)| match v {
+        Value::Int(_) | Value::BigInt(_) | Value::Number(_) => Ok(v.clone()),
+        other => Err(format!("collection::{} expects numbers, found {}", name, other.type_name())),
+    }).collect()
+}
+
+// ints add up exactly (into a bigint if need be), any float makes it a float sum
+fn sum_nfn(args: &[Value]) -> Result<Value, String> {
+    let values = numbers(args, "sum")?;
+
+    if values.iter().all(|v| matches!(v, Value::Int(_) | Value::BigInt(_))) {
+        let total = values.iter().fold(BigInt::from_i64(0), |acc, v| acc.add(&v.as_bigint().unwrap()));
+        return Ok(Value::from_bigint(total));
+    }
+    Ok(Value::Number(values.iter().map(|v| v.as_f64().unwrap()).sum()))
+}
+
+fn avg_nfn(args: &[Value]) -> Result<Value, String> {
+    let values = numbers(args, "avg")?;
+    if values.is_empty() {
+        return Ok(Value::Nil);
+    }
+
+    let total: f64 = values.iter().map(|v| v.as_f64().unwrap()).sum();
+    Ok(Value::Number(total / values.len() as f64))
+}
+
+fn extreme(args: &[Value], name: &str, keep: Ordering) -> Result<Value, String> {
+    let c = collection_arg(args, name, 1)?;
+
+    let mut best: Option<Value> = None;
+    for (_, v) in c.borrow().iter() {
+        let better = match &best {
+            None => true,
+            Some(b) => compare(v, b)? == keep,
+        };
+        if better {
+            best = Some(v.clone());
+        }
+    }
+    Ok(best.unwrap_or(Value::Nil))
+}
+
+fn min_nfn(args: &[Value]) -> Result<Value, String> {
+    extreme(args, "min", Ordering::Less)
+}
+
+fn max_nfn(args: &[Value]) -> Result<Value, String> {
+    extreme(args, "max", Ordering::Greater)
+}
+
+fn compare(a: &Value, b: &Value) -> Result<Ordering, String> {
+    a.partial_cmp(b).ok_or_else(|| format!("can't compare {} with {}", a.type_name(), b.type_name()))
+}
+
+// sorts the values of a plain list by `key_of` and puts them back in the new
+// order. the values get copied out first so a list holding itself is fine, and
+// nothing moves if two of them can't be compared
+fn sort_with(c: &Rc<RefCell<CValue>>, name: &str, key_of: impl Fn(&Value) -> Result<Value, String>) -> Result<(), String> {
+    if !c.borrow().is_sequence() {
+        return Err(format!("collection::{} only sorts lists, this one has keys or holes", name));
+    }
+
+    let values: Vec<Value> = c.borrow().iter().map(|(_, v)| v.clone()).collect();
+    let mut keyed = values.into_iter().map(|v| Ok((key_of(&v)?, v))).collect::<Result<Vec<_>, String>>()?;
+
+    let mut err = None;
+    keyed.sort_by(|(a, _), (b, _)| compare(a, b).unwrap_or_else(|e| {
+        err.get_or_insert(e);
+        Ordering::Equal
+    }));
+    if let Some(e) = err {
+        return Err(e);
+    }
+
+    // stable, so equal ones keep their order
+    for (slot, (_, v)) in c.borrow_mut().dense_mut().iter_mut().zip(keyed) {
+        *slot = v;
+    }
+    Ok(())
+}
+
+// numbers by value and strings alphabetically, in place like push
+fn sort_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = mutable_arg(args, "sort", 1)?;
+    sort_with(c, "sort", |v| Ok(v.clone()))?;
+    Ok(Value::Nil)
+}
+
+// a list of collections ordered by one of their fields, people.sort_by_key("age")
+fn sort_by_key_nfn(args: &[Value]) -> Result<Value, String> {
+    let c = mutable_arg(args, "sort_by_key", 2)?;
+    let key = key_arg(&args[1])?;
+
+    sort_with(c, "sort_by_key", |v| match v {
+        Value::Collection(item) => Ok(item.borrow().get(&key).cloned().unwrap_or(Value::Nil)),
+        other => Err(format!("collection::sort_by_key expects a list of collections, found {}", other.type_name())),
+    })?;
+    Ok(Value::Nil)
+}
+
+// map, filter, reduce, find, each and the group_by/count_by pair take a bit
+// of hexi code (see parse_callback). it runs once per entry with `it` set to
+// the value and `key` to its key, reduce also has `acc`:
+//
+// [1, 2, 3].map("it * 2")                -> [2, 4, 6]
+// [1, 2, 3].filter("it > 1")             -> [2, 3]
+// [1, 2, 3].reduce("acc + it", 0)        -> 6
+// [a = 1].each("io::println(key, it)")
+//
+// the entries are copied out first, so the code can change the collection
+// without upsetting the loop
+type Entries = Vec<(CKey, Value)>;
+
+fn callback_args(args: &[Value], name: &str, want: usize) -> Result<(Entries, Vec<Expr>), String> {
+    let c = collection_arg(args, name, want)?;
+    let code = parse_callback(&format!("collection::{}", name), &args[1])?;
+
+    let entries = c.borrow().iter().map(|(k, v)| (k, v.clone())).collect();
+    Ok((entries, code))
+}
+
+// a list maps to a list, keyed entries keep their keys
+fn map_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let (entries, code) = callback_args(args, "map", 2)?;
+
+    let mut out = CValue::new();
+    for (k, v) in entries {
+        let mapped = run_callback(ctx, &code, &[("it", v), ("key", k.to_value())])?;
+        out.insert(k, mapped);
+    }
+    Ok(Value::collection(out))
+}
+
+// the entries the code is truthy for, a list stays a list with no holes
+fn filter_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let (entries, code) = callback_args(args, "filter", 2)?;
+
+    let mut out = CValue::new();
+    for (k, v) in entries {
+        if !run_callback(ctx, &code, &[("it", v.clone()), ("key", k.to_value())])?.is_truthy() {
+            continue;
+        }
+        match k {
+            CKey::Index(_) => out.push(v),
+            k => out.insert(k, v),
+        }
+    }
+    Ok(Value::collection(out))
+}
+
+fn reduce_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let (entries, code) = callback_args(args, "reduce", 3)?;
+
+    let mut acc = args[2].clone();
+    for (k, v) in entries {
+        acc = run_callback(ctx, &code, &[("acc", acc), ("it", v), ("key", k.to_value())])?;
+    }
+    Ok(acc)
+}
+
+// the first value the code is truthy for, or nil
+fn find_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let (entries, code) = callback_args(args, "find", 2)?;
+
+    for (k, v) in entries {
+        if run_callback(ctx, &code, &[("it", v.clone()), ("key", k.to_value())])?.is_truthy() {
+            return Ok(v);
+        }
+    }
+    Ok(Value::Nil)
+}
+
+// the code's result for each value becomes a key, group_by gathers the values
+// under it and count_by counts them:
+//
+// words.group_by("string::len(it)")   -> [3 = ["cat", "dog"], 4 = ["bird"]]
+// words.count_by("string::len(it)")   -> [3 = 2, 4 = 1]
+fn group_key(ctx: &mut Ctx, code: &[Expr], k: CKey, v: &Value, name: &str) -> Result<CKey, String> {
+    let group = run_callback(ctx, code, &[("it", v.clone()), ("key", k.to_value())])?;
+    group.to_key().ok_or_else(|| format!("collection::{} got NaN, which can't be a key", name))
+}
+
+fn group_by_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let (entries, code) = callback_args(args, "group_by", 2)?;
+
+    let mut groups = CValue::new();
+    for (k, v) in entries {
+        let group = group_key(ctx, &code, k, &v, "group_by")?;
+        match groups.get(&group) {
+            Some(Value::Collection(items)) => items.borrow_mut().push(v),
+            _ => groups.insert(group, list(vec![v])),
+        }
+    }
+    Ok(Value::collection(groups))
+}
+
+fn count_by_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let (entries, code) = callback_args(args, "count_by", 2)?;
+
+    let mut counts = CValue::new();
+    for (k, v) in entries {
+        let group = group_key(ctx, &code, k, &v, "count_by")?;
+        let n = match counts.get(&group) {
+            Some(Value::Int(n)) => *n,
+            _ => 0,
+        };
+        counts.insert(group, Value::Int(n + 1));
+    }
+    Ok(Value::collection(counts))
+}
+
+fn each_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let (entries, code) = callback_args(args, "each", 2)?;
+
+    for (k, v) in entries {
+        run_callback(ctx, &code, &[("it", v), ("key", k.to_value())])?;
+    }
+    Ok(Value::Nil)
+}
+
+// clone(value) and freeze(collection) are globals like str()
+pub(crate) fn clone_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("clone expects 1 argument, got {}", args.len()));
+    }
+
+    Ok(args[0].deep_copy())
+}
+
+// only the collection itself, ones inside it can still change. gives it back
+// so it can be frozen where it's made: val config = freeze([port = 80])
+pub(crate) fn freeze_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("freeze expects 1 argument, got {}", args.len()));
+    }
+
+    match &args[0] {
+        Value::Collection(c) => c.borrow_mut().freeze(),
+        other => return Err(format!("freeze expects a collection, got {}", other.type_name())),
+    }
+    Ok(args[0].clone())
+}
+
+pub const COLLECTION_MOD: Module = Module {
+    name: "collection",
+    funcs: &[
+        ("push", push_nfn),
+        ("pop", pop_nfn),
+        ("size", size_nfn),
+        ("get", get_nfn),
+        ("insert", insert_nfn),
+        ("remove", remove_nfn),
+        ("clear", clear_nfn),
+        ("is_empty", is_empty_nfn),
+        ("has", has_nfn),
+        ("slice", slice_nfn),
+        ("concat", concat_nfn),
+        ("reverse", reverse_nfn),
+        ("first", first_nfn),
+        ("last", last_nfn),
+        ("take", take_nfn),
+        ("drop", drop_nfn),
+        ("merge", merge_nfn),
+        ("deep_merge", deep_merge_nfn),
+        ("zip", zip_nfn),
+        ("enumerate", enumerate_nfn),
+        ("flatten", flatten_nfn),
+        ("unique", unique_nfn),
+        ("sum", sum_nfn),
+        ("avg", avg_nfn),
+        ("min", min_nfn),
+        ("max", max_nfn),
+        ("join", super::string::join_nfn),
+        ("keys", keys_nfn),
+        ("values", values_nfn),
+        ("entries", entries_nfn),
+        ("contains", contains_nfn),
+        ("index_of", index_of_nfn),
+        ("sort", sort_nfn),
+        ("sort_by_key", sort_by_key_nfn),
+    ],
+    ctx_funcs: &[
+        ("map", map_nfn),
+        ("filter", filter_nfn),
+        ("reduce", reduce_nfn),
+        ("find", find_nfn),
+        ("group_by", group_by_nfn),
+        ("count_by", count_by_nfn),
+        ("each", each_nfn),
+    ],
+    method_types: &["collection"],
+    consts: &[],
+};
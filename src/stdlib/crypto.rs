@@ -0,0 +1,91 @@
+use crate::interpreter::Value;
+use crate::stdlib::Module;
+
+// randomness that's safe for secrets, straight from the os, and a comparison
+// that doesn't give away how much of a secret matched by how long it took
+//
+// crypto::random_bytes(16)            -> <bytes 3f9a...>
+// crypto::random_token(32)            -> "q2XbT0..."
+// crypto::compare(given, expected)    -> true
+
+// letters and digits, so tokens are safe in urls and file names
+const TOKEN_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+// more than any sane script needs, but keeps a typo from eating all the memory
+const MAX_LEN: i64 = 1 << 20;
+
+fn fill(name: &str, buf: &mut [u8]) -> Result<(), String> {
+    getrandom::fill(buf).map_err(|e| format!("crypto::{} couldn't get randomness from the os: {}", name, e))
+}
+
+fn len_arg(name: &str, args: &[Value]) -> Result<usize, String> {
+    if args.len() != 1 {
+        return Err(format!("crypto::{} expects 1 argument, got {}", name, args.len()));
+    }
+
+    match args[0] {
+        Value::Int(n) if (0..=MAX_LEN).contains(&n) => Ok(n as usize),
+        Value::Int(n) => Err(format!("crypto::{} expects a length from 0 to {}, got {}", name, MAX_LEN, n)),
+        ref other => Err(format!("crypto::{} expects an int length, got {}", name, other.type_name())),
+    }
+}
+
+fn random_bytes_nfn(args: &[Value]) -> Result<Value, String> {
+    let mut buf = vec![0; len_arg("random_bytes", args)?];
+    fill("random_bytes", &mut buf)?;
+    Ok(Value::Bytes(buf.into()))
+}
+
+// len characters out of TOKEN_CHARS, every one equally likely
+fn random_token_nfn(args: &[Value]) -> Result<Value, String> {
+    let len = len_arg("random_token", args)?;
+
+    // bytes past the last whole multiple of 62 are thrown away, wrapping them
+    // around would make the first few characters more common than the rest
+    let limit = 256 - 256 % TOKEN_CHARS.len();
+    let mut token = String::with_capacity(len);
+    let mut buf = [0u8; 64];
+    while token.len() < len {
+        fill("random_token", &mut buf)?;
+        for &b in buf.iter().filter(|b| (**b as usize) < limit) {
+            if token.len() == len {
+                break;
+            }
+            token.push(TOKEN_CHARS[b as usize % TOKEN_CHARS.len()] as char);
+        }
+    }
+    Ok(Value::String(token.into()))
+}
+
+// strings or bytes, compared in a time that only depends on their length
+fn compare_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("crypto::compare expects 2 arguments, got {}", args.len()));
+    }
+
+    let mut sides = args.iter().map(|v| match v {
+        Value::String(s) => Ok(s.as_bytes()),
+        Value::Bytes(b) => Ok(&**b),
+        other => Err(format!("crypto::compare expects strings or bytes, got {}", other.type_name())),
+    });
+    let a = sides.next().unwrap()?;
+    let b = sides.next().unwrap()?;
+
+    if a.len() != b.len() {
+        return Ok(Value::Bool(false));
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    Ok(Value::Bool(std::hint::black_box(diff) == 0))
+}
+
+pub const CRYPTO_MOD: Module = Module {
+    name: "crypto",
+    funcs: &[
+        ("random_bytes", random_bytes_nfn),
+        ("random_token", random_token_nfn),
+        ("compare", compare_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
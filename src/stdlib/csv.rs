@@ -0,0 +1,148 @@
+use crate::interpreter::{CKey, CValue, Value};
+use crate::stdlib::Module;
+use std::rc::Rc;
+
+// csv::parse(text) gives a collection of rows, each row a collection of strings.
+// with [headers = 1] (any truthy value) the first line names the columns and
+// every row after it is keyed by those names instead. [delimiter = ";"] for
+// other separators
+//
+// csv::write(rows) goes the other way, rows that are keyed get a header line
+// built from the first one's keys
+
+struct Options {
+    headers: bool,
+    delimiter: u8,
+}
+
+fn options(name: &str, arg: Option<&Value>) -> Result<Options, String> {
+    let mut opts = Options { headers: false, delimiter: b',' };
+    let Some(arg) = arg else {
+        return Ok(opts);
+    };
+
+    let Value::Collection(c) = arg else {
+        return Err(format!("csv::{} options must be a collection, got {}", name, arg.type_name()));
+    };
+
+    let c = c.borrow();
+    if let Some(h) = c.get_by_string("headers") {
+        opts.headers = h.is_truthy();
+    }
+
+    if let Some(d) = c.get_by_string("delimiter") {
+        opts.delimiter = match d {
+            Value::String(s) if s.len() == 1 => s.as_bytes()[0],
+            _ => return Err(format!("csv::{} delimiter must be a single character, got {}", name, d)),
+        };
+    }
+
+    Ok(opts)
+}
+
+fn parse_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("csv::parse expects 1 or 2 arguments, got {}", args.len()));
+    }
+
+    let text = match &args[0] {
+        Value::String(s) => s,
+        other => return Err(format!("csv::parse expects a string, got {}", other.type_name())),
+    };
+    let opts = options("parse", args.get(1))?;
+
+    let mut reader = ::csv::ReaderBuilder::new()
+        .has_headers(opts.headers)
+        .delimiter(opts.delimiter)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let headers: Vec<Rc<str>> = if opts.headers {
+        let h = reader.headers().map_err(|e| format!("error while parsing csv: {}", e))?;
+        h.iter().map(Rc::from).collect()
+    } else {
+        vec![]
+    };
+
+    let mut rows = CValue::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("error while parsing csv: {}", e))?;
+
+        let mut row = CValue::new();
+        for (i, field) in record.iter().enumerate() {
+            let value = Value::String(field.into());
+            // fields past the last header keep their position instead
+            match headers.get(i) {
+                Some(h) => row.insert(CKey::String(h.clone()), value),
+                None => row.insert(CKey::Index(i), value),
+            }
+        }
+        rows.push(Value::collection(row));
+    }
+
+    Ok(Value::collection(rows))
+}
+
+// strings as they are, nil as an empty field, anything else how it prints
+fn field(v: &Value) -> String {
+    match v {
+        Value::Nil => String::new(),
+        v => v.to_string(),
+    }
+}
+
+fn write_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("csv::write expects 1 or 2 arguments, got {}", args.len()));
+    }
+
+    let rows = match &args[0] {
+        Value::Collection(c) => c.borrow().iter().map(|(_, v)| v.clone()).collect::<Vec<_>>(),
+        other => return Err(format!("csv::write expects a collection of rows, got {}", other.type_name())),
+    };
+    let opts = options("write", args.get(1))?;
+
+    let mut writer = ::csv::WriterBuilder::new()
+        .delimiter(opts.delimiter)
+        .flexible(true)
+        .from_writer(vec![]);
+    let err = |e: ::csv::Error| format!("error while writing csv: {}", e);
+
+    let mut header: Option<Vec<CKey>> = None;
+    for row in &rows {
+        let Value::Collection(row) = row else {
+            return Err(format!("csv::write expects every row to be a collection, got {}", row.type_name()));
+        };
+        let row = row.borrow();
+
+        if row.is_sequence() {
+            writer.write_record(row.iter().map(|(_, v)| field(v))).map_err(err)?;
+            continue;
+        }
+
+        // the first keyed row decides the columns for all of them
+        let keys = match &header {
+            Some(keys) => keys,
+            None => {
+                let keys: Vec<CKey> = row.keys().collect();
+                writer.write_record(keys.iter().map(|k| field(&k.to_value()))).map_err(err)?;
+                header.insert(keys)
+            },
+        };
+        writer.write_record(keys.iter().map(|k| row.get(k).map_or(String::new(), field))).map_err(err)?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| format!("error while writing csv: {}", e))?;
+    Ok(Value::String(String::from_utf8_lossy(&bytes).into()))
+}
+
+pub const CSV_MOD: Module = Module {
+    name: "csv",
+    funcs: &[
+        ("parse", parse_nfn),
+        ("write", write_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
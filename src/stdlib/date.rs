@@ -0,0 +1,149 @@
+use crate::interpreter::Value;
+use crate::stdlib::Module;
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeDelta, Timelike, Utc};
+use std::fmt::Write;
+
+// dates are plain unix timestamps in whole seconds (what time::now gives),
+// every function here reads and writes them as utc
+//
+// date::format(ts, "%Y-%m-%d") and date::parse(text, fmt) use strftime style
+// specifiers, parse without a format takes iso 8601 / rfc 3339 text
+
+fn timestamp_arg(name: &str, v: &Value) -> Result<DateTime<Utc>, String> {
+    let secs = match v {
+        Value::Int(i) => *i,
+        Value::Number(n) if n.is_finite() => n.floor() as i64,
+        other => return Err(format!("date::{} expects a timestamp, got {}", name, other.type_name())),
+    };
+
+    DateTime::from_timestamp(secs, 0).ok_or_else(|| format!("date::{} timestamp {} is out of range", name, secs))
+}
+
+fn string_arg<'a>(name: &str, v: &'a Value) -> Result<&'a str, String> {
+    match v {
+        Value::String(s) => Ok(s),
+        other => Err(format!("date::{} expects a string, got {}", name, other.type_name())),
+    }
+}
+
+fn format_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("date::format expects 2 arguments, got {}", args.len()));
+    }
+
+    let date = timestamp_arg("format", &args[0])?;
+    let fmt = string_arg("format", &args[1])?;
+
+    // chrono panics on a bad specifier when using to_string, write! reports it instead
+    let mut out = String::new();
+    write!(out, "{}", date.format(fmt)).map_err(|_| format!("invalid date format '{}'", fmt))?;
+    Ok(Value::String(out.into()))
+}
+
+fn parse_with(text: &str, fmt: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(text, fmt).ok()
+        .or_else(|| NaiveDate::parse_from_str(text, fmt).ok().and_then(|d| d.and_hms_opt(0, 0, 0)))
+}
+
+fn parse_iso(text: &str) -> Option<NaiveDateTime> {
+    if let Ok(d) = DateTime::parse_from_rfc3339(text) {
+        return Some(d.naive_utc());
+    }
+
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d"].iter()
+        .find_map(|fmt| parse_with(text, fmt))
+}
+
+// a format with only a date in it parses to midnight that day
+fn parse_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("date::parse expects 1 or 2 arguments, got {}", args.len()));
+    }
+
+    let text = string_arg("parse", &args[0])?;
+    let date = match args.get(1) {
+        Some(fmt) => parse_with(text, string_arg("parse", fmt)?),
+        None => parse_iso(text),
+    };
+
+    match date {
+        Some(d) => Ok(Value::Int(d.and_utc().timestamp())),
+        None => Err(format!("couldn't parse '{}' as a date", text)),
+    }
+}
+
+// one argument (the timestamp) in, one number out
+fn component(name: &str, args: &[Value], f: fn(&DateTime<Utc>) -> i64) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("date::{} expects 1 argument, got {}", name, args.len()));
+    }
+
+    Ok(Value::Int(f(&timestamp_arg(name, &args[0])?)))
+}
+
+fn year_nfn(args: &[Value]) -> Result<Value, String> {
+    component("year", args, |d| d.year() as i64)
+}
+
+// 1 to 12
+fn month_nfn(args: &[Value]) -> Result<Value, String> {
+    component("month", args, |d| d.month() as i64)
+}
+
+fn day_nfn(args: &[Value]) -> Result<Value, String> {
+    component("day", args, |d| d.day() as i64)
+}
+
+// 1 for monday to 7 for sunday, like iso 8601
+fn weekday_nfn(args: &[Value]) -> Result<Value, String> {
+    component("weekday", args, |d| d.weekday().number_from_monday() as i64)
+}
+
+fn hour_nfn(args: &[Value]) -> Result<Value, String> {
+    component("hour", args, |d| d.hour() as i64)
+}
+
+fn minute_nfn(args: &[Value]) -> Result<Value, String> {
+    component("minute", args, |d| d.minute() as i64)
+}
+
+fn second_nfn(args: &[Value]) -> Result<Value, String> {
+    component("second", args, |d| d.second() as i64)
+}
+
+// negative days go backwards
+fn add_days_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("date::add_days expects 2 arguments, got {}", args.len()));
+    }
+
+    let date = timestamp_arg("add_days", &args[0])?;
+    let days = match &args[1] {
+        Value::Int(i) => *i,
+        other => return Err(format!("date::add_days expects a whole number of days, got {}", other)),
+    };
+
+    TimeDelta::try_days(days)
+        .and_then(|delta| date.checked_add_signed(delta))
+        .map(|d| Value::Int(d.timestamp()))
+        .ok_or_else(|| "date::add_days result is out of range".to_string())
+}
+
+pub const DATE_MOD: Module = Module {
+    name: "date",
+    funcs: &[
+        ("format", format_nfn),
+        ("parse", parse_nfn),
+        ("year", year_nfn),
+        ("month", month_nfn),
+        ("day", day_nfn),
+        ("weekday", weekday_nfn),
+        ("hour", hour_nfn),
+        ("minute", minute_nfn),
+        ("second", second_nfn),
+        ("add_days", add_days_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
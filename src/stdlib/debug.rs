@@ -0,0 +1,119 @@
+use crate::format::quote_string;
+use crate::interpreter::{CKey, CValue, Value};
+use crate::native::Ctx;
+use crate::stdlib::Module;
+use crate::stdlib::bytes;
+
+// for looking at values while working on a script. debug::dump spells out the
+// type of everything, so "5" and 5 or nil and a missing entry can't be mixed up:
+//
+// debug::dump([name = "bob", age = "22"])
+//
+// collection (2) [
+//   "name": string "bob"
+//   "age": string "22"
+// ]
+
+fn dump_into(out: &mut String, v: &Value, indent: usize, path: &mut Vec<*const ()>) {
+    let Value::Collection(c) = v else {
+        out.push_str(&match v {
+            Value::Int(_) | Value::BigInt(_) => format!("int {}", v),
+            Value::Number(_) => format!("float {}", v),
+            Value::String(s) => format!("string {}", quote_string(s)),
+            Value::Bytes(b) => format!("bytes ({}) {}", b.len(), bytes::to_hex(b)),
+            Value::Bool(b) => format!("bool {}", b),
+            Value::Userdata(u) => format!("<{}>", u.type_name()),
+            _ => "nil".to_string(),
+        });
+        return;
+    };
+
+    let ptr = c.as_ptr() as *const ();
+    if path.contains(&ptr) {
+        out.push_str("collection <cycle>");
+        return;
+    }
+
+    let c = c.borrow();
+    let frozen = if c.is_frozen() { " frozen" } else { "" };
+    out.push_str(&format!("collection ({}){} [", c.len(), frozen));
+    if c.is_empty() {
+        out.push(']');
+        return;
+    }
+
+    path.push(ptr);
+    for (k, v) in c.iter() {
+        out.push_str(&format!("\n{}{}: ", "  ".repeat(indent + 1), key(&k)));
+        dump_into(out, v, indent + 1, path);
+    }
+    path.pop();
+    out.push_str(&format!("\n{}]", "  ".repeat(indent)));
+}
+
+// string keys in quotes, so ["1" = x] and [1 = x] look different too
+fn key(k: &CKey) -> String {
+    match k.to_value() {
+        Value::String(s) => quote_string(&s),
+        Value::Bytes(b) => bytes::display(&b),
+        other => other.to_string(),
+    }
+}
+
+// the same thing debug::dump prints, as a string
+fn dump_string(args: &[Value], name: &str) -> Result<String, String> {
+    if args.len() != 1 {
+        return Err(format!("debug::{} expects 1 argument, got {}", name, args.len()));
+    }
+
+    let mut out = String::new();
+    dump_into(&mut out, &args[0], 0, &mut Vec::new());
+    Ok(out)
+}
+
+fn dump_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let out = dump_string(args, "dump")?;
+    writeln!(ctx.output(), "{}", out).map_err(|e| format!("debug::dump failed to write output: {}", e))?;
+    Ok(Value::Nil)
+}
+
+fn repr_nfn(args: &[Value]) -> Result<Value, String> {
+    Ok(Value::String(dump_string(args, "repr")?.into()))
+}
+
+// "int", "string", "collection" and so on, userdata by its own type name
+fn type_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("debug::type expects 1 argument, got {}", args.len()));
+    }
+
+    Ok(Value::String(args[0].type_name().into()))
+}
+
+// every variable visible from where it's called, by name. an inner variable
+// hides an outer one with the same name, like it would in code
+fn vars_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("debug::vars expects no arguments, got {}", args.len()));
+    }
+
+    let mut vars = CValue::new();
+    for (name, value) in ctx.interpreter().visible_variables() {
+        vars.insert(CKey::String(name.as_str().into()), value);
+    }
+    Ok(Value::collection(vars))
+}
+
+pub const DEBUG_MOD: Module = Module {
+    name: "debug",
+    funcs: &[
+        ("repr", repr_nfn),
+        ("type", type_nfn),
+    ],
+    ctx_funcs: &[
+        ("dump", dump_nfn),
+        ("vars", vars_nfn),
+    ],
+    method_types: &[],
+    consts: &[],
+};
@@ -0,0 +1,132 @@
+use crate::interpreter::Value;
+use crate::stdlib::Module;
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Output, Stdio};
+
+// the clipboard and desktop notifications, only built with the `desktop`
+// feature. there's no portable api for either so this runs whatever the
+// platform has: pbcopy/pbpaste and osascript on macos, powershell on windows,
+// wl-clipboard, xclip or xsel and notify-send everywhere else
+//
+// desktop::clipboard_set("copied!")
+// desktop::clipboard_get()             -> "copied!"
+// desktop::notify("build", "all done")
+
+// the first of these that's installed gets used
+#[cfg(target_os = "macos")]
+const CLIPBOARD_SET: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(target_os = "macos")]
+const CLIPBOARD_GET: &[&[&str]] = &[&["pbpaste"]];
+
+#[cfg(windows)]
+const CLIPBOARD_SET: &[&[&str]] = &[&["powershell", "-NoProfile", "-Command", "$input | Set-Clipboard"]];
+#[cfg(windows)]
+const CLIPBOARD_GET: &[&[&str]] = &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard -Raw"]];
+
+#[cfg(not(any(target_os = "macos", windows)))]
+const CLIPBOARD_SET: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+#[cfg(not(any(target_os = "macos", windows)))]
+const CLIPBOARD_GET: &[&[&str]] = &[
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-o"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+// runs the first command that exists, feeding it input if there is any
+fn run_first(name: &str, commands: &[&[&str]], input: Option<&str>, env: &[(&str, &str)]) -> Result<Output, String> {
+    for cmd in commands {
+        let mut command = Command::new(cmd[0]);
+        command.args(&cmd[1..]).envs(env.iter().copied()).stdout(Stdio::piped()).stderr(Stdio::piped());
+        command.stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() });
+
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("desktop::{} failed to start {}: {}", name, cmd[0], e)),
+        };
+        if let (Some(text), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin.write_all(text.as_bytes()).map_err(|e| format!("desktop::{} failed to write to {}: {}", name, cmd[0], e))?;
+        }
+
+        let output = child.wait_with_output().map_err(|e| format!("desktop::{} failed: {}", name, e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("desktop::{} failed, {} said: {}", name, cmd[0], stderr.trim()));
+        }
+        return Ok(output);
+    }
+
+    let tools: Vec<_> = commands.iter().map(|c| c[0]).collect();
+    Err(format!("desktop::{} needs one of these installed: {}", name, tools.join(", ")))
+}
+
+fn string_args<'a>(name: &str, args: &'a [Value], want: usize) -> Result<Vec<&'a str>, String> {
+    if args.len() != want {
+        return Err(format!("desktop::{} expects {} argument{}, got {}", name, want, if want == 1 { "" } else { "s" }, args.len()));
+    }
+
+    args.iter().map(|v| match v {
+        Value::String(s) => Ok(&**s),
+        other => Err(format!("desktop::{} expects strings, got {}", name, other.type_name())),
+    }).collect()
+}
+
+fn clipboard_get_nfn(args: &[Value]) -> Result<Value, String> {
+    string_args("clipboard_get", args, 0)?;
+    let output = run_first("clipboard_get", CLIPBOARD_GET, None, &[])?;
+    Ok(Value::String(String::from_utf8_lossy(&output.stdout).into()))
+}
+
+fn clipboard_set_nfn(args: &[Value]) -> Result<Value, String> {
+    let text = string_args("clipboard_set", args, 1)?[0];
+    run_first("clipboard_set", CLIPBOARD_SET, Some(text), &[])?;
+    Ok(Value::Nil)
+}
+
+// notify(title, body) pops up a notification, title and body go in as
+// arguments (or the environment on windows) so nothing in them needs escaping
+fn notify_nfn(args: &[Value]) -> Result<Value, String> {
+    let strings = string_args("notify", args, 2)?;
+    let (title, body) = (strings[0], strings[1]);
+
+    #[cfg(target_os = "macos")]
+    let commands: &[&[&str]] = &[&[
+        "osascript",
+        "-e", "on run argv",
+        "-e", "display notification (item 2 of argv) with title (item 1 of argv)",
+        "-e", "end run",
+        title, body,
+    ]];
+
+    #[cfg(windows)]
+    let commands: &[&[&str]] = &[&[
+        "powershell", "-NoProfile", "-Command",
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $n = New-Object System.Windows.Forms.NotifyIcon; \
+         $n.Icon = [System.Drawing.SystemIcons]::Information; $n.Visible = $true; \
+         $n.ShowBalloonTip(5000, $env:HEXI_NOTIFY_TITLE, $env:HEXI_NOTIFY_BODY, 'Info'); \
+         Start-Sleep -Seconds 5; $n.Dispose()",
+    ]];
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let commands: &[&[&str]] = &[&["notify-send", "--", title, body]];
+
+    run_first("notify", commands, None, &[("HEXI_NOTIFY_TITLE", title), ("HEXI_NOTIFY_BODY", body)])?;
+    Ok(Value::Nil)
+}
+
+pub const DESKTOP_MOD: Module = Module {
+    name: "desktop",
+    funcs: &[
+        ("clipboard_get", clipboard_get_nfn),
+        ("clipboard_set", clipboard_set_nfn),
+        ("notify", notify_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
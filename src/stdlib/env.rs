@@ -0,0 +1,143 @@
+use crate::interpreter::{CKey, CValue, Value};
+use crate::stdlib::Module;
+use std::env;
+use std::fs;
+
+// .env files, one KEY=value per line:
+//
+// # comments and blank lines are skipped
+// export DATABASE_URL=postgres://localhost/app
+// GREETING="hello\nworld"   <- double quotes understand \n, \t, \" and \\
+// RAW='$nothing \n special'  <- single quotes are taken as they are
+//
+// env::parse(text) gives the variables in a collection, env::load(path) also
+// sets them for this process (and anything it starts, see os::set_env).
+// variables that are already set win unless load gets true as a second argument
+
+fn unescape(v: &str) -> String {
+    let mut out = String::new();
+    let mut chars = v.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn parse(text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut vars = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected KEY=value, got '{}'", n + 1, line));
+        };
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+            return Err(format!("line {}: '{}' isn't a valid variable name", n + 1, key));
+        }
+
+        let value = value.trim();
+        let value = if let Some(rest) = value.strip_prefix('"') {
+            // the closing quote is the first one that isn't escaped
+            let mut end = None;
+            let mut escaped = false;
+            for (i, c) in rest.char_indices() {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => { end = Some(i); break; },
+                    _ => {},
+                }
+            }
+            let end = end.ok_or_else(|| format!("line {}: unterminated \" in the value of {}", n + 1, key))?;
+            unescape(&rest[..end])
+        } else if let Some(rest) = value.strip_prefix('\'') {
+            let end = rest.find('\'').ok_or_else(|| format!("line {}: unterminated ' in the value of {}", n + 1, key))?;
+            rest[..end].to_string()
+        } else {
+            // unquoted values can have a comment after them
+            match value.find(" #") {
+                Some(at) => value[..at].trim_end().to_string(),
+                None => value.to_string(),
+            }
+        };
+
+        if value.contains('\0') {
+            return Err(format!("line {}: the value of {} contains nul", n + 1, key));
+        }
+        vars.push((key.to_string(), value));
+    }
+
+    Ok(vars)
+}
+
+fn to_collection(vars: &[(String, String)]) -> Value {
+    let mut c = CValue::new();
+    for (k, v) in vars {
+        c.insert(CKey::String(k.as_str().into()), Value::String(v.as_str().into()));
+    }
+    Value::collection(c)
+}
+
+fn parse_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("env::parse expects 1 argument, got {}", args.len()));
+    }
+
+    let text = match &args[0] {
+        Value::String(s) => s,
+        other => return Err(format!("env::parse expects a string, got {}", other.type_name())),
+    };
+    let vars = parse(text).map_err(|e| format!("env::parse {}", e))?;
+    Ok(to_collection(&vars))
+}
+
+// load(path) or load(path, true) to replace variables that are already set,
+// gives back everything the file had in it
+fn load_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("env::load expects 1 or 2 arguments, got {}", args.len()));
+    }
+
+    let path = match &args[0] {
+        Value::String(s) => s,
+        other => return Err(format!("expected a string value for path argument, got {}", other)),
+    };
+    let replace = args.get(1).is_some_and(Value::is_truthy);
+
+    let text = fs::read_to_string(&**path).map_err(|e| format!("env::load failed to read '{}': {}", path, e))?;
+    let vars = parse(&text).map_err(|e| format!("env::load '{}' {}", path, e))?;
+
+    for (k, v) in &vars {
+        if replace || env::var_os(k).is_none() {
+            // same caveat as os::set_env about other threads reading the environment
+            unsafe { env::set_var(k, v) };
+        }
+    }
+    Ok(to_collection(&vars))
+}
+
+pub const ENV_MOD: Module = Module {
+    name: "env",
+    funcs: &[
+        ("parse", parse_nfn),
+        ("load", load_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
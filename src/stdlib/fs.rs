@@ -1,20 +1,32 @@
+use std::cell::RefCell;
 use std::fs;
-use crate::interpreter::Value;
-use crate::stdlib::Module;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::ast::Expr;
+use crate::error::HexiError;
+use crate::interpreter::{CValue, Value};
+use crate::native::{Ctx, MethodTable, UserMethod, Userdata};
+use crate::stdlib::{parse_callback, run_callback, Module};
 
 fn read_file_nfn(args: &[Value]) -> Result<Value, String> {
     if args.len() > 1 {
         return Err(format!("too many arguments for fs::read, got {}", args.len()));
     }
 
-    if let Some(p) = args.get(0) {
+    if let Some(p) = args.first() {
         // referenced from https://doc.rust-lang.org/book/ch12-02-reading-a-file.html
         let contents = match p {
-            Value::String(s) => fs::read_to_string(s).map_err(|e| format!("fs::read failed to read input: {}", e))?,
+            Value::String(s) => fs::read_to_string(&**s).map_err(|e| format!("fs::read failed to read input: {}", e))?,
             _ => return Err(format!("expected a string value for path argument, got {}", p))
         };
 
-        Ok(Value::String(contents))
+        Ok(Value::String(contents.into()))
     } else {
         Ok(Value::Nil)
     }
@@ -40,10 +52,592 @@ fn write_file_nfn(args: &[Value]) -> Result<Value, String> {
     Ok(Value::Bool(true))
 }
 
+fn read_bytes_nfn(args: &[Value]) -> Result<Value, String> {
+    let path = path_arg("read_bytes", args, 1)?;
+    let contents = fs::read(path).map_err(|e| format!("fs::read_bytes failed to read '{}': {}", path, e))?;
+    Ok(Value::Bytes(contents.into()))
+}
+
+fn write_bytes_nfn(args: &[Value]) -> Result<Value, String> {
+    let path = path_arg("write_bytes", args, 2)?;
+    let contents = match &args[1] {
+        Value::Bytes(b) => b,
+        other => return Err(format!("fs::write_bytes expects bytes, got {}", other.type_name())),
+    };
+
+    fs::write(path, contents).map_err(|e| format!("fs::write_bytes failed to write '{}': {}", path, e))?;
+    Ok(Value::Bool(true))
+}
+
+fn path_arg<'a>(name: &str, args: &'a [Value], want: usize) -> Result<&'a str, String> {
+    if args.len() != want {
+        return Err(format!("fs::{} expects {} argument{}, got {}", name, want, if want == 1 { "" } else { "s" }, args.len()));
+    }
+
+    match &args[0] {
+        Value::String(s) => Ok(s),
+        other => Err(format!("expected a string value for path argument, got {}", other)),
+    }
+}
+
+fn second_path_arg(args: &[Value]) -> Result<&str, String> {
+    match &args[1] {
+        Value::String(s) => Ok(s),
+        other => Err(format!("expected a string value for path argument, got {}", other)),
+    }
+}
+
+fn exists_nfn(args: &[Value]) -> Result<Value, String> {
+    let path = path_arg("exists", args, 1)?;
+    Ok(Value::Bool(Path::new(path).exists()))
+}
+
+// just the names, sorted since the os hands them out in no particular order
+fn list_dir_nfn(args: &[Value]) -> Result<Value, String> {
+    let path = path_arg("list_dir", args, 1)?;
+    let err = |e: std::io::Error| format!("fs::list_dir failed to read '{}': {}", path, e);
+
+    let mut names = Vec::new();
+    for entry in fs::read_dir(path).map_err(err)? {
+        names.push(entry.map_err(err)?.file_name().to_string_lossy().into_owned());
+    }
+    names.sort();
+
+    Ok(Value::collection(CValue::from_array(names.into_iter().map(|n| Value::String(n.into())).collect())))
+}
+
+// makes the missing parents too, and is fine with the directory already existing
+fn mkdir_nfn(args: &[Value]) -> Result<Value, String> {
+    let path = path_arg("mkdir", args, 1)?;
+    fs::create_dir_all(path).map_err(|e| format!("fs::mkdir failed to create '{}': {}", path, e))?;
+    Ok(Value::Bool(true))
+}
+
+// files, or directories along with everything in them
+fn remove_nfn(args: &[Value]) -> Result<Value, String> {
+    let path = path_arg("remove", args, 1)?;
+    // symlink_metadata so a link to a directory removes the link, not what's behind it
+    let meta = fs::symlink_metadata(path).map_err(|e| format!("fs::remove failed to remove '{}': {}", path, e))?;
+
+    let result = if meta.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) };
+    result.map_err(|e| format!("fs::remove failed to remove '{}': {}", path, e))?;
+    Ok(Value::Bool(true))
+}
+
+// files only, overwrites whatever is at the destination
+fn copy_nfn(args: &[Value]) -> Result<Value, String> {
+    let from = path_arg("copy", args, 2)?;
+    let to = second_path_arg(args)?;
+    fs::copy(from, to).map_err(|e| format!("fs::copy failed to copy '{}' to '{}': {}", from, to, e))?;
+    Ok(Value::Bool(true))
+}
+
+fn rename_nfn(args: &[Value]) -> Result<Value, String> {
+    let from = path_arg("rename", args, 2)?;
+    let to = second_path_arg(args)?;
+    fs::rename(from, to).map_err(|e| format!("fs::rename failed to move '{}' to '{}': {}", from, to, e))?;
+    Ok(Value::Bool(true))
+}
+
+fn content_arg(name: &str, args: &[Value]) -> Result<String, String> {
+    match &args[1] {
+        Value::String(s) => Ok(s.to_string()),
+        other => Err(format!("fs::{} expects string content, got {}", name, other.type_name())),
+    }
+}
+
+// creates the file if it isn't there yet
+fn append_nfn(args: &[Value]) -> Result<Value, String> {
+    let path = path_arg("append", args, 2)?;
+    let content = content_arg("append", args)?;
+
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(path)
+        .map_err(|e| format!("fs::append failed to open '{}': {}", path, e))?;
+    file.write_all(content.as_bytes()).map_err(|e| format!("fs::append failed to write '{}': {}", path, e))?;
+    Ok(Value::Bool(true))
+}
+
+// writes next to the target first and renames over it, so anything reading
+// the file sees either the old contents or the new ones, never half of each
+fn write_atomic_nfn(args: &[Value]) -> Result<Value, String> {
+    let path = Path::new(path_arg("write_atomic", args, 2)?);
+    let content = content_arg("write_atomic", args)?;
+
+    if path.file_name().is_none() {
+        return Err(format!("fs::write_atomic needs a file path, got '{}'", path.display()));
+    }
+
+    write_atomic(path, content.as_bytes()).map_err(|e| format!("fs::write_atomic failed to write '{}': {}", path.display(), e))?;
+    Ok(Value::Bool(true))
+}
+
+// also what store uses to save
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let name = path.file_name().ok_or(std::io::ErrorKind::InvalidInput)?;
+    // same directory so the rename can't end up crossing file systems
+    let temp = path.with_file_name(format!(".{}.{}.tmp", name.to_string_lossy(), std::process::id()));
+
+    let written = fs::File::create(&temp)
+        .and_then(|mut f| {
+            f.write_all(content)?;
+            f.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp, path));
+
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written
+}
+
+// [size = bytes, modified = unix seconds, is_dir = .., is_file = ..], links are followed
+fn stat_nfn(args: &[Value]) -> Result<Value, String> {
+    let path = path_arg("stat", args, 1)?;
+    let meta = fs::metadata(path).map_err(|e| format!("fs::stat failed to read '{}': {}", path, e))?;
+
+    // not every platform keeps a modified time
+    let modified = meta.modified().ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(Value::Nil, |d| Value::Int(d.as_secs() as i64));
+
+    let mut stat = HashMap::new();
+    stat.insert("size".to_string(), Value::Int(meta.len() as i64));
+    stat.insert("modified".to_string(), modified);
+    stat.insert("is_dir".to_string(), Value::Bool(meta.is_dir()));
+    stat.insert("is_file".to_string(), Value::Bool(meta.is_file()));
+    Ok(Value::collection(CValue::from_object(stat)))
+}
+
+fn walk_into(dir: &Path, files: &mut Vec<String>) -> std::io::Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        // file_type doesn't follow links, so a link back up the tree can't loop forever
+        if entry.file_type()?.is_dir() {
+            walk_into(&entry.path(), files)?;
+        } else {
+            files.push(entry.path().to_string_lossy().into_owned());
+        }
+    }
+    Ok(())
+}
+
+// every file anywhere under the directory as a path starting with it, in
+// sorted order. directories themselves aren't listed, symlinks are but not
+// followed
+fn walk_nfn(args: &[Value]) -> Result<Value, String> {
+    let path = path_arg("walk", args, 1)?;
+
+    let mut files = Vec::new();
+    walk_into(Path::new(path), &mut files).map_err(|e| format!("fs::walk failed to read '{}': {}", path, e))?;
+    Ok(Value::collection(CValue::from_array(files.into_iter().map(|f| Value::String(f.into())).collect())))
+}
+
+// what fs::open gives back. reads go through a buffer so read_line doesn't hit
+// the disk once per byte, the handle is None once closed
+type Handle = BufReader<fs::File>;
+
+struct File {
+    path: String,
+    handle: RefCell<Option<Handle>>,
+}
+
+// fs::open(path, mode) for going through a file a line at a time instead of
+// reading it all in with fs::read
+//
+// val f = fs::open("big.log")
+// f.read_line()    -> the next line without its ending, nil at the end
+// f.write("text")  -> strings or bytes, written as is
+// f.seek(0)        -> back to the start
+// f.close()
+//
+// modes are "r" (the default), "w" to truncate or create, "a" to append and
+// "r+" to read and write an existing file
+fn open_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("fs::open expects 1 or 2 arguments, got {}", args.len()));
+    }
+
+    let path = match &args[0] {
+        Value::String(s) => s,
+        other => return Err(format!("expected a string value for path argument, got {}", other)),
+    };
+    let mode = match args.get(1) {
+        None => "r",
+        Some(Value::String(m)) => m,
+        Some(other) => return Err(format!("fs::open expects a mode string, got {}", other.type_name())),
+    };
+
+    let mut options = fs::OpenOptions::new();
+    match mode {
+        "r" => options.read(true),
+        "w" => options.write(true).create(true).truncate(true),
+        "a" => options.append(true).create(true),
+        "r+" => options.read(true).write(true),
+        _ => return Err(format!("fs::open mode must be \"r\", \"w\", \"a\" or \"r+\", got \"{}\"", mode)),
+    };
+
+    let file = options.open(&**path).map_err(|e| format!("fs::open failed to open '{}': {}", path, e))?;
+    let file = File { path: path.to_string(), handle: RefCell::new(Some(BufReader::new(file))) };
+    Ok(Value::userdata(Userdata::new("file", file).with_methods(FILE_METHODS.with(Rc::clone))))
+}
+
+fn file_method(f: fn(&File, &mut Handle, &[Value]) -> Result<Value, String>) -> UserMethod {
+    Rc::new(move |_: &mut Ctx, u: &Userdata, args: &[Value]| {
+        let file = u.downcast::<File>().expect("file methods are only given to files");
+        let mut handle = file.handle.borrow_mut();
+        let handle = handle.as_mut().ok_or_else(|| HexiError::runtime(format!("file '{}' is already closed", file.path)))?;
+        f(file, handle, args).map_err(HexiError::runtime)
+    })
+}
+
+fn file_read_line(file: &File, handle: &mut Handle, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("file.read_line expects no arguments, got {}", args.len()));
+    }
+
+    let mut line = String::new();
+    let n = handle.read_line(&mut line).map_err(|e| format!("failed to read from '{}': {}", file.path, e))?;
+    if n == 0 {
+        return Ok(Value::Nil);
+    }
+
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    Ok(Value::String(line.into()))
+}
+
+fn file_write(file: &File, handle: &mut Handle, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("file.write expects 1 argument, got {}", args.len()));
+    }
+
+    // whatever the buffer read ahead has to be given back first, or the write
+    // lands past where the script thinks it is
+    let failed = |e: std::io::Error| format!("failed to write to '{}': {}", file.path, e);
+    let pos = handle.stream_position().map_err(failed)?;
+    handle.seek(SeekFrom::Start(pos)).map_err(failed)?;
+    let out = handle.get_mut();
+    match &args[0] {
+        Value::Bytes(b) => out.write_all(b),
+        Value::String(s) => out.write_all(s.as_bytes()),
+        other => out.write_all(other.to_string().as_bytes()),
+    }.map_err(failed)?;
+    Ok(Value::Nil)
+}
+
+// to a byte offset from the start
+fn file_seek(file: &File, handle: &mut Handle, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("file.seek expects 1 argument, got {}", args.len()));
+    }
+
+    let offset = match &args[0] {
+        Value::Int(i) => u64::try_from(*i).map_err(|_| format!("file.seek expects a non negative offset, got {}", i))?,
+        other => return Err(format!("file.seek expects an int offset, got {}", other.type_name())),
+    };
+
+    handle.seek(SeekFrom::Start(offset)).map_err(|e| format!("failed to seek in '{}': {}", file.path, e))?;
+    Ok(Value::Nil)
+}
+
+fn file_close(file: &File, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("file.close expects no arguments, got {}", args.len()));
+    }
+
+    // dropping it is what closes it, and any use after this errors
+    file.handle.borrow_mut().take();
+    Ok(Value::Nil)
+}
+
+thread_local! {
+    static FILE_METHODS: Rc<MethodTable> = {
+        let mut methods = MethodTable::new();
+        methods.insert("read_line", file_method(file_read_line));
+        methods.insert("write", file_method(file_write));
+        methods.insert("seek", file_method(file_seek));
+        // closing a closed file is fine, so this one skips the check
+        methods.insert("close", Rc::new(|_: &mut Ctx, u: &Userdata, args: &[Value]| {
+            let file = u.downcast::<File>().expect("file methods are only given to files");
+            file_close(file, args).map_err(HexiError::runtime)
+        }));
+        Rc::new(methods)
+    };
+}
+
+// the paths fs::temp_file and fs::temp_dir handed out, they all get removed
+// when the interpreter holding this is dropped (so when the script finishes)
+#[derive(Default)]
+pub(crate) struct TempPaths(Vec<PathBuf>);
+
+// every interpreter's temp paths together, for remove_temp_paths
+static LIVE_TEMP_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+impl TempPaths {
+    pub(crate) fn add(&mut self, path: PathBuf) {
+        LIVE_TEMP_PATHS.lock().unwrap_or_else(|e| e.into_inner()).push(path.clone());
+        self.0.push(path);
+    }
+}
+
+impl Drop for TempPaths {
+    fn drop(&mut self) {
+        LIVE_TEMP_PATHS.lock().unwrap_or_else(|e| e.into_inner()).retain(|p| !self.0.contains(p));
+        for path in self.0.drain(..).rev() {
+            remove_temp_path(&path);
+        }
+    }
+}
+
+// best effort, the script may have removed or moved them already
+fn remove_temp_path(path: &Path) {
+    let _ = if path.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) };
+}
+
+// std::process::exit skips the drops that would clean up after fs::temp_file
+// and fs::temp_dir, so anything that exits (the cli on an error or ctrl+c)
+// calls this first. it's safe from any thread, and removes the temp paths of
+// every interpreter in the process
+pub fn remove_temp_paths() {
+    let paths = std::mem::take(&mut *LIVE_TEMP_PATHS.lock().unwrap_or_else(|e| e.into_inner()));
+    for path in paths.iter().rev() {
+        remove_temp_path(path);
+    }
+}
+
+// a name nothing else should be using, the pid keeps separate runs apart and
+// the counter separate calls. the time is there in case a pid gets reused
+// while an old run's files are still around
+fn temp_path(suffix: &str) -> PathBuf {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+    std::env::temp_dir().join(format!("hexi-{}-{}-{}{}", std::process::id(), nanos, n, suffix))
+}
+
+fn suffix_arg<'a>(name: &str, args: &'a [Value]) -> Result<&'a str, String> {
+    match args {
+        [] => Ok(""),
+        [Value::String(s)] if !s.contains(['/', '\\']) => Ok(s),
+        [Value::String(s)] => Err(format!("fs::{} suffix can't contain a path separator, got '{}'", name, s)),
+        [other] => Err(format!("fs::{} expects a suffix string, got {}", name, other.type_name())),
+        _ => Err(format!("fs::{} expects 0 or 1 arguments, got {}", name, args.len())),
+    }
+}
+
+// fs::temp_file() or fs::temp_file(".json") creates an empty file in the
+// system's temp directory and gives back its path
+fn temp_file_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let suffix = suffix_arg("temp_file", args)?;
+    let path = temp_path(suffix);
+    fs::OpenOptions::new().write(true).create_new(true).open(&path)
+        .map_err(|e| format!("fs::temp_file failed to create '{}': {}", path.display(), e))?;
+
+    let result = Value::String(path.to_string_lossy().into());
+    ctx.interpreter().add_temp_path(path);
+    Ok(result)
+}
+
+// same for an empty directory, everything put in it goes with it
+fn temp_dir_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let suffix = suffix_arg("temp_dir", args)?;
+    let path = temp_path(suffix);
+    fs::create_dir(&path).map_err(|e| format!("fs::temp_dir failed to create '{}': {}", path.display(), e))?;
+
+    let result = Value::String(path.to_string_lossy().into());
+    ctx.interpreter().add_temp_path(path);
+    Ok(result)
+}
+
+// fs::watch(path, code) runs a bit of code whenever something under path is
+// created, modified or deleted, once fs::watch_run() hands control to it:
+//
+// fs::watch("src", "io::println(event, path)")
+// fs::watch("config.ini", "fs::watch_stop()")
+// fs::watch_run()
+//
+// inside the code `event` is "create", "modify" or "delete" and `path` is the
+// file it happened to. watching works by looking at modified times and sizes
+// every quarter of a second, so a change that leaves both the same goes
+// unnoticed. run keeps going until nothing is watched, fs::watch_stop() is
+// called or ctrl+c, and an error in the code ends it there and then
+
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+// modified time and size of every file a watch covers
+type Snapshot = HashMap<PathBuf, (Option<SystemTime>, u64)>;
+
+struct Watch {
+    id: i64,
+    path: PathBuf,
+    code: Rc<Vec<Expr>>,
+    seen: Snapshot,
+}
+
+// every interpreter has its own
+#[derive(Default)]
+pub(crate) struct Watches {
+    list: Vec<Watch>,
+    next_id: i64,
+    stopped: bool,
+}
+
+// a path that isn't there (yet) is an empty snapshot, for a directory it's
+// every file under it the way fs::walk finds them
+fn snapshot(path: &Path) -> Snapshot {
+    let mut files = Vec::new();
+    if path.is_dir() {
+        // whatever can't be read right now shows up on a later look
+        let _ = walk_into(path, &mut files);
+    } else {
+        files.push(path.to_string_lossy().into_owned());
+    }
+
+    files.into_iter().filter_map(|f| {
+        let meta = fs::metadata(&f).ok()?;
+        Some((PathBuf::from(f), (meta.modified().ok(), meta.len())))
+    }).collect()
+}
+
+// sorted by path so events for a batch of changes come in a stable order
+fn changes(before: &Snapshot, after: &Snapshot) -> Vec<(&'static str, PathBuf)> {
+    let mut events: Vec<_> = after.iter().filter_map(|(path, now)| match before.get(path) {
+        None => Some(("create", path.clone())),
+        Some(was) if was != now => Some(("modify", path.clone())),
+        Some(_) => None,
+    }).collect();
+    events.extend(before.keys().filter(|p| !after.contains_key(*p)).map(|p| ("delete", p.clone())));
+    events.sort_by(|a, b| a.1.cmp(&b.1));
+    events
+}
+
+// gives back an id for fs::unwatch
+fn watch_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("fs::watch expects 2 arguments, got {}", args.len()));
+    }
+
+    let path = match &args[0] {
+        Value::String(s) => PathBuf::from(&**s),
+        other => return Err(format!("expected a string value for path argument, got {}", other)),
+    };
+    let code = parse_callback("fs::watch", &args[1])?;
+
+    let seen = snapshot(&path);
+    let watches = ctx.interpreter().watches();
+    watches.next_id += 1;
+    let id = watches.next_id;
+    watches.list.push(Watch { id, path, code: Rc::new(code), seen });
+    Ok(Value::Int(id))
+}
+
+// true if there was a watch with that id left to remove
+fn unwatch_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("fs::unwatch expects 1 argument, got {}", args.len()));
+    }
+
+    let Value::Int(id) = args[0] else {
+        return Err(format!("fs::unwatch expects a watch id, got {}", args[0].type_name()));
+    };
+    let list = &mut ctx.interpreter().watches().list;
+    let before = list.len();
+    list.retain(|w| w.id != id);
+    Ok(Value::Bool(list.len() != before))
+}
+
+// ends fs::watch_run once the code that called this is done, the watches
+// themselves stay for the next run
+fn watch_stop_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("fs::watch_stop expects no arguments, got {}", args.len()));
+    }
+
+    ctx.interpreter().watches().stopped = true;
+    Ok(Value::Nil)
+}
+
+fn watch_run_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("fs::watch_run expects no arguments, got {}", args.len()));
+    }
+
+    let interrupt = ctx.interpreter().interrupt_handle();
+    ctx.interpreter().watches().stopped = false;
+    'run: loop {
+        let ids: Vec<i64> = ctx.interpreter().watches().list.iter().map(|w| w.id).collect();
+        if ids.is_empty() {
+            break;
+        }
+
+        for id in ids {
+            // the code run for an earlier watch may have removed this one
+            let Some(path) = ctx.interpreter().watches().list.iter().find(|w| w.id == id).map(|w| w.path.clone()) else {
+                continue;
+            };
+            let now = snapshot(&path);
+            let Some(watch) = ctx.interpreter().watches().list.iter_mut().find(|w| w.id == id) else {
+                continue;
+            };
+            let events = changes(&watch.seen, &now);
+            watch.seen = now;
+            let code = watch.code.clone();
+
+            for (event, path) in events {
+                let bindings = [("event", Value::String(event.into())), ("path", Value::String(path.to_string_lossy().into()))];
+                run_callback(ctx, &code, &bindings)?;
+                if ctx.interpreter().watches().stopped {
+                    break 'run;
+                }
+            }
+        }
+
+        // sleeping in slices like time::sleep, so ctrl+c still gets through
+        let until = Instant::now() + WATCH_INTERVAL;
+        while Instant::now() < until {
+            if interrupt.load(Ordering::Relaxed) {
+                break 'run;  // the interpreter reports it at the next expression
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    Ok(Value::Nil)
+}
+
 pub const FS_MOD: Module = Module {
     name: "fs",
     funcs: &[
         ("read", read_file_nfn),
         ("write", write_file_nfn),
+        ("read_bytes", read_bytes_nfn),
+        ("write_bytes", write_bytes_nfn),
+        ("append", append_nfn),
+        ("write_atomic", write_atomic_nfn),
+        ("exists", exists_nfn),
+        ("list_dir", list_dir_nfn),
+        ("mkdir", mkdir_nfn),
+        ("remove", remove_nfn),
+        ("copy", copy_nfn),
+        ("rename", rename_nfn),
+        ("stat", stat_nfn),
+        ("walk", walk_nfn),
+        ("open", open_nfn),
+    ],
+    ctx_funcs: &[
+        ("temp_file", temp_file_nfn),
+        ("temp_dir", temp_dir_nfn),
+        ("watch", watch_nfn),
+        ("unwatch", unwatch_nfn),
+        ("watch_stop", watch_stop_nfn),
+        ("watch_run", watch_run_nfn),
     ],
+    method_types: &[],
+    consts: &[],
 };
\ No newline at end of file
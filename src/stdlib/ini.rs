@@ -0,0 +1,89 @@
+use crate::interpreter::{CKey, CValue, Value};
+use crate::stdlib::Module;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// ini::parse(text) turns
+//
+// name = app
+// [server]
+// host = localhost
+// port = 8080   ; comments start with ; or #
+//
+// into [name = "app", server = [host = "localhost", port = "8080"]]. values
+// stay strings (string::parse them where numbers are wanted), surrounding
+// quotes are dropped and a section that shows up twice is merged
+
+fn unquote(v: &str) -> &str {
+    for q in ['"', '\''] {
+        if v.len() >= 2 && v.starts_with(q) && v.ends_with(q) {
+            return &v[1..v.len() - 1];
+        }
+    }
+    v
+}
+
+fn parse_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("ini::parse expects 1 argument, got {}", args.len()));
+    }
+
+    let text = match &args[0] {
+        Value::String(s) => s,
+        other => return Err(format!("ini::parse expects a string, got {}", other.type_name())),
+    };
+
+    let root = Rc::new(RefCell::new(CValue::new()));
+    let mut section = root.clone();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with([';', '#']) {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name.strip_suffix(']').ok_or_else(|| format!("ini::parse line {}: section header is missing its ]", n + 1))?.trim();
+            let key = CKey::String(name.into());
+            let existing = root.borrow().get(&key).cloned();
+            section = match existing {
+                Some(Value::Collection(s)) => s,
+                Some(_) => return Err(format!("ini::parse line {}: '{}' is both a key and a section", n + 1, name)),
+                None => {
+                    let s = Rc::new(RefCell::new(CValue::new()));
+                    root.borrow_mut().insert(key, Value::Collection(s.clone()));
+                    s
+                },
+            };
+            continue;
+        }
+
+        let Some(split) = line.find(['=', ':']) else {
+            return Err(format!("ini::parse line {}: expected key = value, got '{}'", n + 1, line));
+        };
+        let key = line[..split].trim();
+        let mut value = line[split + 1..].trim();
+        // a comment after an unquoted value needs whitespace in front of it
+        if !value.starts_with(['"', '\''])
+            && let Some(at) = value.find(" ;").or_else(|| value.find(" #"))
+        {
+            value = value[..at].trim_end();
+        }
+
+        if key.is_empty() {
+            return Err(format!("ini::parse line {}: missing key before the =", n + 1));
+        }
+        section.borrow_mut().insert(CKey::String(key.into()), Value::String(unquote(value).into()));
+    }
+
+    Ok(Value::Collection(root))
+}
+
+pub const INI_MOD: Module = Module {
+    name: "ini",
+    funcs: &[
+        ("parse", parse_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
@@ -1,46 +1,84 @@
 use crate::interpreter::Value;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::interpreter::CValue;
+use crate::native::Ctx;
 use super::Module;
+#[cfg(not(target_arch = "wasm32"))]
+use super::string::to_number_nfn;
+use std::io::Write;
 
-fn print_nfn(args: &[Value]) -> Result<Value, String> {
+// everything goes through the interpreter's output and input, which are the
+// real stdout/stdin unless the host swapped them out
+
+fn write_args(out: &mut dyn Write, args: &[Value]) -> Result<(), String> {
     for (i, arg) in args.iter().enumerate() {
         if i > 0 {
-            print!(" ");
+            write!(out, " ").map_err(|e| format!("io: failed to write output: {}", e))?;
         }
 
-        print!("{}", arg);
+        write!(out, "{}", arg).map_err(|e| format!("io: failed to write output: {}", e))?;
     }
 
-    println!();
+    Ok(())
+}
+
+// print leaves the cursor where it is (flushing, so a prompt shows up before
+// io::input waits), println ends the line
+fn print_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    write_args(ctx.output(), args)?;
+    ctx.output().flush().map_err(|e| format!("io::print failed to write output: {}", e))?;
     Ok(Value::Nil)
 }
 
-fn println_nfn(args: &[Value]) -> Result<Value, String> {
-    for (i, arg) in args.iter().enumerate() {
-        if i > 0 {
-            print!(" ");
-        }
+fn println_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    write_args(ctx.output(), args)?;
+    writeln!(ctx.output()).map_err(|e| format!("io::println failed to write output: {}", e))?;
+    Ok(Value::Nil)
+}
 
-        print!("{}", arg);
+// exactly one value and nothing else, no spaces, no newline. bytes go out as
+// the raw bytes they are
+fn write_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("io::write expects 1 argument, got {}", args.len()));
     }
 
-    println!();
+    let out = ctx.output();
+    let written = match &args[0] {
+        Value::Bytes(b) => out.write_all(b),
+        v => write!(out, "{}", v),
+    };
+    written.and_then(|_| out.flush()).map_err(|e| format!("io::write failed to write output: {}", e))?;
+    Ok(Value::Nil)
+}
+
+// print and println for stderr, so diagnostics stay out of piped output
+fn eprint_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    write_args(ctx.error_output(), args)?;
+    ctx.error_output().flush().map_err(|e| format!("io::eprint failed to write output: {}", e))?;
     Ok(Value::Nil)
 }
 
-fn input_nfn(args: &[Value]) -> Result<Value, String> {
+fn eprintln_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    write_args(ctx.error_output(), args)?;
+    writeln!(ctx.error_output()).map_err(|e| format!("io::eprintln failed to write output: {}", e))?;
+    Ok(Value::Nil)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn input_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
     if args.len() > 1 {
         return Err(format!("too many arguments for function io::input, got {}", args.len()));
     }
 
     // if a prompt is given p[rint it
-    if let Some(p) = args.get(0) {
-        print!("{}", p);
-        use std::io::{self, Write};
-        io::stdout().flush().unwrap();
+    if let Some(p) = args.first() {
+        let out = ctx.output();
+        write!(out, "{}", p).and_then(|_| out.flush()).map_err(|e| format!("io::input[error] failed to write prompt: {}", e))?;
     }
 
     let mut input = String::new();
-    std::io::stdin().read_line(&mut input).map_err(|e| format!("io::input[error] failed to read input: {}", e))?;
+    ctx.input().read_line(&mut input).map_err(|e| format!("io::input[error] failed to read input: {}", e))?;
 
     // remove any trailin chars such as newlines or carrier
     // this is because raw input is like this:
@@ -53,15 +91,103 @@ fn input_nfn(args: &[Value]) -> Result<Value, String> {
         }
     }
 
-    Ok(Value::String(input))
+    Ok(Value::String(input.into()))
+}
+
+// one line without its line ending, None once the input runs out
+#[cfg(not(target_arch = "wasm32"))]
+fn read_line(ctx: &mut Ctx, name: &str) -> Result<Option<String>, String> {
+    let mut line = String::new();
+    let n = ctx.input().read_line(&mut line).map_err(|e| format!("io::{} failed to read input: {}", name, e))?;
+    if n == 0 {
+        return Ok(None);
+    }
+
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    Ok(Some(line))
+}
+
+// everything left on stdin as one string, for `cat x | hexi f.hx`
+#[cfg(not(target_arch = "wasm32"))]
+fn read_all_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("io::read_all expects no arguments, got {}", args.len()));
+    }
+
+    let mut all = String::new();
+    ctx.input().read_to_string(&mut all).map_err(|e| format!("io::read_all failed to read input: {}", e))?;
+    Ok(Value::String(all.into()))
+}
+
+// every line left on stdin, line endings dropped. there's nothing to iterate
+// lazily with so it's a collection
+#[cfg(not(target_arch = "wasm32"))]
+fn read_lines_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("io::read_lines expects no arguments, got {}", args.len()));
+    }
+
+    let mut lines = Vec::new();
+    while let Some(line) = read_line(ctx, "read_lines")? {
+        lines.push(Value::String(line.into()));
+    }
+    Ok(Value::collection(CValue::from_array(lines)))
+}
+
+// keeps asking until it gets something string::parse takes. running out of
+// input is an error, there's no number coming at that point
+#[cfg(not(target_arch = "wasm32"))]
+fn read_number_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() > 1 {
+        return Err(format!("io::read_number expects 0 or 1 arguments, got {}", args.len()));
+    }
+
+    loop {
+        if let Some(p) = args.first() {
+            let out = ctx.output();
+            write!(out, "{}", p).and_then(|_| out.flush()).map_err(|e| format!("io::read_number failed to write prompt: {}", e))?;
+        }
+
+        let Some(line) = read_line(ctx, "read_number")? else {
+            return Err("io::read_number ran out of input before getting a number".to_string());
+        };
+
+        match to_number_nfn(&[Value::String(line.as_str().into())])? {
+            Value::Number(n) if !n.is_finite() => {},
+            Value::Nil => {},
+            n => return Ok(n),
+        }
+
+        let out = ctx.output();
+        writeln!(out, "'{}' isn't a number, try again", line.trim()).map_err(|e| format!("io::read_number failed to write output: {}", e))?;
+    }
 }
 
 
 pub const IO_MOD: Module = Module {
     name: "io",
-    funcs: &[
+    funcs: &[],
+    ctx_funcs: &[
         ("print", print_nfn),
         ("println", println_nfn),
+        ("write", write_nfn),
+        ("eprint", eprint_nfn),
+        ("eprintln", eprintln_nfn),
+        // or a terminal to read from
+        #[cfg(not(target_arch = "wasm32"))]
         ("input", input_nfn),
+        #[cfg(not(target_arch = "wasm32"))]
+        ("read_all", read_all_nfn),
+        #[cfg(not(target_arch = "wasm32"))]
+        ("read_lines", read_lines_nfn),
+        #[cfg(not(target_arch = "wasm32"))]
+        ("read_number", read_number_nfn),
     ],
-};
\ No newline at end of file
+    method_types: &[],
+    consts: &[],
+};
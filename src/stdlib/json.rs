@@ -1,6 +1,7 @@
-use crate::interpreter::{CKey, CValue, Value};
+use crate::interpreter::Value;
 use crate::stdlib::Module;
-use serde_json::{self, Value as JsonValue};
+
+// Value implements serde's traits (see serialize.rs), so this is just serde_json
 
 fn parse_nfn(args: &[Value]) -> Result<Value, String> {
     if args.len() != 1 {
@@ -12,37 +13,7 @@ fn parse_nfn(args: &[Value]) -> Result<Value, String> {
         _ => return Err("expected a JSON string".to_string())
     };
 
-    let parsed: JsonValue = serde_json::from_str(content.as_str()).map_err(|e| format!("error while parsing json: {}", e))?;
-
-    fn json_to_value(json: JsonValue) -> Result<Value, String> {
-        match json {
-            JsonValue::Object(m) => {
-                let mut hashmap = CValue::new();
-                for (key, value) in m {
-                    let val = json_to_value(value)?;
-                    hashmap.insert(CKey::String(key), val);
-                }
-
-                Ok(Value::Collection(hashmap))
-            }
-            JsonValue::Array(a) => {
-                let mut c = CValue::new();
-                for (i, json_val) in a.into_iter().enumerate() {
-                    let val = json_to_value(json_val)?;
-                    c.insert(CKey::Index(i), val);
-                }
-
-                c.size = c.entries.len();
-                Ok(Value::Collection(c))
-            }
-            JsonValue::String(s) => Ok(Value::String(s)),
-            JsonValue::Number(n) => Ok(Value::Number(n.as_f64().ok_or("invalid number format")?)),
-            JsonValue::Bool(b) => Ok(Value::Bool(b)),
-            JsonValue::Null => Ok(Value::Nil),
-        }
-    }
-
-    json_to_value(parsed)
+    serde_json::from_str(content).map_err(|e| format!("error while parsing json: {}", e))
 }
 
 pub const JSON_MOD: Module = Module {
@@ -50,4 +21,7 @@ pub const JSON_MOD: Module = Module {
     funcs: &[
         ("parse", crate::stdlib::json::parse_nfn),
     ],
-};
\ No newline at end of file
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
@@ -0,0 +1,196 @@
+use crate::interpreter::{CKey, Value};
+use crate::native::Ctx;
+use crate::stdlib::Module;
+use chrono::{DateTime, SecondsFormat};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// log::info("fetched", [url = u, ms = 120]) writes a line like
+//
+// 2026-10-16T09:30:00.123Z INFO  fetched url=https://example.com ms=120
+//
+// to stderr, so it never mixes with what the script prints. any number of
+// keyed collections can follow the message, their entries become fields
+//
+// only messages at or above the level get written, that's info unless the
+// HEXI_LOG environment variable or log::set_level says otherwise ("debug",
+// "info", "warn", "error" or "off"). log::set_format("json") writes one json
+// object per line instead, for feeding into other tools
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Off,
+}
+
+impl Level {
+    fn parse(name: &str) -> Option<Level> {
+        match name.to_ascii_lowercase().as_str() {
+            "debug" => Some(Level::Debug),
+            "info" => Some(Level::Info),
+            "warn" => Some(Level::Warn),
+            "error" => Some(Level::Error),
+            "off" => Some(Level::Off),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+            Level::Off => "off",
+        }
+    }
+}
+
+// each interpreter keeps its own, the level is read from HEXI_LOG the first
+// time something gets logged unless a script set one before that
+#[derive(Debug, Default)]
+pub(crate) struct LogConfig {
+    level: Option<Level>,
+    json: bool,
+}
+
+impl LogConfig {
+    fn level(&mut self) -> Level {
+        *self.level.get_or_insert_with(|| {
+            std::env::var("HEXI_LOG").ok().and_then(|l| Level::parse(&l)).unwrap_or(Level::Info)
+        })
+    }
+}
+
+fn timestamp() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    DateTime::from_timestamp(now.as_secs() as i64, now.subsec_nanos())
+        .map_or_else(String::new, |d| d.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+// strings with spaces, quotes or an = in them get quoted so the line still
+// splits back into fields
+fn text_value(v: &Value) -> String {
+    match v {
+        Value::String(s) if s.is_empty() || s.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') => format!("{:?}", s),
+        other => other.to_string(),
+    }
+}
+
+fn field_name(k: &CKey) -> String {
+    match k.to_value() {
+        Value::String(s) => s.to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn write_log(ctx: &mut Ctx, level: Level, args: &[Value]) -> Result<Value, String> {
+    let name = level.name();
+    let Some(msg) = args.first() else {
+        return Err(format!("log::{} expects a message, got no arguments", name));
+    };
+
+    let mut fields = Vec::new();
+    for arg in &args[1..] {
+        match arg {
+            Value::Collection(c) => fields.extend(c.borrow().iter().map(|(k, v)| (field_name(&k), v.clone()))),
+            other => return Err(format!("log::{} expects fields in a collection, got {}", name, other.type_name())),
+        }
+    }
+
+    let config = ctx.interpreter().log_config();
+    if level < config.level() {
+        return Ok(Value::Nil);
+    }
+
+    let msg = match msg {
+        Value::String(s) => s.to_string(),
+        other => other.to_string(),
+    };
+
+    let line = if config.json {
+        let mut object = serde_json::Map::new();
+        object.insert("time".to_string(), timestamp().into());
+        object.insert("level".to_string(), name.into());
+        object.insert("msg".to_string(), msg.into());
+        for (k, v) in fields {
+            let v = serde_json::to_value(&v).map_err(|e| format!("log::{} couldn't write field '{}': {}", name, k, e))?;
+            object.insert(k, v);
+        }
+        serde_json::Value::Object(object).to_string()
+    } else {
+        let mut line = format!("{} {:<5} {}", timestamp(), name.to_ascii_uppercase(), msg);
+        for (k, v) in fields {
+            line.push_str(&format!(" {}={}", k, text_value(&v)));
+        }
+        line
+    };
+
+    writeln!(ctx.error_output(), "{}", line).map_err(|e| format!("log::{} failed to write output: {}", name, e))?;
+    Ok(Value::Nil)
+}
+
+fn debug_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    write_log(ctx, Level::Debug, args)
+}
+
+fn info_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    write_log(ctx, Level::Info, args)
+}
+
+fn warn_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    write_log(ctx, Level::Warn, args)
+}
+
+fn error_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    write_log(ctx, Level::Error, args)
+}
+
+fn string_arg<'a>(name: &str, args: &'a [Value]) -> Result<&'a str, String> {
+    if args.len() != 1 {
+        return Err(format!("log::{} expects 1 argument, got {}", name, args.len()));
+    }
+
+    match &args[0] {
+        Value::String(s) => Ok(s),
+        other => Err(format!("log::{} expects a string, got {}", name, other.type_name())),
+    }
+}
+
+fn set_level_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let level = string_arg("set_level", args)?;
+    let level = Level::parse(level)
+        .ok_or_else(|| format!("log::set_level expects \"debug\", \"info\", \"warn\", \"error\" or \"off\", got \"{}\"", level))?;
+
+    ctx.interpreter().log_config().level = Some(level);
+    Ok(Value::Nil)
+}
+
+// "text" (the default) or "json"
+fn set_format_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let json = match string_arg("set_format", args)? {
+        "text" => false,
+        "json" => true,
+        other => return Err(format!("log::set_format expects \"text\" or \"json\", got \"{}\"", other)),
+    };
+
+    ctx.interpreter().log_config().json = json;
+    Ok(Value::Nil)
+}
+
+pub const LOG_MOD: Module = Module {
+    name: "log",
+    funcs: &[],
+    ctx_funcs: &[
+        ("debug", debug_nfn),
+        ("info", info_nfn),
+        ("warn", warn_nfn),
+        ("error", error_nfn),
+        ("set_level", set_level_nfn),
+        ("set_format", set_format_nfn),
+    ],
+    method_types: &[],
+    consts: &[],
+};
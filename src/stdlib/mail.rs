@@ -0,0 +1,294 @@
+use crate::interpreter::{CKey, CValue, Value};
+use crate::stdlib::Module;
+use crate::stdlib::tls::Conn;
+use chrono::DateTime;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// sending plain text email over smtp, only built with the `mail` feature
+//
+// mail::send("localhost:25", "cron@box", "me@example.com", "disk full", "/ is at 98%")
+//
+// val server = [host = "smtp.example.com", port = 587, user = "alerts", password = env_password]
+// mail::send(server, "alerts@example.com", ["a@example.com", "b@example.com"], "down", "api is down")
+//
+// the server is "host[:port]" or a collection with host, port, user, password
+// and tls. tls is "tls" for a connection that starts out encrypted (the
+// default on port 465), "starttls" to require upgrading a plain one, or "none"
+// to never encrypt. left out, starttls is used whenever the server offers it.
+// a password is never sent over a connection that isn't encrypted
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(PartialEq)]
+enum Encryption {
+    Auto,
+    Implicit,
+    Upgrade,
+    Never,
+}
+
+struct Server {
+    host: String,
+    port: u16,
+    login: Option<(String, String)>,
+    tls: Encryption,
+}
+
+fn field(c: &CValue, name: &str) -> Result<Option<String>, String> {
+    match c.get(&CKey::String(name.into())) {
+        None | Some(Value::Nil) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.to_string())),
+        Some(other) => Err(format!("mail::send expects the server's {} to be a string, got {}", name, other.type_name())),
+    }
+}
+
+fn server_arg(v: &Value) -> Result<Server, String> {
+    let (host, port, login, tls) = match v {
+        Value::String(s) => match s.rsplit_once(':') {
+            Some((host, port)) => (host.to_string(), Some(Value::String(port.into())), None, None),
+            None => (s.to_string(), None, None, None),
+        },
+        Value::Collection(c) => {
+            let c = c.borrow();
+            let host = field(&c, "host")?.ok_or("mail::send expects the server to have a host")?;
+            let login = match (field(&c, "user")?, field(&c, "password")?) {
+                (Some(user), Some(password)) => Some((user, password)),
+                (None, None) => None,
+                _ => return Err("mail::send expects both a user and a password, or neither".to_string()),
+            };
+            (host, c.get(&CKey::String("port".into())).cloned(), login, field(&c, "tls")?)
+        },
+        other => return Err(format!("mail::send expects a server string or collection, got {}", other.type_name())),
+    };
+
+    let port = match port {
+        None | Some(Value::Nil) => None,
+        Some(Value::Int(p)) if (1..=65535).contains(&p) => Some(p as u16),
+        Some(Value::String(p)) if p.parse::<u16>().is_ok_and(|p| p > 0) => p.parse().ok(),
+        Some(other) => return Err(format!("mail::send expects a port from 1 to 65535, got {}", other)),
+    };
+    let tls = match tls.as_deref() {
+        None if port == Some(465) => Encryption::Implicit,
+        None => Encryption::Auto,
+        Some("tls") => Encryption::Implicit,
+        Some("starttls") => Encryption::Upgrade,
+        Some("none") => Encryption::Never,
+        Some(other) => return Err(format!("mail::send expects tls to be \"tls\", \"starttls\" or \"none\", got \"{}\"", other)),
+    };
+    let port = port.unwrap_or(match tls {
+        Encryption::Implicit => 465,
+        _ if login.is_some() => 587,
+        _ => 25,
+    });
+
+    Ok(Server { host, port, login, tls })
+}
+
+// anything that ends up in a header or an smtp command, where a line break
+// would let it add headers or commands of its own
+fn single_line<'a>(what: &str, v: &'a Value) -> Result<&'a str, String> {
+    match v {
+        Value::String(s) if s.contains(['\r', '\n']) => Err(format!("mail::send expects the {} to be on one line", what)),
+        Value::String(s) => Ok(s),
+        other => Err(format!("mail::send expects the {} to be a string, got {}", what, other.type_name())),
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(CHARS[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// non ascii subjects have to be encoded (rfc 2047)
+fn encode_header(s: &str) -> String {
+    if s.is_ascii() {
+        s.to_string()
+    } else {
+        format!("=?utf-8?B?{}?=", base64(s.as_bytes()))
+    }
+}
+
+struct Smtp {
+    conn: Conn,
+    pending: Vec<u8>,
+}
+
+impl Smtp {
+    fn read_line(&mut self) -> Result<String, String> {
+        loop {
+            if let Some(end) = self.pending.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = self.pending.drain(..=end).collect();
+                return Ok(String::from_utf8_lossy(&line).trim_end().to_string());
+            }
+            let mut buf = [0; 512];
+            match self.conn.read(&mut buf) {
+                Ok(0) => return Err("mail::send lost the connection to the server".to_string()),
+                Ok(n) => self.pending.extend_from_slice(&buf[..n]),
+                Err(e) => return Err(format!("mail::send failed to read from the server: {}", e)),
+            }
+        }
+    }
+
+    // a reply is one or more "250-..." lines ending with a "250 ..." one
+    fn reply(&mut self, expect: &[u16]) -> Result<Vec<String>, String> {
+        let mut lines = Vec::new();
+        loop {
+            let line = self.read_line()?;
+            let code = line.get(..3).and_then(|c| c.parse::<u16>().ok())
+                .ok_or_else(|| format!("mail::send got a reply it doesn't understand: {}", line))?;
+            let last = line.as_bytes().get(3) != Some(&b'-');
+            lines.push(line.get(4..).unwrap_or("").to_string());
+
+            if last {
+                if !expect.contains(&code) {
+                    return Err(format!("mail::send was refused by the server: {} {}", code, lines.join(" ")));
+                }
+                return Ok(lines);
+            }
+        }
+    }
+
+    fn send(&mut self, data: &str) -> Result<(), String> {
+        self.conn.write_all(data.as_bytes())
+            .and_then(|_| self.conn.flush())
+            .map_err(|e| format!("mail::send failed to write to the server: {}", e))
+    }
+
+    fn command(&mut self, line: &str, expect: &[u16]) -> Result<Vec<String>, String> {
+        self.send(&format!("{}\r\n", line))?;
+        self.reply(expect)
+    }
+
+    fn ehlo(&mut self) -> Result<Vec<String>, String> {
+        let name = hostname::get().ok().and_then(|h| h.into_string().ok()).unwrap_or_else(|| "localhost".to_string());
+        self.command(&format!("EHLO {}", name), &[250])
+    }
+}
+
+fn connect(server: &Server) -> Result<Smtp, String> {
+    let failed = |e: &dyn std::fmt::Display| format!("mail::send failed to connect to {}:{}: {}", server.host, server.port, e);
+
+    let addrs: Vec<_> = (server.host.as_str(), server.port).to_socket_addrs().map_err(|e| failed(&e))?.collect();
+    let mut last_error = None;
+    let tcp = addrs.iter().find_map(|addr| match TcpStream::connect_timeout(addr, TIMEOUT) {
+        Ok(tcp) => Some(tcp),
+        Err(e) => {
+            last_error = Some(e);
+            None
+        },
+    });
+    let tcp = match (tcp, last_error) {
+        (Some(tcp), _) => tcp,
+        (None, Some(e)) => return Err(failed(&e)),
+        (None, None) => return Err(failed(&"no addresses found")),
+    };
+    tcp.set_read_timeout(Some(TIMEOUT)).and_then(|_| tcp.set_write_timeout(Some(TIMEOUT))).map_err(|e| failed(&e))?;
+
+    let mut conn = Conn::Plain(tcp);
+    if server.tls == Encryption::Implicit {
+        conn = conn.upgrade(&server.host).map_err(|e| failed(&e))?;
+    }
+    Ok(Smtp { conn, pending: Vec::new() })
+}
+
+// send(server, from, to, subject, body), to is an address or a list of them
+fn send_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 5 {
+        return Err(format!("mail::send expects 5 arguments, got {}", args.len()));
+    }
+
+    let server = server_arg(&args[0])?;
+    let from = single_line("from address", &args[1])?;
+    let to = match &args[2] {
+        Value::Collection(c) => c.borrow().iter().map(|(_, v)| single_line("to address", v).map(str::to_string)).collect::<Result<Vec<_>, _>>()?,
+        other => vec![single_line("to address", other)?.to_string()],
+    };
+    if to.is_empty() {
+        return Err("mail::send expects at least one to address".to_string());
+    }
+    let subject = single_line("subject", &args[3])?;
+    let body = match &args[4] {
+        Value::String(s) => s,
+        other => return Err(format!("mail::send expects the body to be a string, got {}", other.type_name())),
+    };
+
+    let mut smtp = connect(&server)?;
+    smtp.reply(&[220])?;
+    let extensions = smtp.ehlo()?;
+
+    let offers_starttls = extensions.iter().any(|e| e.eq_ignore_ascii_case("STARTTLS"));
+    let wants_starttls = match server.tls {
+        Encryption::Upgrade if !offers_starttls => return Err(format!("mail::send was told to use starttls but {} doesn't offer it", server.host)),
+        Encryption::Upgrade => true,
+        Encryption::Auto => offers_starttls,
+        Encryption::Implicit | Encryption::Never => false,
+    };
+    if wants_starttls {
+        smtp.command("STARTTLS", &[220])?;
+        if !smtp.pending.is_empty() {
+            return Err("mail::send got data from the server before tls started".to_string());
+        }
+        smtp.conn = smtp.conn.upgrade(&server.host)?;
+        smtp.ehlo()?;
+    }
+
+    if let Some((user, password)) = &server.login {
+        if !matches!(smtp.conn, Conn::Tls(_)) {
+            return Err(format!("mail::send won't send a password to {} without tls", server.host));
+        }
+        let token = base64(format!("\0{}\0{}", user, password).as_bytes());
+        smtp.command(&format!("AUTH PLAIN {}", token), &[235])?;
+    }
+
+    smtp.command(&format!("MAIL FROM:<{}>", from), &[250])?;
+    for address in &to {
+        smtp.command(&format!("RCPT TO:<{}>", address), &[250, 251])?;
+    }
+    smtp.command("DATA", &[354])?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let date = DateTime::from_timestamp(now.as_secs() as i64, 0).map_or_else(String::new, |d| d.to_rfc2822());
+    let mut message = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+        from, to.join(", "), encode_header(subject), date,
+    );
+    // a line with just a . would end the message early, so lines starting
+    // with one get another in front (the server takes it off again)
+    for line in body.lines() {
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message.push_str(".\r\n");
+    smtp.send(&message)?;
+    smtp.reply(&[250])?;
+
+    // the mail's already accepted, a server that hangs up early is fine
+    let _ = smtp.command("QUIT", &[221]);
+    Ok(Value::Nil)
+}
+
+pub const MAIL_MOD: Module = Module {
+    name: "mail",
+    funcs: &[
+        ("send", send_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
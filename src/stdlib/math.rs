@@ -1,5 +1,16 @@
+use crate::bigint::BigInt;
 use crate::interpreter::Value;
-use super::Module;
+use super::{Const, Module};
+use std::cmp::Ordering;
+
+// floor/ceil hand back ints when the result fits in one
+fn whole(n: f64) -> Value {
+    if n.is_finite() && n >= i64::MIN as f64 && n < i64::MAX as f64 {
+        Value::Int(n as i64)
+    } else {
+        Value::Number(n)
+    }
+}
 
 fn abs_nfn(args: &[Value]) -> Result<Value, String> {
     if args.len() != 1 {
@@ -7,7 +18,9 @@ fn abs_nfn(args: &[Value]) -> Result<Value, String> {
     }
 
     match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.abs() as f64)),
+        Value::Int(i) => Ok(i.checked_abs().map(Value::Int).unwrap_or_else(|| Value::from_bigint(BigInt::from_i64(*i).abs()))),
+        Value::BigInt(b) => Ok(Value::from_bigint(b.abs())),
+        Value::Number(n) => Ok(Value::Number(n.abs())),
         _ => Err(format!("not a number in math::abs, got {}", args[0])),
     }
 }
@@ -17,8 +30,8 @@ fn sqrt_nfn(args: &[Value]) -> Result<Value, String> {
         return Err(format!("too many arguments for function math::abs, got {}", args.len()));
     }
 
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.sqrt() as f64)),
+    match args[0].as_f64() {
+        Some(n) => Ok(Value::Number(n.sqrt())),
         _ => Err(format!("not a number in math::abs, got {}", args[0])),
     }
 }
@@ -29,7 +42,13 @@ fn pow_nfn(args: &[Value]) -> Result<Value, String> {
     }
 
     match (&args[0], &args[1]) {
-        (Value::Number(base), Value::Number(exp)) => Ok(Value::Number(base.powf(*exp) as f64)),
+        // int to a non negative int power stays an int, growing into a bigint if it has to
+        (Value::Int(_) | Value::BigInt(_), Value::Int(exp)) if *exp >= 0 => {
+            Ok(Value::from_bigint(args[0].as_bigint().unwrap().pow(*exp as u64)))
+        },
+        (base, exp) if base.as_f64().is_some() && exp.as_f64().is_some() => {
+            Ok(Value::Number(base.as_f64().unwrap().powf(exp.as_f64().unwrap())))
+        },
         _ => Err(format!("not a number in math::pow, got {}", args[0])),
     }
 }
@@ -40,7 +59,8 @@ fn floor_nfn(args: &[Value]) -> Result<Value, String> {
     }
 
     match &args[0]{
-        Value::Number(n) => Ok(Value::Number(n.floor() as f64)),
+        Value::Int(_) | Value::BigInt(_) => Ok(args[0].clone()),
+        Value::Number(n) => Ok(whole(n.floor())),
         _ => Err(format!("not a number in math::floor, got {}", args[0])),
     }
 }
@@ -51,7 +71,8 @@ fn ceil_nfn(args: &[Value]) -> Result<Value, String> {
     }
 
     match &args[0]{
-        Value::Number(n) => Ok(Value::Number(n.ceil() as f64)),
+        Value::Int(_) | Value::BigInt(_) => Ok(args[0].clone()),
+        Value::Number(n) => Ok(whole(n.ceil())),
         _ => Err(format!("not a number in math::ceil, got {}", args[0])),
     }
 }
@@ -61,8 +82,8 @@ fn sin_nfn(args: &[Value]) -> Result<Value, String> {
         return Err(format!("too many arguments for function math::sin, got {}", args.len()));
     }
 
-    match &args[0]{
-        Value::Number(n) => Ok(Value::Number(n.sin() as f64)),
+    match args[0].as_f64() {
+        Some(n) => Ok(Value::Number(n.sin())),
         _ => Err(format!("not a number in math::floor, got {}", args[0])),
     }
 }
@@ -72,34 +93,210 @@ fn cos_nfn(args: &[Value]) -> Result<Value, String> {
         return Err(format!("too many arguments for function math::cos, got {}", args.len()));
     }
 
-    match &args[0]{
-        Value::Number(n) => Ok(Value::Number(n.cos() as f64)),
+    match args[0].as_f64() {
+        Some(n) => Ok(Value::Number(n.cos())),
         _ => Err(format!("not a number in math::cos, got {}", args[0])),
     }
 }
 
-fn max_nfn(args: &[Value]) -> Result<Value, String> {
-    if args.len() != 2 {
-        return Err(format!("too many arguments or too little for function math::max, got {}", args.len()));
+// round(x) to the nearest int, halves away from zero, round(x, places) to
+// that many decimals. negative places round to tens, hundreds and so on
+fn round_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("too many arguments or too little for function math::round, got {}, want 1 or 2", args.len()));
     }
 
-    match (&args[0], &args[1]) {
-        (Value::Number(n), Value::Number(m)) => Ok(Value::Number(n.max(*m) as f64)),
-        _ => Err(format!("not a number in math::floor, got {}", args[0])),
+    let places = match args.get(1) {
+        None => 0,
+        Some(Value::Int(p)) => (*p).clamp(-400, 400) as i32,
+        Some(other) => return Err(format!("math::round expects a whole number of places, got {}", other)),
+    };
+
+    match &args[0] {
+        Value::Int(_) | Value::BigInt(_) if places >= 0 => Ok(args[0].clone()),
+        v => {
+            let Some(n) = v.as_f64() else {
+                return Err(format!("not a number in math::round, got {}", v));
+            };
+            let scale = 10f64.powi(places);
+            let rounded = if places == 0 {
+                n.round()
+            } else if scale.is_finite() && scale != 0.0 && (n * scale).is_finite() {
+                (n * scale).round() / scale
+            } else {
+                n   // more places than a float has, or so few it's all zeros
+            };
+            Ok(if places <= 0 { whole(rounded) } else { Value::Number(rounded) })
+        },
     }
 }
 
-fn min_nfn(args: &[Value]) -> Result<Value, String> {
+// drops the fraction, towards zero
+fn trunc_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("too many arguments for function math::trunc, got {}", args.len()));
+    }
+
+    match &args[0] {
+        Value::Int(_) | Value::BigInt(_) => Ok(args[0].clone()),
+        Value::Number(n) => Ok(whole(n.trunc())),
+        _ => Err(format!("not a number in math::trunc, got {}", args[0])),
+    }
+}
+
+// -1, 0 or 1, NaN stays NaN
+fn sign_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("too many arguments for function math::sign, got {}", args.len()));
+    }
+
+    match &args[0] {
+        Value::Number(n) if n.is_nan() => Ok(Value::Number(f64::NAN)),
+        v => match v.partial_cmp(&Value::Int(0)) {
+            Some(o) => Ok(Value::Int(o as i64)),
+            None => Err(format!("not a number in math::sign, got {}", v)),
+        },
+    }
+}
+
+// clamp(x, lo, hi), x itself when it's already in range so ints stay ints
+fn clamp_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 3 {
+        return Err(format!("too many arguments or too little for function math::clamp, got {}, want 3", args.len()));
+    }
+
+    if let Some(v) = args.iter().find(|v| v.as_f64().is_none()) {
+        return Err(format!("not a number in math::clamp, got {}", v));
+    }
+
+    let (x, lo, hi) = (&args[0], &args[1], &args[2]);
+    if lo.partial_cmp(hi) == Some(Ordering::Greater) {
+        return Err(format!("math::clamp expects lo to be at most hi, got {} and {}", lo, hi));
+    }
+
+    Ok(match x.partial_cmp(lo) {
+        None => x.clone(),  // NaN
+        Some(Ordering::Less) => lo.clone(),
+        _ if x.partial_cmp(hi) == Some(Ordering::Greater) => hi.clone(),
+        _ => x.clone(),
+    })
+}
+
+// lerp(a, b, t) goes from a at t = 0 to b at t = 1, and keeps going past either
+fn lerp_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 3 {
+        return Err(format!("too many arguments or too little for function math::lerp, got {}, want 3", args.len()));
+    }
+
+    match (args[0].as_f64(), args[1].as_f64(), args[2].as_f64()) {
+        (Some(a), Some(b), Some(t)) => Ok(Value::Number(a + (b - a) * t)),
+        _ => Err(format!("not a number in math::lerp, got {}", args.iter().find(|v| v.as_f64().is_none()).unwrap())),
+    }
+}
+
+// one number in, a float out. domain errors (asin(2), log(-1)) come back as
+// NaN like they would anywhere else rather than failing the script
+fn float_fn(name: &str, args: &[Value], f: fn(f64) -> f64) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("too many arguments or too little for function math::{}, got {}, want 1", name, args.len()));
+    }
+
+    match args[0].as_f64() {
+        Some(n) => Ok(Value::Number(f(n))),
+        _ => Err(format!("not a number in math::{}, got {}", name, args[0])),
+    }
+}
+
+fn float_fn2(name: &str, args: &[Value], f: fn(f64, f64) -> f64) -> Result<Value, String> {
     if args.len() != 2 {
-        return Err(format!("too many arguments or too little for function math::min, got {}", args.len()));
+        return Err(format!("too many arguments or too little for function math::{}, got {}, want 2", name, args.len()));
     }
 
-    match (&args[0], &args[1]) {
-        (Value::Number(n), Value::Number(m)) => Ok(Value::Number(n.min(*m) as f64)),
-        _ => Err(format!("not a number in math::min, got {}", args[0])),
+    match (args[0].as_f64(), args[1].as_f64()) {
+        (Some(a), Some(b)) => Ok(Value::Number(f(a, b))),
+        (None, _) => Err(format!("not a number in math::{}, got {}", name, args[0])),
+        (_, None) => Err(format!("not a number in math::{}, got {}", name, args[1])),
+    }
+}
+
+fn tan_nfn(args: &[Value]) -> Result<Value, String> {
+    float_fn("tan", args, f64::tan)
+}
+
+fn asin_nfn(args: &[Value]) -> Result<Value, String> {
+    float_fn("asin", args, f64::asin)
+}
+
+fn acos_nfn(args: &[Value]) -> Result<Value, String> {
+    float_fn("acos", args, f64::acos)
+}
+
+fn atan_nfn(args: &[Value]) -> Result<Value, String> {
+    float_fn("atan", args, f64::atan)
+}
+
+// atan2(y, x), the angle of the point (x, y) with the signs picking the quadrant
+fn atan2_nfn(args: &[Value]) -> Result<Value, String> {
+    float_fn2("atan2", args, f64::atan2)
+}
+
+// log(x) is the natural log, log(x, base) any other
+fn log_nfn(args: &[Value]) -> Result<Value, String> {
+    match args.len() {
+        2 => float_fn2("log", args, f64::log),
+        _ => float_fn("log", args, f64::ln),
     }
 }
 
+fn log10_nfn(args: &[Value]) -> Result<Value, String> {
+    float_fn("log10", args, f64::log10)
+}
+
+fn log2_nfn(args: &[Value]) -> Result<Value, String> {
+    float_fn("log2", args, f64::log2)
+}
+
+fn exp_nfn(args: &[Value]) -> Result<Value, String> {
+    float_fn("exp", args, f64::exp)
+}
+
+// sqrt(a*a + b*b) without overflowing on the way there
+fn hypot_nfn(args: &[Value]) -> Result<Value, String> {
+    float_fn2("hypot", args, f64::hypot)
+}
+
+// max(a, b, ...) with any number of numbers, or max(collection) for the
+// numbers in it. the winner comes back as it was, so ints stay ints, and a NaN
+// anywhere makes the answer NaN
+fn pick(args: &[Value], name: &str, keep: Ordering) -> Result<Value, String> {
+    let values: Vec<Value> = match args {
+        [Value::Collection(c)] => c.borrow().iter().map(|(_, v)| v.clone()).collect(),
+        _ => args.to_vec(),
+    };
+
+    let mut best: Option<Value> = None;
+    for v in values {
+        match v.as_f64() {
+            None => return Err(format!("not a number in math::{}, got {}", name, v)),
+            Some(n) if n.is_nan() => return Ok(Value::Number(f64::NAN)),
+            Some(_) => {},
+        }
+        if best.as_ref().is_none_or(|b| v.partial_cmp(b) == Some(keep)) {
+            best = Some(v);
+        }
+    }
+
+    best.ok_or_else(|| format!("math::{} needs at least one number", name))
+}
+
+fn max_nfn(args: &[Value]) -> Result<Value, String> {
+    pick(args, "max", Ordering::Greater)
+}
+
+fn min_nfn(args: &[Value]) -> Result<Value, String> {
+    pick(args, "min", Ordering::Less)
+}
+
 pub const MATH_MOD: Module = Module {
     name: "math",
     funcs: &[
@@ -108,9 +305,33 @@ pub const MATH_MOD: Module = Module {
         ("pow", pow_nfn),
         ("floor", floor_nfn),
         ("ceil", ceil_nfn),
+        ("round", round_nfn),
+        ("trunc", trunc_nfn),
+        ("sign", sign_nfn),
+        ("clamp", clamp_nfn),
+        ("lerp", lerp_nfn),
         ("sin", sin_nfn),
         ("cos", cos_nfn),
+        ("tan", tan_nfn),
+        ("asin", asin_nfn),
+        ("acos", acos_nfn),
+        ("atan", atan_nfn),
+        ("atan2", atan2_nfn),
+        ("log", log_nfn),
+        ("log10", log10_nfn),
+        ("log2", log2_nfn),
+        ("exp", exp_nfn),
+        ("hypot", hypot_nfn),
         ("max", max_nfn),
         ("min", min_nfn),
     ],
+    ctx_funcs: &[],
+    method_types: &["int", "float"],
+    consts: &[
+        ("PI", Const::Number(std::f64::consts::PI)),
+        ("TAU", Const::Number(std::f64::consts::TAU)),
+        ("E", Const::Number(std::f64::consts::E)),
+        ("INF", Const::Number(f64::INFINITY)),
+        ("NAN", Const::Number(f64::NAN)),
+    ],
 };
\ No newline at end of file
@@ -1,27 +1,209 @@
-use crate::interpreter::Value;
+use crate::ast::Expr;
+use crate::interpreter::{CValue, Value};
+use crate::native::Ctx;
 
+pub mod bytes;
+pub mod collection;
 pub mod io;
 pub mod math;
+pub mod number;
 pub mod string;
 pub mod fs;
+pub mod test;
 mod json;
+mod yaml;
+mod bigint;
+mod csv;
+mod debug;
+#[cfg(all(feature = "desktop", not(target_arch = "wasm32")))]
+mod desktop;
+mod ini;
+#[cfg(all(feature = "mail", not(target_arch = "wasm32")))]
+mod mail;
+mod msgpack;
+mod date;
+pub(crate) mod sys;
+mod template;
+#[cfg(not(target_arch = "wasm32"))]
+mod crypto;
+#[cfg(not(target_arch = "wasm32"))]
+mod env;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod log;
+#[cfg(not(target_arch = "wasm32"))]
+mod net;
+#[cfg(not(target_arch = "wasm32"))]
+mod os;
+#[cfg(not(target_arch = "wasm32"))]
+mod proc;
+mod serial;
+#[cfg(not(target_arch = "wasm32"))]
+mod store;
+#[cfg(not(target_arch = "wasm32"))]
+mod zip;
+#[cfg(not(target_arch = "wasm32"))]
+mod term;
+#[cfg(all(any(feature = "mail", feature = "ws"), not(target_arch = "wasm32")))]
+mod tls;
+#[cfg(not(target_arch = "wasm32"))]
+mod time;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod timer;
+#[cfg(all(feature = "ws", not(target_arch = "wasm32")))]
+mod ws;
 
 // func(value_1) -> value, string as result
 pub type NativeFn = fn(&[Value]) -> Result<Value, String>;
 
+// same thing for natives that need the interpreter, for its input/output and such
+pub type CtxNativeFn = fn(&mut Ctx, &[Value]) -> Result<Value, String>;
+
+// there are no function values to pass around, so natives that run some hexi
+// later or once per element (collection::map, test::case, timers, watches and
+// so on) take a string of code instead. it's parsed straight away so a typo
+// shows up at the call, not whenever it first gets to run. name is what the
+// errors call the native, "collection::map"
+pub(crate) fn parse_callback(name: &str, code: &Value) -> Result<Vec<Expr>, String> {
+    match code {
+        Value::String(s) => crate::parse(s).map_err(|e| format!("{} couldn't parse its code: {}", name, e.message)),
+        other => Err(format!("{} expects a string of code, got {}", name, other.type_name())),
+    }
+}
+
+// runs the code from parse_callback in a scope of its own under the current
+// one, with the bindings defined in it. ctrl+c or a limit inside it still
+// stops the whole script rather than coming back as an ordinary error
+pub(crate) fn run_callback(ctx: &mut Ctx, code: &[Expr], bindings: &[(&str, Value)]) -> Result<Value, String> {
+    ctx.interpreter().eval_with(code, bindings).map_err(|e| ctx.interpreter().escape(e))
+}
+
 pub struct Module {
     pub name: &'static str,
     pub funcs: &'static [(&'static str, NativeFn)],
+    pub ctx_funcs: &'static [(&'static str, CtxNativeFn)],
+    // the funcs double as methods on values of these types (by type_name), the
+    // value the method is called on goes in as the first argument
+    pub method_types: &'static [&'static str],
+    // plain values read as module::NAME, like math::PI
+    pub consts: &'static [(&'static str, Const)],
+}
+
+// a module constant, strings and such in a Value can't be built at compile
+// time so these stand in for them until the module is loaded
+pub enum Const {
+    Int(i64),
+    Number(f64),
+    Str(&'static str),
+    Bool(bool),
+}
+
+impl Const {
+    pub fn to_value(&self) -> Value {
+        match self {
+            Const::Int(i) => Value::Int(*i),
+            Const::Number(n) => Value::Number(*n),
+            Const::Str(s) => Value::String((*s).into()),
+            Const::Bool(b) => Value::Bool(*b),
+        }
+    }
 }
 
 // standard registry of modules
 pub const REGISTRY_STD: &[Module] = &[
     io::IO_MOD,
     math::MATH_MOD,
+    collection::COLLECTION_MOD,
     string::STRING_MOD,
+    bytes::BYTES_MOD,
+    number::NUMBER_MOD,
 ];
 
+// called without a module name, these are there even with no modules loaded
+pub const GLOBALS: &[(&str, NativeFn)] = &[
+    ("str", string::str_nfn),
+    ("clone", collection::clone_nfn),
+    ("freeze", collection::freeze_nfn),
+];
+
+// globals that need the interpreter
+pub const CTX_GLOBALS: &[(&str, CtxNativeFn)] = &[
+    ("modules", modules_nfn),
+    ("module_funcs", module_funcs_nfn),
+];
+
+// modules() lists every module there is to call right now (the std ones and
+// whatever has been included), sorted
+fn modules_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("modules expects no arguments, got {}", args.len()));
+    }
+
+    let names = ctx.interpreter().module_names().into_iter().map(|n| Value::String(n.into())).collect();
+    Ok(Value::collection(CValue::from_array(names)))
+}
+
+// module_funcs("math") lists its functions, sorted. a module that hasn't been
+// included yet is an error rather than an empty list
+fn module_funcs_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("module_funcs expects 1 argument, got {}", args.len()));
+    }
+
+    let module = match &args[0] {
+        Value::String(s) => s,
+        other => return Err(format!("module_funcs expects a module name, got {}", other.type_name())),
+    };
+    let names = ctx.interpreter().module_functions(module)
+        .ok_or_else(|| format!("module_funcs found no module '{}', it may need including first", module))?;
+    Ok(Value::collection(CValue::from_array(names.into_iter().map(|n| Value::String(n.into())).collect())))
+}
+
 pub const REGISTRY_OPTIONAL: &[Module] = &[
+    // there's no file system in the browser
+    #[cfg(not(target_arch = "wasm32"))]
     fs::FS_MOD,
     json::JSON_MOD,
+    yaml::YAML_MOD,
+    bigint::BIGINT_MOD,
+    csv::CSV_MOD,
+    ini::INI_MOD,
+    date::DATE_MOD,
+    sys::SYS_MOD,
+    test::TEST_MOD,
+    debug::DEBUG_MOD,
+    serial::SERIAL_MOD,
+    msgpack::MSGPACK_MOD,
+    template::TEMPLATE_MOD,
+    // no clock or sleeping on wasm32-unknown-unknown either
+    #[cfg(not(target_arch = "wasm32"))]
+    time::TIME_MOD,
+    #[cfg(not(target_arch = "wasm32"))]
+    log::LOG_MOD,
+    // or environment
+    #[cfg(not(target_arch = "wasm32"))]
+    os::OS_MOD,
+    #[cfg(not(target_arch = "wasm32"))]
+    env::ENV_MOD,
+    #[cfg(not(target_arch = "wasm32"))]
+    proc::PROC_MOD,
+    #[cfg(not(target_arch = "wasm32"))]
+    store::STORE_MOD,
+    #[cfg(not(target_arch = "wasm32"))]
+    zip::ZIP_MOD,
+    #[cfg(not(target_arch = "wasm32"))]
+    term::TERM_MOD,
+    #[cfg(not(target_arch = "wasm32"))]
+    timer::TIMER_MOD,
+    #[cfg(not(target_arch = "wasm32"))]
+    crypto::CRYPTO_MOD,
+    // or sockets
+    #[cfg(not(target_arch = "wasm32"))]
+    net::NET_MOD,
+    // shells out to the desktop's own tools, so only when asked for
+    #[cfg(all(feature = "desktop", not(target_arch = "wasm32")))]
+    desktop::DESKTOP_MOD,
+    #[cfg(all(feature = "mail", not(target_arch = "wasm32")))]
+    mail::MAIL_MOD,
+    #[cfg(all(feature = "ws", not(target_arch = "wasm32")))]
+    ws::WS_MOD,
 ];
\ No newline at end of file
@@ -0,0 +1,44 @@
+use crate::interpreter::Value;
+use crate::stdlib::Module;
+
+// messagepack, for talking to services that speak it. goes through the same
+// serde impls as json (see serialize.rs), so plain lists become arrays and
+// everything else a map, bytes stay bytes and bigints are written as strings
+//
+// val packed = msgpack::encode([id = 7, tags = ["a", "b"]])
+// msgpack::decode(packed).tags[1]   -> "b"
+//
+// use serial::encode instead when the other end is hexi too, that one keeps
+// everything exactly as it was
+
+fn encode_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("msgpack::encode expects 1 argument, got {}", args.len()));
+    }
+
+    let packed = rmp_serde::to_vec(&args[0]).map_err(|e| format!("msgpack::encode failed: {}", e))?;
+    Ok(Value::Bytes(packed.into()))
+}
+
+fn decode_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("msgpack::decode expects 1 argument, got {}", args.len()));
+    }
+
+    let data = match &args[0] {
+        Value::Bytes(b) => b,
+        other => return Err(format!("msgpack::decode expects bytes, got {}", other.type_name())),
+    };
+    rmp_serde::from_slice(data).map_err(|e| format!("msgpack::decode got data that isn't valid messagepack: {}", e))
+}
+
+pub const MSGPACK_MOD: Module = Module {
+    name: "msgpack",
+    funcs: &[
+        ("encode", encode_nfn),
+        ("decode", decode_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
@@ -0,0 +1,97 @@
+use crate::interpreter::{CValue, Value};
+use crate::stdlib::Module;
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+// a few things for scripts checking on the network
+//
+// net::resolve("localhost")          -> ["127.0.0.1", "::1"]
+// net::my_ip()                        -> "192.168.1.20"
+// net::port_open("example.com", 443)  -> true
+
+const DEFAULT_TIMEOUT: f64 = 2.0;
+
+fn host_arg<'a>(name: &str, v: &'a Value) -> Result<&'a str, String> {
+    match v {
+        Value::String(s) => Ok(s),
+        other => Err(format!("net::{} expects a host name, got {}", name, other.type_name())),
+    }
+}
+
+fn lookup(name: &str, host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+    (host, port).to_socket_addrs()
+        .map(|addrs| addrs.collect())
+        .map_err(|e| format!("net::{} couldn't resolve '{}': {}", name, host, e))
+}
+
+// every address the name resolves to, in the order the resolver gave them
+fn resolve_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("net::resolve expects 1 argument, got {}", args.len()));
+    }
+
+    let mut ips: Vec<IpAddr> = Vec::new();
+    for addr in lookup("resolve", host_arg("resolve", &args[0])?, 0)? {
+        if !ips.contains(&addr.ip()) {
+            ips.push(addr.ip());
+        }
+    }
+    let ips = ips.into_iter().map(|ip| Value::String(ip.to_string().into())).collect();
+    Ok(Value::collection(CValue::from_array(ips)))
+}
+
+// the address this machine sends from when it talks to the internet, the one
+// on the local network behind any router. nothing is actually sent, picking a
+// route for a udp socket is enough. nil when there's no network
+fn my_ip_nfn(args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("net::my_ip expects no arguments, got {}", args.len()));
+    }
+
+    let ip = UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| socket.connect("8.8.8.8:80").map(|_| socket))
+        .and_then(|socket| socket.local_addr())
+        .map(|addr| addr.ip());
+    Ok(match ip {
+        Ok(ip) if !ip.is_unspecified() => Value::String(ip.to_string().into()),
+        _ => Value::Nil,
+    })
+}
+
+// port_open(host, port, [timeout]) is true if something accepts a tcp
+// connection there within timeout seconds (2 by default). a name that doesn't
+// resolve is an error, a closed port or a timeout is just false
+fn port_open_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(format!("net::port_open expects 2 or 3 arguments, got {}", args.len()));
+    }
+
+    let host = host_arg("port_open", &args[0])?;
+    let port = match args[1] {
+        Value::Int(p) if (1..=65535).contains(&p) => p as u16,
+        ref other => return Err(format!("net::port_open expects a port from 1 to 65535, got {}", other)),
+    };
+    let timeout = match args.get(2) {
+        None => DEFAULT_TIMEOUT,
+        Some(v) => match v.as_f64() {
+            Some(t) if t > 0.0 && t.is_finite() => t,
+            _ => return Err(format!("net::port_open expects a timeout above 0 seconds, got {}", v)),
+        },
+    };
+
+    let timeout = Duration::try_from_secs_f64(timeout).map_err(|_| format!("net::port_open can't wait {} seconds", timeout))?;
+    let open = lookup("port_open", host, port)?.iter().any(|addr| TcpStream::connect_timeout(addr, timeout).is_ok());
+    Ok(Value::Bool(open))
+}
+
+pub const NET_MOD: Module = Module {
+    name: "net",
+    funcs: &[
+        ("resolve", resolve_nfn),
+        ("my_ip", my_ip_nfn),
+        ("port_open", port_open_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
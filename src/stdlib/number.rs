@@ -0,0 +1,57 @@
+use crate::interpreter::Value;
+use super::{Const, Module};
+
+// number::to_fixed(n, 2) is n with exactly 2 decimals as a string, whatever
+// float precision is set. to_hex and from_hex go between ints and hex digits
+
+fn to_fixed_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("number::to_fixed expects 2 arguments, got {}", args.len()));
+    }
+
+    let n = args[0].as_f64().ok_or_else(|| format!("number::to_fixed expects a number, got {}", args[0].type_name()))?;
+    let places = match &args[1] {
+        Value::Int(p) if *p >= 0 => *p as usize,
+        other => return Err(format!("number::to_fixed expects a non negative int for places, got {}", other)),
+    };
+
+    Ok(Value::String(format!("{:.*}", places, n).into()))
+}
+
+// lowercase, with a leading - for negative numbers and no 0x
+fn to_hex_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("number::to_hex expects 1 argument, got {}", args.len()));
+    }
+
+    match args[0].as_bigint() {
+        Some(b) => Ok(Value::String(b.to_string_radix(16).into())),
+        None => Err(format!("number::to_hex expects an int, got {}", args[0].type_name())),
+    }
+}
+
+// nil when it isn't hex, same as string::parse_int(s, 16)
+fn from_hex_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("number::from_hex expects 1 argument, got {}", args.len()));
+    }
+
+    super::string::parse_int_nfn(&[args[0].clone(), Value::Int(16)])
+}
+
+pub const NUMBER_MOD: Module = Module {
+    name: "number",
+    funcs: &[
+        ("to_fixed", to_fixed_nfn),
+        ("to_hex", to_hex_nfn),
+        ("from_hex", from_hex_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &["int", "float"],
+    consts: &[
+        ("MAX_INT", Const::Int(i64::MAX)),
+        ("MIN_INT", Const::Int(i64::MIN)),
+        // the gap between 1.0 and the next float up
+        ("EPSILON", Const::Number(f64::EPSILON)),
+    ],
+};
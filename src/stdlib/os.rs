@@ -0,0 +1,116 @@
+use crate::interpreter::{CKey, CValue, Value};
+use crate::stdlib::{Const, Module};
+use std::env;
+
+fn string_arg<'a>(name: &str, v: &'a Value) -> Result<&'a str, String> {
+    match v {
+        Value::String(s) => Ok(s),
+        other => Err(format!("os::{} expects a string, got {}", name, other.type_name())),
+    }
+}
+
+// os::env(name) is the variable or nil when it isn't set, os::env() is every
+// variable in a collection keyed by name
+fn env_nfn(args: &[Value]) -> Result<Value, String> {
+    match args {
+        [] => {
+            let mut vars = CValue::new();
+            // vars_os so one non unicode variable doesn't take the whole call down
+            for (k, v) in env::vars_os() {
+                if let (Some(k), Some(v)) = (k.to_str(), v.to_str()) {
+                    vars.insert(CKey::String(k.into()), Value::String(v.into()));
+                }
+            }
+            Ok(Value::collection(vars))
+        },
+        [name] => {
+            let name = string_arg("env", name)?;
+            Ok(env::var(name).map_or(Value::Nil, |v| Value::String(v.into())))
+        },
+        _ => Err(format!("os::env expects 0 or 1 arguments, got {}", args.len())),
+    }
+}
+
+// only changes this process (and anything it starts), not the shell it came from
+fn set_env_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("os::set_env expects 2 arguments, got {}", args.len()));
+    }
+
+    let name = string_arg("set_env", &args[0])?;
+    if name.is_empty() || name.contains(['=', '\0']) {
+        return Err(format!("'{}' isn't a valid environment variable name", name));
+    }
+
+    let value = match &args[1] {
+        Value::String(s) => s.to_string(),
+        other => other.to_string(),
+    };
+    if value.contains('\0') {
+        return Err("environment variable values can't contain nul".to_string());
+    }
+
+    // setting variables races with other threads reading the environment,
+    // hexi itself never does. embedders running scripts next to their own
+    // threads (InterpreterThread) and worried about it can leave os out
+    unsafe { env::set_var(name, value) };
+    Ok(Value::Nil)
+}
+
+// "linux", "macos", "windows" and so on
+fn platform_nfn(args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("os::platform expects no arguments, got {}", args.len()));
+    }
+
+    Ok(Value::String(env::consts::OS.into()))
+}
+
+fn cwd_nfn(args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("os::cwd expects no arguments, got {}", args.len()));
+    }
+
+    let dir = env::current_dir().map_err(|e| format!("os::cwd failed: {}", e))?;
+    Ok(Value::String(dir.to_string_lossy().into()))
+}
+
+fn chdir_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("os::chdir expects 1 argument, got {}", args.len()));
+    }
+
+    let path = string_arg("chdir", &args[0])?;
+    env::set_current_dir(path).map_err(|e| format!("os::chdir failed to change to '{}': {}", path, e))?;
+    Ok(Value::Nil)
+}
+
+fn hostname_nfn(args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("os::hostname expects no arguments, got {}", args.len()));
+    }
+
+    let name = hostname::get().map_err(|e| format!("os::hostname failed: {}", e))?;
+    Ok(Value::String(name.to_string_lossy().into()))
+}
+
+pub const OS_MOD: Module = Module {
+    name: "os",
+    funcs: &[
+        ("env", env_nfn),
+        ("set_env", set_env_nfn),
+        ("platform", platform_nfn),
+        ("cwd", cwd_nfn),
+        ("chdir", chdir_nfn),
+        ("hostname", hostname_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[
+        // between the parts of a path, "/" or "\\"
+        ("SEP", Const::Str(std::path::MAIN_SEPARATOR_STR)),
+        // between the entries of PATH and the like
+        ("PATH_SEP", Const::Str(if cfg!(windows) { ";" } else { ":" })),
+        ("EOL", Const::Str(if cfg!(windows) { "\r\n" } else { "\n" })),
+    ],
+};
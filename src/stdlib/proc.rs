@@ -0,0 +1,72 @@
+use crate::interpreter::{CValue, Value};
+use crate::stdlib::Module;
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+
+// proc::run("git", ["status", "--short"]) waits for the command and gives back
+// [status = 0, stdout = "...", stderr = ""], status is nil when the process was
+// killed by a signal. a failing command isn't an error, one that can't be
+// started at all is. proc::spawn starts one without waiting and gives its pid
+//
+// commands run directly, not through a shell, so no globs or pipes
+
+fn command(name: &str, args: &[Value]) -> Result<Command, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("proc::{} expects 1 or 2 arguments, got {}", name, args.len()));
+    }
+
+    let program = match &args[0] {
+        Value::String(s) => s,
+        other => return Err(format!("proc::{} expects a command string, got {}", name, other.type_name())),
+    };
+
+    let mut cmd = Command::new(&**program);
+    match args.get(1) {
+        None => {},
+        Some(Value::Collection(c)) => {
+            for (_, arg) in c.borrow().iter() {
+                // numbers and such go in as they'd print
+                match arg {
+                    Value::String(s) => cmd.arg(&**s),
+                    other => cmd.arg(other.to_string()),
+                };
+            }
+        },
+        Some(other) => return Err(format!("proc::{} expects a collection of arguments, got {}", name, other.type_name())),
+    }
+
+    Ok(cmd)
+}
+
+fn run_nfn(args: &[Value]) -> Result<Value, String> {
+    let output = command("run", args)?
+        .output()
+        .map_err(|e| format!("proc::run failed to start '{}': {}", args[0], e))?;
+
+    let mut result = HashMap::new();
+    result.insert("status".to_string(), output.status.code().map_or(Value::Nil, |c| Value::Int(c as i64)));
+    result.insert("stdout".to_string(), Value::String(String::from_utf8_lossy(&output.stdout).into()));
+    result.insert("stderr".to_string(), Value::String(String::from_utf8_lossy(&output.stderr).into()));
+    Ok(Value::collection(CValue::from_object(result)))
+}
+
+// output still goes to the terminal, there's just nothing waiting on it
+fn spawn_nfn(args: &[Value]) -> Result<Value, String> {
+    let child = command("spawn", args)?
+        .stdin(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("proc::spawn failed to start '{}': {}", args[0], e))?;
+
+    Ok(Value::Int(child.id() as i64))
+}
+
+pub const PROC_MOD: Module = Module {
+    name: "proc",
+    funcs: &[
+        ("run", run_nfn),
+        ("spawn", spawn_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
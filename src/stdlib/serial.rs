@@ -0,0 +1,258 @@
+use crate::bigint::BigInt;
+use crate::interpreter::{CValue, Value};
+use crate::stdlib::Module;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// hexi's own binary format, for caching values on disk and reading them back
+// exactly as they were. unlike json it keeps ints and floats apart, bytes as
+// bytes, any kind of key, holes, frozen collections and collections that show
+// up more than once (or inside themselves)
+//
+// fs::write_bytes("cache.bin", serial::encode(results))
+// val results = serial::decode(fs::read_bytes("cache.bin"))
+//
+// the data starts with "hxs" and a version byte, then the value:
+//
+// nil, false, true          just the tag
+// int                       zigzag varint
+// bigint                    varint length + decimal digits
+// float                     8 bytes little endian
+// string, bytes             varint length + the bytes
+// collection                flags (1 = frozen), varint size, varint entry
+//                           count, then each key and value
+// seen collection           varint of which collection it was, in the order
+//                           they were first written
+//
+// userdata can't be encoded. bump VERSION whenever the layout changes, decode
+// refuses any version it doesn't know
+
+const MAGIC: &[u8] = b"hxs";
+const VERSION: u8 = 1;
+
+const NIL: u8 = 0;
+const FALSE: u8 = 1;
+const TRUE: u8 = 2;
+const INT: u8 = 3;
+const BIGINT: u8 = 4;
+const FLOAT: u8 = 5;
+const STRING: u8 = 6;
+const BYTES: u8 = 7;
+const COLLECTION: u8 = 8;
+const SEEN: u8 = 9;
+
+const FROZEN: u8 = 1;
+
+// a limit on nesting so bad data can't blow the stack while decoding
+const MAX_DEPTH: usize = 512;
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_blob(out: &mut Vec<u8>, tag: u8, data: &[u8]) {
+    out.push(tag);
+    write_varint(out, data.len() as u64);
+    out.extend_from_slice(data);
+}
+
+fn encode_into(out: &mut Vec<u8>, v: &Value, seen: &mut Vec<*const ()>) -> Result<(), String> {
+    match v {
+        Value::Nil => out.push(NIL),
+        Value::Bool(false) => out.push(FALSE),
+        Value::Bool(true) => out.push(TRUE),
+        Value::Int(i) => {
+            out.push(INT);
+            write_varint(out, ((i << 1) ^ (i >> 63)) as u64);
+        },
+        Value::BigInt(b) => write_blob(out, BIGINT, b.to_string().as_bytes()),
+        Value::Number(n) => {
+            out.push(FLOAT);
+            out.extend_from_slice(&n.to_le_bytes());
+        },
+        Value::String(s) => write_blob(out, STRING, s.as_bytes()),
+        Value::Bytes(b) => write_blob(out, BYTES, b),
+        Value::Collection(c) => {
+            let ptr = c.as_ptr() as *const ();
+            if let Some(i) = seen.iter().position(|p| *p == ptr) {
+                out.push(SEEN);
+                write_varint(out, i as u64);
+                return Ok(());
+            }
+            seen.push(ptr);
+
+            let c = c.borrow();
+            out.push(COLLECTION);
+            out.push(if c.is_frozen() { FROZEN } else { 0 });
+            write_varint(out, c.size() as u64);
+            write_varint(out, c.iter().count() as u64);
+            for (k, v) in c.iter() {
+                encode_into(out, &k.to_value(), seen)?;
+                encode_into(out, v, seen)?;
+            }
+        },
+        Value::Userdata(u) => return Err(format!("serial::encode can't encode a {}", u.type_name())),
+    }
+
+    Ok(())
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    seen: Vec<Rc<RefCell<CValue>>>,
+}
+
+impl Decoder<'_> {
+    fn truncated() -> String {
+        "serial::decode got data that ends too early".to_string()
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        let b = *self.data.get(self.pos).ok_or_else(Self::truncated)?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn take(&mut self, n: usize) -> Result<&[u8], String> {
+        let end = self.pos.checked_add(n).filter(|end| *end <= self.data.len()).ok_or_else(Self::truncated)?;
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            n |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err("serial::decode got a number that's too long".to_string())
+    }
+
+    fn len(&mut self) -> Result<usize, String> {
+        let n = self.varint()?;
+        usize::try_from(n).map_err(|_| format!("serial::decode got a length that's too big ({})", n))
+    }
+
+    fn text(&mut self) -> Result<&str, String> {
+        let len = self.len()?;
+        std::str::from_utf8(self.take(len)?).map_err(|_| "serial::decode got a string that isn't valid utf-8".to_string())
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, String> {
+        if depth > MAX_DEPTH {
+            return Err(format!("serial::decode got collections nested more than {} deep", MAX_DEPTH));
+        }
+
+        let value = match self.byte()? {
+            NIL => Value::Nil,
+            FALSE => Value::Bool(false),
+            TRUE => Value::Bool(true),
+            INT => {
+                let n = self.varint()?;
+                Value::Int((n >> 1) as i64 ^ -((n & 1) as i64))
+            },
+            BIGINT => {
+                let digits = self.text()?;
+                let b = BigInt::parse(digits, 10).ok_or_else(|| format!("serial::decode got a bigint that isn't a number: '{}'", digits))?;
+                Value::from_bigint(b)
+            },
+            FLOAT => {
+                let raw = self.take(8)?;
+                Value::Number(f64::from_le_bytes(raw.try_into().unwrap()))
+            },
+            STRING => Value::String(self.text()?.into()),
+            BYTES => {
+                let len = self.len()?;
+                Value::Bytes(self.take(len)?.into())
+            },
+            COLLECTION => {
+                let flags = self.byte()?;
+                let size = self.len()?;
+                let count = self.len()?;
+
+                // registered before its entries so they can point back at it
+                let c = Rc::new(RefCell::new(CValue::new()));
+                self.seen.push(c.clone());
+                for _ in 0..count {
+                    let k = self.value(depth + 1)?;
+                    let key = k.to_key().ok_or_else(|| format!("serial::decode got {} as a key", k))?;
+                    let v = self.value(depth + 1)?;
+                    c.borrow_mut().insert(key, v);
+                }
+
+                let mut inner = c.borrow_mut();
+                inner.extend_size(size);
+                if flags & FROZEN != 0 {
+                    inner.freeze();
+                }
+                drop(inner);
+                Value::Collection(c)
+            },
+            SEEN => {
+                let i = self.len()?;
+                let c = self.seen.get(i).ok_or_else(|| format!("serial::decode got a reference to collection {} before it was written", i))?;
+                Value::Collection(c.clone())
+            },
+            tag => return Err(format!("serial::decode got an unknown tag {}", tag)),
+        };
+
+        Ok(value)
+    }
+}
+
+fn encode_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("serial::encode expects 1 argument, got {}", args.len()));
+    }
+
+    let mut out = MAGIC.to_vec();
+    out.push(VERSION);
+    encode_into(&mut out, &args[0], &mut Vec::new())?;
+    Ok(Value::Bytes(out.into()))
+}
+
+fn decode_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("serial::decode expects 1 argument, got {}", args.len()));
+    }
+
+    let data = match &args[0] {
+        Value::Bytes(b) => b,
+        other => return Err(format!("serial::decode expects bytes, got {}", other.type_name())),
+    };
+    let Some(rest) = data.strip_prefix(MAGIC) else {
+        return Err("serial::decode got data that wasn't made by serial::encode".to_string());
+    };
+    match rest.first() {
+        None => return Err("serial::decode got data that ends too early".to_string()),
+        Some(&VERSION) => {},
+        Some(v) => return Err(format!("serial::decode got version {} data, this hexi reads version {}", v, VERSION)),
+    }
+
+    let mut decoder = Decoder { data: &rest[1..], pos: 0, seen: Vec::new() };
+    let value = decoder.value(0)?;
+    if decoder.pos != decoder.data.len() {
+        return Err(format!("serial::decode got {} extra bytes after the value", decoder.data.len() - decoder.pos));
+    }
+    Ok(value)
+}
+
+pub const SERIAL_MOD: Module = Module {
+    name: "serial",
+    funcs: &[
+        ("encode", encode_nfn),
+        ("decode", decode_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
@@ -0,0 +1,141 @@
+use crate::interpreter::{CValue, Value};
+use crate::native::Userdata;
+use crate::stdlib::fs::write_atomic;
+use crate::stdlib::Module;
+use serde_json::Map;
+use std::cell::RefCell;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+// a json file holding named values, for scripts that need to remember
+// something between runs
+//
+// val db = store::open("state.json")
+// db.set("runs", db.get("runs") + 1)
+// db.delete("last_error")
+//
+// every set and delete saves the whole file straight away (atomically, see
+// fs::write_atomic), so there's nothing to close or flush. values go through
+// json on the way in and out, get always hands back a fresh copy and changing
+// it doesn't touch what's stored until it's set again
+
+struct Store {
+    path: PathBuf,
+    data: RefCell<Map<String, serde_json::Value>>,
+}
+
+impl Store {
+    fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(&*self.data.borrow()).map_err(|e| e.to_string())?;
+        write_atomic(&self.path, json.as_bytes()).map_err(|e| format!("store failed to save '{}': {}", self.path.display(), e))
+    }
+}
+
+// a file that isn't there yet is an empty store, it's created on the first set
+fn open_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("store::open expects 1 argument, got {}", args.len()));
+    }
+
+    let path = match &args[0] {
+        Value::String(s) => PathBuf::from(&**s),
+        other => return Err(format!("expected a string value for path argument, got {}", other)),
+    };
+
+    let data = match fs::read_to_string(&path) {
+        Ok(text) if text.trim().is_empty() => Map::new(),
+        Ok(text) => match serde_json::from_str(&text) {
+            Ok(serde_json::Value::Object(map)) => map,
+            Ok(_) => return Err(format!("store::open expects '{}' to hold a json object", path.display())),
+            Err(e) => return Err(format!("store::open couldn't parse '{}': {}", path.display(), e)),
+        },
+        Err(e) if e.kind() == ErrorKind::NotFound => Map::new(),
+        Err(e) => return Err(format!("store::open failed to read '{}': {}", path.display(), e)),
+    };
+
+    Ok(Value::userdata(Userdata::new("store", Store { path, data: RefCell::new(data) })))
+}
+
+fn store_args<'a>(name: &str, args: &'a [Value], want: usize) -> Result<(&'a Store, &'a [Value]), String> {
+    if args.len() != want {
+        return Err(format!("store::{} expects {} arguments including the store, got {}", name, want, args.len()));
+    }
+
+    let store = args[0].as_userdata().and_then(|u| u.downcast::<Store>())
+        .ok_or_else(|| format!("store::{} expects a store, got {}", name, args[0].type_name()))?;
+    Ok((store, &args[1..]))
+}
+
+fn key_arg<'a>(name: &str, v: &'a Value) -> Result<&'a str, String> {
+    match v {
+        Value::String(s) => Ok(s),
+        other => Err(format!("store::{} expects a string key, got {}", name, other.type_name())),
+    }
+}
+
+// nil when there's nothing under the key
+fn get_nfn(args: &[Value]) -> Result<Value, String> {
+    let (store, rest) = store_args("get", args, 2)?;
+    let key = key_arg("get", &rest[0])?;
+
+    match store.data.borrow().get(key) {
+        Some(v) => serde_json::from_value(v.clone()).map_err(|e| format!("store::get couldn't read '{}': {}", key, e)),
+        None => Ok(Value::Nil),
+    }
+}
+
+// anything json can hold, so no userdata or collections used as keys
+fn set_nfn(args: &[Value]) -> Result<Value, String> {
+    let (store, rest) = store_args("set", args, 3)?;
+    let key = key_arg("set", &rest[0])?;
+    let value = serde_json::to_value(&rest[1]).map_err(|e| format!("store::set can't store that value: {}", e))?;
+
+    let previous = store.data.borrow_mut().insert(key.to_string(), value);
+    if let Err(e) = store.save() {
+        // keep what's in memory the same as what's on disk
+        let mut data = store.data.borrow_mut();
+        match previous {
+            Some(v) => data.insert(key.to_string(), v),
+            None => data.remove(key),
+        };
+        return Err(e);
+    }
+    Ok(Value::Nil)
+}
+
+// true if there was something to delete
+fn delete_nfn(args: &[Value]) -> Result<Value, String> {
+    let (store, rest) = store_args("delete", args, 2)?;
+    let key = key_arg("delete", &rest[0])?;
+
+    let Some(previous) = store.data.borrow_mut().remove(key) else {
+        return Ok(Value::Bool(false));
+    };
+    if let Err(e) = store.save() {
+        store.data.borrow_mut().insert(key.to_string(), previous);
+        return Err(e);
+    }
+    Ok(Value::Bool(true))
+}
+
+// sorted, that's the order they're kept in
+fn keys_nfn(args: &[Value]) -> Result<Value, String> {
+    let (store, _) = store_args("keys", args, 1)?;
+    let keys = store.data.borrow().keys().map(|k| Value::String(k.as_str().into())).collect();
+    Ok(Value::collection(CValue::from_array(keys)))
+}
+
+pub const STORE_MOD: Module = Module {
+    name: "store",
+    funcs: &[
+        ("open", open_nfn),
+        ("get", get_nfn),
+        ("set", set_nfn),
+        ("delete", delete_nfn),
+        ("keys", keys_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &["store"],
+    consts: &[],
+};
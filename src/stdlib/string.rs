@@ -1,4 +1,7 @@
-use crate::interpreter::Value;
+use crate::bigint::BigInt;
+use crate::interpreter::{CValue, Value};
+use crate::native::Ctx;
+use crate::number;
 use super::Module;
 
 fn len_nfn(args: &[Value]) -> Result<Value, String> {
@@ -7,29 +10,72 @@ fn len_nfn(args: &[Value]) -> Result<Value, String> {
     }
 
     match &args[0] {
-        Value::String(s) => Ok(Value::Number(s.len() as f64)),
+        Value::String(s) => Ok(Value::Int(s.len() as i64)),
         _ => Err(format!("not a string in string::abs, got {}", args[0])),
     }
 }
 
-fn to_number_nfn(args: &[Value]) -> Result<Value, String> {
+// nil for anything that isn't a number, so `if string::parse(s) == nil` can
+// check input instead of the script dying on it. surrounding whitespace is fine
+pub(crate) fn to_number_nfn(args: &[Value]) -> Result<Value, String> {
     if args.len() != 1 {
-        return Err(format!("too many arguments or too little for function string::to_number, got {}, want 1", args[0]));
+        return Err(format!("too many arguments or too little for function string::to_number, got {}, want 1", args.len()));
     }
 
     match &args[0] {
-        Value::String(s) => Ok(Value::Number(s.parse::<f64>().unwrap())),
+        Value::String(s) => {
+            let s = s.trim();
+            match s.parse::<i64>() {
+                Ok(i) => Ok(Value::Int(i)),
+                Err(_) => match BigInt::parse(s, 10) {
+                    Some(b) => Ok(Value::from_bigint(b)),
+                    None => Ok(s.parse::<f64>().map_or(Value::Nil, Value::Number)),
+                },
+            }
+        },
         _ => Err(format!("not a string in string::to_number, got {}", args[0])),
     }
 }
 
+// parse_int("ff", 16), parse_int("-101", 2). a 0x, 0o or 0b matching the radix
+// is skipped, and like parse it's nil when the digits don't fit the radix
+pub(crate) fn parse_int_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("too many arguments or too little for function string::parse_int, got {}, want 1 or 2", args.len()));
+    }
+
+    let s = match &args[0] {
+        Value::String(s) => s.trim(),
+        _ => return Err(format!("not a string in string::parse_int, got {}", args[0])),
+    };
+    let radix = match args.get(1) {
+        None => 10,
+        Some(Value::Int(r)) if (2..=36).contains(r) => *r as u32,
+        Some(other) => return Err(format!("string::parse_int expects a radix from 2 to 36, got {}", other)),
+    };
+
+    let (sign, digits) = match s.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", s.strip_prefix('+').unwrap_or(s)),
+    };
+    let prefix = match radix {
+        16 => Some("0x"),
+        8 => Some("0o"),
+        2 => Some("0b"),
+        _ => None,
+    };
+    let digits = prefix.and_then(|p| digits.strip_prefix(p)).unwrap_or(digits);
+
+    Ok(BigInt::parse(&format!("{}{}", sign, digits), radix).map_or(Value::Nil, Value::from_bigint))
+}
+
 fn upper_nfn(args: &[Value]) -> Result<Value, String> {
     if args.len() != 1 {
         return Err(format!("too many arguments or too little for function string::len, got {}, want 1", args.len()))
     }
 
     match &args[0] {
-        Value::String(s) => Ok(Value::String(s.to_uppercase())),
+        Value::String(s) => Ok(Value::String(s.to_uppercase().into())),
         _ => Err(format!("not a string in string::upper, got {}", args[0])),
     }
 }
@@ -40,7 +86,7 @@ fn lower_nfn(args: &[Value]) -> Result<Value, String> {
     }
 
     match &args[0] {
-        Value::String(s) => Ok(Value::String(s.to_lowercase())),
+        Value::String(s) => Ok(Value::String(s.to_lowercase().into())),
         _ => Err(format!("not a string in string::lower, got {}", args[0])),
     }
 }
@@ -51,7 +97,7 @@ fn trim_nfn(args: &[Value]) -> Result<Value, String> {
     }
 
     match &args[0] {
-        Value::String(s) => Ok(Value::String(s.trim().to_string())),
+        Value::String(s) => Ok(Value::String(s.trim().into())),
         _ => Err(format!("not a string in string::trim, got {}", args[0])),
     }
 }
@@ -62,7 +108,7 @@ fn starts_with_nfn(args: &[Value]) -> Result<Value, String> {
     }
 
     match (&args[0], &args[1]) {
-        (Value::String(s), Value::String(p)) => Ok(Value::Bool(s.starts_with(p))),
+        (Value::String(s), Value::String(p)) => Ok(Value::Bool(s.starts_with(&**p))),
         _ => Err(format!("not a string in string::trim, got {}", args[0])),
     }
 }
@@ -73,7 +119,7 @@ fn ends_with_nfn(args: &[Value]) -> Result<Value, String> {
     }
 
     match (&args[0], &args[1]) {
-        (Value::String(s), Value::String(p)) => Ok(Value::Bool(s.ends_with(p))),
+        (Value::String(s), Value::String(p)) => Ok(Value::Bool(s.ends_with(&**p))),
         _ => Err(format!("not a string in string::trim, got {}", args[0])),
     }
 }
@@ -84,7 +130,7 @@ fn contains_nfn(args: &[Value]) -> Result<Value, String> {
     }
 
     match (&args[0], &args[1]) {
-        (Value::String(s), Value::String(substr)) => Ok(Value::Bool(s.contains(substr))),
+        (Value::String(s), Value::String(substr)) => Ok(Value::Bool(s.contains(&**substr))),
         (Value::String(_), _) => Err(format!("string::contains expects second argument to be a string, got {}", args[1])),
         _ => Err(format!("not a string in string::contains, got {}", args[0])),
     }
@@ -97,7 +143,7 @@ fn replace_nfn(args: &[Value]) -> Result<Value, String> {
 
     match (&args[0], &args[1], &args[2]) {
         (Value::String(s), Value::String(from), Value::String(to)) => {
-            Ok(Value::String(s.replace(from, to)))
+            Ok(Value::String(s.replace(&**from, to).into()))
         },
         (Value::String(_), Value::String(_), _) => {
             Err(format!("string::replace expects third argument to be a string, got {}", args[2]))
@@ -115,17 +161,21 @@ fn sub_nfn(args: &[Value]) -> Result<Value, String> {
     }
 
     match (&args[0], &args[1], &args[2]) {
-        (Value::String(s), Value::Number(start), Value::Number(end)) => {
-            let start_idx = *start as usize;
-            let end_idx = *end as usize;
+        (Value::String(s), start, end) if start.as_f64().is_some() && end.as_f64().is_some() => {
+            let start_idx = start.as_f64().unwrap() as usize;
+            let end_idx = end.as_f64().unwrap() as usize;
 
             if start_idx > s.len() || end_idx > s.len() || start_idx > end_idx {
                 return Err("string::sub: invalid indices".to_string());
             }
 
-            Ok(Value::String(s[start_idx..end_idx].to_string()))
+            // in range but not both on a character boundary
+            match s.get(start_idx..end_idx) {
+                Some(sub) => Ok(Value::String(sub.into())),
+                None => Err(format!("string::sub: indices {} and {} don't both fall between characters", start_idx, end_idx)),
+            }
         },
-        (Value::String(_), Value::Number(_), _) => {
+        (Value::String(_), Value::Int(_) | Value::Number(_), _) => {
             Err(format!("string::sub expects third argument to be a number, got {}", args[2]))
         },
         (Value::String(_), _, _) => {
@@ -135,6 +185,82 @@ fn sub_nfn(args: &[Value]) -> Result<Value, String> {
     }
 }
 
+// {} takes the next argument as it is, {0} and {1} pick one by position
+// (counting from the first argument after the format string) without moving
+// the {} counter along. after a colon comes how to lay it out:
+//
+// {:8}     padded out to 8 characters, numbers to the right and everything else
+//          to the left like rust does. {:<8} and {:>8} pick the side
+// {:*>8}   pads with * instead of spaces
+// {:.2}    numbers rounded to 2 decimal places, strings cut down to 2 characters
+// {1:>8.2} all of it at once
+//
+// widths and precisions stop at 65535, past that the placeholder is invalid
+struct Spec {
+    index: Option<usize>,
+    fill: char,
+    align: Option<char>,
+    width: usize,
+    precision: Option<usize>,
+}
+
+const MAX_LAYOUT: usize = u16::MAX as usize;
+
+impl Spec {
+    fn parse(spec: &str) -> Result<Spec, String> {
+        let invalid = || format!("string::format: invalid placeholder '{{{}}}'", spec);
+        let (index, layout) = spec.split_once(':').unwrap_or((spec, ""));
+
+        let index = match index {
+            "" => None,
+            i => Some(i.parse().map_err(|_| invalid())?),
+        };
+
+        let chars: Vec<char> = layout.chars().collect();
+        let (fill, align, rest) = match chars.as_slice() {
+            [fill, align @ ('<' | '>'), rest @ ..] => (*fill, Some(*align), rest),
+            [align @ ('<' | '>'), rest @ ..] => (' ', Some(*align), rest),
+            rest => (' ', None, rest),
+        };
+
+        let rest: String = rest.iter().collect();
+        let (width, precision) = match rest.split_once('.') {
+            Some((width, precision)) => (width, Some(precision.parse().map_err(|_| invalid())?)),
+            None => (rest.as_str(), None),
+        };
+        let width = match width {
+            "" => 0,
+            w => w.parse().map_err(|_| invalid())?,
+        };
+        if width > MAX_LAYOUT || precision.is_some_and(|p| p > MAX_LAYOUT) {
+            return Err(invalid());
+        }
+
+        Ok(Spec { index, fill, align, width, precision })
+    }
+
+    fn apply(&self, value: &Value, s: String) -> String {
+        let numeric = matches!(value, Value::Int(_) | Value::BigInt(_) | Value::Number(_));
+        let s = match self.precision {
+            Some(p) if numeric => format!("{:.*}", p, value.as_f64().unwrap()),
+            Some(p) => s.chars().take(p).collect(),
+            None => s,
+        };
+
+        match self.align {
+            Some('>') => pad(&s, self.width, self.fill, true),
+            Some(_) => pad(&s, self.width, self.fill, false),
+            None => pad(&s, self.width, self.fill, numeric),
+        }
+    }
+}
+
+// fills up to `width` characters (not bytes), strings already that long are left alone
+fn pad(s: &str, width: usize, fill: char, left: bool) -> String {
+    let padding: String = std::iter::repeat_n(fill, width.saturating_sub(s.chars().count())).collect();
+    if left { padding + s } else { s.to_string() + &padding }
+}
+
 fn format_nfn(args: &[Value]) -> Result<Value, String> {
     if args.is_empty() {
         return Err("string::format expects at least one argument".to_string());
@@ -148,27 +274,41 @@ fn format_nfn(args: &[Value]) -> Result<Value, String> {
     let mut result = String::new();
     let mut chars = format_str.chars().peekable();
     let mut arg_index = 1;
+    let mut used = vec![false; args.len()];
 
     while let Some(ch) = chars.next() {
         if ch == '{' {
-            if chars.peek() == Some(&'}') {
-                chars.next(); // eat '}'
-                
-                if arg_index >= args.len() {
-                    return Err("string::format: not enough arguments for format placeholders".to_string());
+            if chars.peek().is_some_and(|c| *c == '}' || *c == ':' || c.is_ascii_digit()) {
+                // everything up to the closing brace, {1:>8} has "1:>8" in it
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => spec.push(c),
+                        None => return Err("string::format: placeholder is missing its closing '}'".to_string()),
+                    }
                 }
+                let spec = Spec::parse(&spec)?;
 
-                // convert 
-                let arg_str = match &args[arg_index] {
-                    Value::String(s) => s.clone(),
-                    Value::Number(n) => {
-                        // make numbers look pretty
-                        if n.fract() == 0.0 {
-                            format!("{}", *n as i64)
-                        } else {
-                            format!("{}", n)
-                        }
+                let too_few = || "string::format: not enough arguments for format placeholders".to_string();
+                let index = match spec.index {
+                    Some(i) => i.checked_add(1).ok_or_else(too_few)?,
+                    None => {
+                        arg_index += 1;
+                        arg_index - 1
                     },
+                };
+                if index >= args.len() {
+                    return Err(too_few());
+                }
+                used[index] = true;
+
+                // convert 
+                let arg_str = match &args[index] {
+                    Value::String(s) => s.to_string(),
+                    Value::Int(i) => i.to_string(),
+                    Value::BigInt(b) => b.to_string(),
+                    Value::Number(n) => number::format_float(*n),
                     Value::Bool(b) => b.to_string(),
                     // Value::Array(arr) => {
                     //     // [v1, v2, ...]
@@ -186,11 +326,10 @@ fn format_nfn(args: &[Value]) -> Result<Value, String> {
                     //     }).collect();
                     //     format!("[{}]", elements.join(", "))
                     // },
-                    _ => format!("{:?}", args[arg_index]), 
+                    _ => format!("{:?}", args[index]), 
                 };
 
-                result.push_str(&arg_str);
-                arg_index += 1;
+                result.push_str(&spec.apply(&args[index], arg_str));
             } else {
                 result.push(ch);
             }
@@ -199,12 +338,263 @@ fn format_nfn(args: &[Value]) -> Result<Value, String> {
         }
     }
 
-    if arg_index < args.len() {
+    if used.iter().skip(1).any(|u| !u) {
         return Err("string::format: too many arguments for format placeholders".to_string());
     }
 
-    Ok(Value::String(result))
+    Ok(Value::String(result.into()))
+}
+// split(s, sep) on every sep, split(s) on runs of whitespace with the ends trimmed
+fn split_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("too many arguments or too little for function string::split, got {}, want 1 or 2", args.len()));
+    }
+
+    let s = match &args[0] {
+        Value::String(s) => s,
+        _ => return Err(format!("not a string in string::split, got {}", args[0])),
+    };
+
+    let parts: Vec<Value> = match args.get(1) {
+        None => s.split_whitespace().map(|p| Value::String(p.into())).collect(),
+        Some(Value::String(sep)) if sep.is_empty() => return Err("string::split separator can't be empty".to_string()),
+        Some(Value::String(sep)) => s.split(&**sep).map(|p| Value::String(p.into())).collect(),
+        Some(other) => return Err(format!("string::split expects second argument to be a string, got {}", other)),
+    };
+
+    Ok(Value::collection(CValue::from_array(parts)))
+}
+
+// \n or \r\n line endings, a newline at the very end doesn't add an empty line
+fn lines_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("too many arguments or too little for function string::lines, got {}, want 1", args.len()));
+    }
+
+    match &args[0] {
+        Value::String(s) => Ok(Value::collection(CValue::from_array(s.lines().map(|l| Value::String(l.into())).collect()))),
+        _ => Err(format!("not a string in string::lines, got {}", args[0])),
+    }
+}
+
+// the same as split(s) without a separator
+fn split_ws_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("too many arguments or too little for function string::split_ws, got {}, want 1", args.len()));
+    }
+
+    split_nfn(args)
+}
+
+// join(collection, sep), strings go in as they are and anything else how it
+// prints. this one is also collection::join, so c.join(", ") works
+pub(crate) fn join_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("too many arguments or too little for function string::join, got {}, want 1 or 2", args.len()));
+    }
+
+    let c = match &args[0] {
+        Value::Collection(c) => c,
+        _ => return Err(format!("string::join expects first argument to be a collection, got {}", args[0])),
+    };
+
+    let sep = match args.get(1) {
+        None => "",
+        Some(Value::String(sep)) => sep,
+        Some(other) => return Err(format!("string::join expects second argument to be a string, got {}", other)),
+    };
+
+    let parts: Vec<String> = c.borrow().iter().map(|(_, v)| v.to_string()).collect();
+    Ok(Value::String(parts.join(sep).into()))
 }
+
+// chars, char_at, code and from_code all go by unicode characters, unlike len
+// and sub which count bytes
+fn chars_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("too many arguments or too little for function string::chars, got {}, want 1", args.len()));
+    }
+
+    match &args[0] {
+        Value::String(s) => Ok(Value::collection(CValue::from_array(s.chars().map(|c| Value::String(c.to_string().into())).collect()))),
+        _ => Err(format!("not a string in string::chars, got {}", args[0])),
+    }
+}
+
+// the utf-8 encoding, same as bytes::from_string
+fn bytes_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("too many arguments or too little for function string::bytes, got {}, want 1", args.len()));
+    }
+
+    match &args[0] {
+        Value::String(s) => Ok(Value::Bytes(s.as_bytes().into())),
+        _ => Err(format!("not a string in string::bytes, got {}", args[0])),
+    }
+}
+
+// the character starting at byte i (see find), nil past the end
+fn char_at_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("too many arguments or too little for function string::char_at, got {}, want 2", args.len()));
+    }
+
+    match (&args[0], &args[1]) {
+        (Value::String(s), Value::Int(i)) => {
+            let Some(rest) = usize::try_from(*i).ok().and_then(|i| s.get(i..)) else {
+                return match usize::try_from(*i) {
+                    Ok(i) if i < s.len() => Err(format!("string::char_at: byte {} is in the middle of a character", i)),
+                    _ => Ok(Value::Nil),
+                };
+            };
+            Ok(rest.chars().next().map_or(Value::Nil, |c| Value::String(c.to_string().into())))
+        },
+        (Value::String(_), _) => Err(format!("string::char_at expects second argument to be an int, got {}", args[1])),
+        _ => Err(format!("not a string in string::char_at, got {}", args[0])),
+    }
+}
+
+// the code point of a single character string
+fn code_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("too many arguments or too little for function string::code, got {}, want 1", args.len()));
+    }
+
+    let s = match &args[0] {
+        Value::String(s) => s,
+        _ => return Err(format!("not a string in string::code, got {}", args[0])),
+    };
+
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(Value::Int(c as i64)),
+        _ => Err(format!("string::code expects a single character, got \"{}\"", s)),
+    }
+}
+
+fn from_code_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("too many arguments or too little for function string::from_code, got {}, want 1", args.len()));
+    }
+
+    match &args[0] {
+        Value::Int(i) => u32::try_from(*i).ok().and_then(char::from_u32)
+            .map(|c| Value::String(c.to_string().into()))
+            .ok_or_else(|| format!("{} isn't a valid character code", i)),
+        _ => Err(format!("string::from_code expects an int, got {}", args[0])),
+    }
+}
+
+fn needle_args<'a>(args: &'a [Value], name: &str) -> Result<(&'a str, &'a str), String> {
+    if args.len() != 2 {
+        return Err(format!("too many arguments or too little for function string::{}, got {}, want 2", name, args.len()));
+    }
+
+    match (&args[0], &args[1]) {
+        (Value::String(s), Value::String(needle)) => Ok((s, needle)),
+        (Value::String(_), _) => Err(format!("string::{} expects second argument to be a string, got {}", name, args[1])),
+        _ => Err(format!("not a string in string::{}, got {}", name, args[0])),
+    }
+}
+
+// every index into a string is a byte offset, len, sub, char_at and these two
+// alike, so what find gives back can go straight into sub or char_at. anything
+// outside ascii is more than one byte, and sub or char_at in the middle of a
+// character is an error. nil when the needle isn't there
+fn find_nfn(args: &[Value]) -> Result<Value, String> {
+    let (s, needle) = needle_args(args, "find")?;
+    Ok(s.find(needle).map_or(Value::Nil, |i| Value::Int(i as i64)))
+}
+
+fn rfind_nfn(args: &[Value]) -> Result<Value, String> {
+    let (s, needle) = needle_args(args, "rfind")?;
+    Ok(s.rfind(needle).map_or(Value::Nil, |i| Value::Int(i as i64)))
+}
+
+// non overlapping, so "aaaa" has 2 "aa" in it
+fn count_nfn(args: &[Value]) -> Result<Value, String> {
+    let (s, needle) = needle_args(args, "count")?;
+    if needle.is_empty() {
+        return Err("string::count can't count an empty string".to_string());
+    }
+
+    Ok(Value::Int(s.matches(needle).count() as i64))
+}
+
+// pad_left(s, width) and pad_right(s, width) with spaces, or pad_left(s, width, "0")
+fn pad_args<'a>(args: &'a [Value], name: &str) -> Result<(&'a str, usize, char), String> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(format!("too many arguments or too little for function string::{}, got {}, want 2 or 3", name, args.len()));
+    }
+
+    let s = match &args[0] {
+        Value::String(s) => s,
+        _ => return Err(format!("not a string in string::{}, got {}", name, args[0])),
+    };
+    let width = match &args[1] {
+        Value::Int(w) if *w >= 0 => *w as usize,
+        _ => return Err(format!("string::{} expects second argument to be a non negative int, got {}", name, args[1])),
+    };
+    let fill = match args.get(2) {
+        None => ' ',
+        Some(Value::String(f)) if f.chars().count() == 1 => f.chars().next().unwrap(),
+        Some(other) => return Err(format!("string::{} expects third argument to be a single character, got {}", name, other)),
+    };
+
+    Ok((s, width, fill))
+}
+
+// these three know how long the result will be up front, so the string limit
+// gets checked before any of it is allocated
+fn check_len(ctx: &mut Ctx, name: &str, len: Option<usize>) -> Result<(), String> {
+    let checked = ctx.interpreter().check_string_len(len.unwrap_or(usize::MAX));
+    checked.map_err(|e| format!("string::{}: {}", name, ctx.interpreter().escape(e)))
+}
+
+fn pad_len(s: &str, width: usize, fill: char) -> Option<usize> {
+    width.saturating_sub(s.chars().count()).checked_mul(fill.len_utf8())?.checked_add(s.len())
+}
+
+fn pad_left_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let (s, width, fill) = pad_args(args, "pad_left")?;
+    check_len(ctx, "pad_left", pad_len(s, width, fill))?;
+    Ok(Value::String(pad(s, width, fill, true).into()))
+}
+
+fn pad_right_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    let (s, width, fill) = pad_args(args, "pad_right")?;
+    check_len(ctx, "pad_right", pad_len(s, width, fill))?;
+    Ok(Value::String(pad(s, width, fill, false).into()))
+}
+
+fn repeat_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("too many arguments or too little for function string::repeat, got {}, want 2", args.len()));
+    }
+
+    match (&args[0], &args[1]) {
+        (Value::String(s), Value::Int(n)) if *n >= 0 => {
+            check_len(ctx, "repeat", s.len().checked_mul(*n as usize))?;
+            Ok(Value::String(s.repeat(*n as usize).into()))
+        },
+        (Value::String(_), _) => Err(format!("string::repeat expects second argument to be a non negative int, got {}", args[1])),
+        _ => Err(format!("not a string in string::repeat, got {}", args[0])),
+    }
+}
+
+// str(value) is a global, not string::str. strings come back as they are and
+// everything else the way io::println would show it
+pub(crate) fn str_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("too many arguments or too little for function str, got {}, want 1", args.len()));
+    }
+
+    match &args[0] {
+        Value::String(s) => Ok(Value::String(s.clone())),
+        other => Ok(Value::String(other.to_string().into())),
+    }
+}
+
 pub const STRING_MOD: Module = Module {
     name: "string",
     funcs: &[
@@ -218,6 +608,26 @@ pub const STRING_MOD: Module = Module {
         ("replace", replace_nfn),
         ("sub", sub_nfn),
         ("parse", to_number_nfn),
-        ("fmt", format_nfn)
+        ("parse_int", parse_int_nfn),
+        ("fmt", format_nfn),
+        ("split", split_nfn),
+        ("join", join_nfn),
+        ("lines", lines_nfn),
+        ("split_ws", split_ws_nfn),
+        ("chars", chars_nfn),
+        ("bytes", bytes_nfn),
+        ("char_at", char_at_nfn),
+        ("code", code_nfn),
+        ("from_code", from_code_nfn),
+        ("find", find_nfn),
+        ("rfind", rfind_nfn),
+        ("count", count_nfn),
+    ],
+    ctx_funcs: &[
+        ("pad_left", pad_left_nfn),
+        ("pad_right", pad_right_nfn),
+        ("repeat", repeat_nfn),
     ],
+    method_types: &["string"],
+    consts: &[],
 };
\ No newline at end of file
@@ -0,0 +1,142 @@
+use crate::ast::Expr;
+use crate::interpreter::{CValue, Value};
+use crate::native::Ctx;
+use crate::stdlib::{parse_callback, Const, Module};
+use std::collections::HashMap;
+use std::rc::Rc;
+#[cfg(unix)]
+use std::sync::Arc;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// `hexi script.hx a b` gives sys::args() == ["a", "b"] and
+// sys::script_path() == "script.hx". in the repl or when embedded without
+// Interpreter::set_script the args are empty and the path is nil
+
+fn args_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("sys::args expects no arguments, got {}", args.len()));
+    }
+
+    let script_args = ctx.interpreter().script_args().iter()
+        .map(|a| Value::String(a.as_str().into()))
+        .collect();
+    Ok(Value::collection(CValue::from_array(script_args)))
+}
+
+fn script_path_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("sys::script_path expects no arguments, got {}", args.len()));
+    }
+
+    Ok(ctx.interpreter().script_path().map_or(Value::Nil, |p| Value::String(p.into())))
+}
+
+// sys::on_signal(name, code) runs a bit of cleanup code when the script is
+// stopped by "INT" (ctrl+c), or on unix "TERM" and "HUP":
+//
+// sys::on_signal("INT", "db.close()")
+// sys::on_signal("TERM", "log::warn('stopping on ' signal)")
+//
+// the code runs between two expressions rather than in the middle of one, with
+// `signal` set to the name, and the script stops right after like it would
+// have anyway. a second ctrl+c while it's running stops the script straight
+// away. registering a signal again replaces its code
+
+// the signals there's a handler for. every interpreter has its own
+#[derive(Default)]
+pub(crate) struct Signals {
+    handlers: HashMap<&'static str, Rc<Vec<Expr>>>,
+    // set by the os alongside the interrupt flag, to tell TERM and HUP apart
+    // from a plain ctrl+c
+    #[cfg(unix)]
+    raised: Vec<(&'static str, Arc<AtomicBool>)>,
+    running: bool,
+}
+
+impl Signals {
+    // what stopped the script and the code to run for it, if there is any and
+    // a handler isn't running already
+    pub(crate) fn take_handler(&mut self) -> Option<(&'static str, Rc<Vec<Expr>>)> {
+        #[allow(unused_mut)]
+        let mut signal = "INT";
+        #[cfg(unix)]
+        for (name, flag) in &self.raised {
+            if flag.swap(false, Ordering::Relaxed) {
+                signal = name;
+            }
+        }
+
+        if self.running {
+            return None;
+        }
+        self.handlers.get(signal).map(|code| (signal, code.clone()))
+    }
+
+    pub(crate) fn set_running(&mut self, running: bool) {
+        self.running = running;
+    }
+}
+
+#[cfg(unix)]
+fn signal_number(name: &str) -> Option<(&'static str, i32)> {
+    use signal_hook::consts::{SIGHUP, SIGTERM};
+    match name {
+        "TERM" => Some(("TERM", SIGTERM)),
+        "HUP" => Some(("HUP", SIGHUP)),
+        _ => None,
+    }
+}
+
+fn on_signal_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("sys::on_signal expects 2 arguments, got {}", args.len()));
+    }
+
+    let name = match &args[0] {
+        Value::String(s) => s.trim_start_matches("SIG"),
+        other => return Err(format!("sys::on_signal expects a signal name, got {}", other.type_name())),
+    };
+    let code = parse_callback("sys::on_signal", &args[1])?;
+
+    let name = match name {
+        // ctrl+c already sets the interrupt flag
+        "INT" => "INT",
+        #[cfg(unix)]
+        _ if let Some((name, number)) = signal_number(name) => {
+            let interrupt = ctx.interpreter().interrupt_handle();
+            let signals = ctx.interpreter().signals();
+            if !signals.raised.iter().any(|(n, _)| *n == name) {
+                // the flag saying which signal it was goes first, so it's set
+                // by the time anything sees the interrupt
+                let raised = Arc::new(AtomicBool::new(false));
+                signal_hook::flag::register(number, raised.clone())
+                    .and_then(|_| signal_hook::flag::register(number, interrupt))
+                    .map_err(|e| format!("sys::on_signal couldn't catch {}: {}", name, e))?;
+                signals.raised.push((name, raised));
+            }
+            name
+        },
+        #[cfg(unix)]
+        _ => return Err(format!("sys::on_signal expects \"INT\", \"TERM\" or \"HUP\", got \"{}\"", name)),
+        #[cfg(not(unix))]
+        _ => return Err(format!("sys::on_signal can only catch \"INT\" here, got \"{}\"", name)),
+    };
+
+    ctx.interpreter().signals().handlers.insert(name, Rc::new(code));
+    Ok(Value::Nil)
+}
+
+pub const SYS_MOD: Module = Module {
+    name: "sys",
+    funcs: &[],
+    ctx_funcs: &[
+        ("args", args_nfn),
+        ("script_path", script_path_nfn),
+        ("on_signal", on_signal_nfn),
+    ],
+    method_types: &[],
+    consts: &[
+        ("VERSION", Const::Str(env!("CARGO_PKG_VERSION"))),
+    ],
+};
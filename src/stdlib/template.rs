@@ -0,0 +1,212 @@
+use crate::ast::Expr;
+use crate::interpreter::Value;
+use crate::native::Ctx;
+use crate::stdlib::{parse_callback, run_callback, Module};
+
+// template::render(text, vars) fills in a template from a keyed collection:
+//
+// Hi {{ user.name }}, you have {{ items.size() }} items
+// {% if items.size() > 0 %}
+//   {% for item in items %}- {{ item }}
+//   {% end %}
+// {% else %}
+//   nothing here
+// {% end %}
+//
+// whatever sits between the braces is hexi code, run with every entry of vars
+// defined as a variable. `for` goes over a collection's values, or its keys
+// and values with `{% for key, value in c %}`. nil renders as nothing and
+// nothing gets escaped, so html-escape anything untrusted first
+
+enum Node {
+    Text(String),
+    Value(Vec<Expr>),
+    If(Vec<Expr>, Vec<Node>, Vec<Node>),
+    For(Option<String>, String, Vec<Expr>, Vec<Node>),
+}
+
+// where a block of nodes stopped, so `if` knows whether an else follows
+#[derive(PartialEq)]
+enum Stop {
+    Else,
+    End,
+    Eof,
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl Parser<'_> {
+    fn code(tag: &str, source: &str) -> Result<Vec<Expr>, String> {
+        let code = parse_callback(&format!("template's '{}'", tag), &Value::String(source.into()))?;
+        if code.is_empty() {
+            return Err(format!("template has an empty '{}'", tag));
+        }
+        Ok(code)
+    }
+
+    // nodes up to the next else/end (or the end of the text)
+    fn block(&mut self) -> Result<(Vec<Node>, Stop), String> {
+        let mut nodes = Vec::new();
+        loop {
+            let next = [self.rest.find("{{"), self.rest.find("{%")].into_iter().flatten().min();
+            let Some(start) = next else {
+                if !self.rest.is_empty() {
+                    nodes.push(Node::Text(self.rest.to_string()));
+                }
+                self.rest = "";
+                return Ok((nodes, Stop::Eof));
+            };
+
+            if start > 0 {
+                nodes.push(Node::Text(self.rest[..start].to_string()));
+            }
+
+            let is_value = self.rest[start..].starts_with("{{");
+            let close = if is_value { "}}" } else { "%}" };
+            let body_start = start + 2;
+            let Some(len) = self.rest[body_start..].find(close) else {
+                return Err(format!("template has an unclosed '{}'", &self.rest[start..body_start]));
+            };
+            let tag = &self.rest[start..body_start + len + 2];
+            let body = self.rest[body_start..body_start + len].trim();
+            self.rest = &self.rest[body_start + len + 2..];
+
+            if is_value {
+                nodes.push(Node::Value(Self::code(tag, body)?));
+                continue;
+            }
+
+            let (word, args) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+            match word {
+                "if" => {
+                    let cond = Self::code(tag, args)?;
+                    let (then, stop) = self.block()?;
+                    let otherwise = match stop {
+                        Stop::Else => self.end_block("if")?,
+                        Stop::End => Vec::new(),
+                        Stop::Eof => return Err(format!("template '{}' is missing its {{% end %}}", tag)),
+                    };
+                    nodes.push(Node::If(cond, then, otherwise));
+                },
+                "for" => {
+                    let Some((names, source)) = args.split_once(" in ") else {
+                        return Err(format!("template '{}' should look like {{% for item in collection %}}", tag));
+                    };
+                    let (key, value) = match names.split_once(',') {
+                        Some((k, v)) => (Some(k.trim().to_string()), v.trim().to_string()),
+                        None => (None, names.trim().to_string()),
+                    };
+                    let source = Self::code(tag, source)?;
+                    let body = self.end_block("for")?;
+                    nodes.push(Node::For(key, value, source, body));
+                },
+                "else" => return Ok((nodes, Stop::Else)),
+                "end" => return Ok((nodes, Stop::End)),
+                _ => return Err(format!("template has an unknown tag '{}'", tag)),
+            }
+        }
+    }
+
+    fn end_block(&mut self, what: &str) -> Result<Vec<Node>, String> {
+        match self.block()? {
+            (nodes, Stop::End) => Ok(nodes),
+            (_, Stop::Else) => Err(format!("template has an {{% else %}} with no if in a {}", what)),
+            (_, Stop::Eof) => Err(format!("template has a {} that's missing its {{% end %}}", what)),
+        }
+    }
+}
+
+fn eval(ctx: &mut Ctx, code: &[Expr], scope: &[(String, Value)]) -> Result<Value, String> {
+    // later names (loop variables) shadow earlier ones with the same name
+    let mut bindings: Vec<(&str, Value)> = Vec::new();
+    for (name, value) in scope.iter().rev() {
+        if !bindings.iter().any(|(n, _)| *n == name.as_str()) {
+            bindings.push((name, value.clone()));
+        }
+    }
+
+    run_callback(ctx, code, &bindings).map_err(|e| format!("template error: {}", e))
+}
+
+fn render(ctx: &mut Ctx, nodes: &[Node], scope: &mut Vec<(String, Value)>, out: &mut String) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Value(code) => match eval(ctx, code, scope)? {
+                Value::Nil => {},
+                v => out.push_str(&v.to_string()),
+            },
+            Node::If(cond, then, otherwise) => {
+                let branch = if eval(ctx, cond, scope)?.is_truthy() { then } else { otherwise };
+                render(ctx, branch, scope, out)?;
+            },
+            Node::For(key, value, source, body) => {
+                let Value::Collection(c) = eval(ctx, source, scope)? else {
+                    return Err("template can only loop over a collection".to_string());
+                };
+                let entries: Vec<_> = c.borrow().iter().map(|(k, v)| (k.to_value(), v.clone())).collect();
+
+                for (k, v) in entries {
+                    let depth = scope.len();
+                    if let Some(key) = key {
+                        scope.push((key.clone(), k));
+                    }
+                    scope.push((value.clone(), v));
+                    let rendered = render(ctx, body, scope, out);
+                    scope.truncate(depth);
+                    rendered?;
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+fn render_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("template::render expects 1 or 2 arguments, got {}", args.len()));
+    }
+
+    let text = match &args[0] {
+        Value::String(s) => s,
+        other => return Err(format!("template::render expects a template string, got {}", other.type_name())),
+    };
+
+    let mut scope = Vec::new();
+    match args.get(1) {
+        None | Some(Value::Nil) => {},
+        Some(Value::Collection(c)) => {
+            for (k, v) in c.borrow().iter() {
+                match k.to_value() {
+                    Value::String(name) => scope.push((name.to_string(), v.clone())),
+                    other => return Err(format!("template::render expects vars keyed by name, got a {} key", other.type_name())),
+                }
+            }
+        },
+        Some(other) => return Err(format!("template::render expects a collection of vars, got {}", other.type_name())),
+    }
+
+    let (nodes, stop) = Parser { rest: text }.block()?;
+    match stop {
+        Stop::Else => return Err("template has an {% else %} with no if".to_string()),
+        Stop::End => return Err("template has an {% end %} with nothing to end".to_string()),
+        Stop::Eof => {},
+    }
+
+    let mut out = String::new();
+    render(ctx, &nodes, &mut scope, &mut out)?;
+    Ok(Value::String(out.into()))
+}
+
+pub const TEMPLATE_MOD: Module = Module {
+    name: "template",
+    funcs: &[],
+    ctx_funcs: &[
+        ("render", render_nfn),
+    ],
+    method_types: &[],
+    consts: &[],
+};
@@ -0,0 +1,388 @@
+use crate::error::HexiError;
+use crate::interpreter::{CValue, Value};
+use crate::native::{Ctx, MethodTable, UserMethod, Userdata};
+use crate::stdlib::Module;
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use terminal_size::{Height, Width};
+
+// the styling functions hand back the text wrapped in ansi escape codes, to
+// print like any other string:
+//
+// io::println(term::color("failed", "red"), term::bold("3 errors"))
+//
+// they leave the text alone when NO_COLOR is set (https://no-color.org).
+// clear, move_to and the cursor functions write their codes straight to the
+// output instead, and so do progress bars and spinners:
+//
+// val bar = term::progress(collection::size(files))
+// bar.tick()       -> one more done, tick(5) for five
+// bar.finish()     -> draws it full and moves on to the next line
+//
+// val s = term::spinner("downloading")
+// s.tick()         -> next frame
+// s.finish("done") -> replaces the spinner line with the message
+
+// "bright_" in front of any of these works too
+const COLORS: &[(&str, u8)] = &[
+    ("black", 30),
+    ("red", 31),
+    ("green", 32),
+    ("yellow", 33),
+    ("blue", 34),
+    ("magenta", 35),
+    ("cyan", 36),
+    ("white", 37),
+];
+
+fn color_code(name: &str, v: &Value) -> Result<u8, String> {
+    let Value::String(color) = v else {
+        return Err(format!("term::{} expects a color name, got {}", name, v.type_name()));
+    };
+
+    let (bright, base) = match color.strip_prefix("bright_") {
+        Some(base) => (60, base),
+        None => (0, &**color),
+    };
+    COLORS.iter().find(|(c, _)| *c == base)
+        .map(|(_, code)| code + bright)
+        .ok_or_else(|| format!("term::{} doesn't know the color '{}'", name, color))
+}
+
+fn styled(name: &str, args: &[Value], want: usize, code: u8) -> Result<Value, String> {
+    if args.len() != want {
+        return Err(format!("term::{} expects {} argument{}, got {}", name, want, if want == 1 { "" } else { "s" }, args.len()));
+    }
+
+    let text = match &args[0] {
+        Value::String(s) => s.to_string(),
+        other => other.to_string(),
+    };
+    if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        return Ok(Value::String(text.into()));
+    }
+    Ok(Value::String(format!("\x1b[{}m{}\x1b[0m", code, text).into()))
+}
+
+// color(text, "red"), the names are in COLORS
+fn color_nfn(args: &[Value]) -> Result<Value, String> {
+    let code = color_code("color", args.get(1).unwrap_or(&Value::Nil))?;
+    styled("color", args, 2, code)
+}
+
+// same names, for the background
+fn bg_nfn(args: &[Value]) -> Result<Value, String> {
+    let code = color_code("bg", args.get(1).unwrap_or(&Value::Nil))?;
+    styled("bg", args, 2, code + 10)
+}
+
+fn bold_nfn(args: &[Value]) -> Result<Value, String> {
+    styled("bold", args, 1, 1)
+}
+
+fn dim_nfn(args: &[Value]) -> Result<Value, String> {
+    styled("dim", args, 1, 2)
+}
+
+fn italic_nfn(args: &[Value]) -> Result<Value, String> {
+    styled("italic", args, 1, 3)
+}
+
+fn underline_nfn(args: &[Value]) -> Result<Value, String> {
+    styled("underline", args, 1, 4)
+}
+
+// [width = columns, height = rows], nil when the output isn't a terminal and
+// COLUMNS/LINES don't say either
+fn size_nfn(args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("term::size expects no arguments, got {}", args.len()));
+    }
+
+    let from_env = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u16>().ok());
+    let size = terminal_size::terminal_size()
+        .map(|(Width(w), Height(h))| (w, h))
+        .or_else(|| Some((from_env("COLUMNS")?, from_env("LINES")?)));
+
+    let Some((width, height)) = size else {
+        return Ok(Value::Nil);
+    };
+    let mut result = HashMap::new();
+    result.insert("width".to_string(), Value::Int(width as i64));
+    result.insert("height".to_string(), Value::Int(height as i64));
+    Ok(Value::collection(CValue::from_object(result)))
+}
+
+fn write_code(ctx: &mut Ctx, name: &str, code: &str) -> Result<Value, String> {
+    let out = ctx.output();
+    write!(out, "{}", code).and_then(|_| out.flush()).map_err(|e| format!("term::{} failed to write output: {}", name, e))?;
+    Ok(Value::Nil)
+}
+
+fn no_args(name: &str, args: &[Value]) -> Result<(), String> {
+    if !args.is_empty() {
+        return Err(format!("term::{} expects no arguments, got {}", name, args.len()));
+    }
+    Ok(())
+}
+
+// whole screen, and the cursor back to the top left
+fn clear_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    no_args("clear", args)?;
+    write_code(ctx, "clear", "\x1b[2J\x1b[H")
+}
+
+// the line the cursor is on, and the cursor to its start
+fn clear_line_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    no_args("clear_line", args)?;
+    write_code(ctx, "clear_line", "\x1b[2K\r")
+}
+
+fn count_arg(name: &str, v: &Value) -> Result<i64, String> {
+    match v {
+        Value::Int(n) if *n >= 0 && *n <= u16::MAX as i64 => Ok(*n),
+        other => Err(format!("term::{} expects a count from 0 to {}, got {}", name, u16::MAX, other)),
+    }
+}
+
+// move_to(row, col), both starting at 1 like the terminal counts them
+fn move_to_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("term::move_to expects 2 arguments, got {}", args.len()));
+    }
+
+    let row = count_arg("move_to", &args[0])?.max(1);
+    let col = count_arg("move_to", &args[1])?.max(1);
+    write_code(ctx, "move_to", &format!("\x1b[{};{}H", row, col))
+}
+
+// up(n), down(n) and so on, n defaults to 1
+fn move_by(ctx: &mut Ctx, name: &str, args: &[Value], dir: char) -> Result<Value, String> {
+    let n = match args {
+        [] => 1,
+        [n] => count_arg(name, n)?,
+        _ => return Err(format!("term::{} expects 0 or 1 arguments, got {}", name, args.len())),
+    };
+    if n == 0 {
+        return Ok(Value::Nil);  // a 0 would still move one in most terminals
+    }
+    write_code(ctx, name, &format!("\x1b[{}{}", n, dir))
+}
+
+fn up_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    move_by(ctx, "up", args, 'A')
+}
+
+fn down_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    move_by(ctx, "down", args, 'B')
+}
+
+fn right_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    move_by(ctx, "right", args, 'C')
+}
+
+fn left_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    move_by(ctx, "left", args, 'D')
+}
+
+fn hide_cursor_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    no_args("hide_cursor", args)?;
+    write_code(ctx, "hide_cursor", "\x1b[?25l")
+}
+
+fn show_cursor_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    no_args("show_cursor", args)?;
+    write_code(ctx, "show_cursor", "\x1b[?25h")
+}
+
+// redrawing on every tick of a tight loop would spend more time on the
+// terminal than on the work, so ticks in between redraws only count
+const REDRAW_EVERY: Duration = Duration::from_millis(50);
+const BAR_WIDTH: u64 = 30;
+
+struct Progress {
+    total: u64,
+    done: Cell<u64>,
+    drawn: Cell<Option<Instant>>,
+    finished: Cell<bool>,
+}
+
+struct Spinner {
+    label: String,
+    frame: Cell<usize>,
+    drawn: Cell<Option<Instant>>,
+    finished: Cell<bool>,
+}
+
+const FRAMES: &[char] = &['|', '/', '-', '\\'];
+
+fn due(drawn: &Cell<Option<Instant>>) -> bool {
+    let now = Instant::now();
+    if drawn.get().is_some_and(|last| now - last < REDRAW_EVERY) {
+        return false;
+    }
+    drawn.set(Some(now));
+    true
+}
+
+fn draw_progress(ctx: &mut Ctx, p: &Progress, name: &str) -> Result<Value, String> {
+    let done = p.done.get().min(p.total);
+    let (filled, percent) = match p.total {
+        0 => (BAR_WIDTH, 100),
+        total => (done * BAR_WIDTH / total, done * 100 / total),
+    };
+    let bar = format!("{}{}", "=".repeat(filled as usize), " ".repeat((BAR_WIDTH - filled) as usize));
+    write_code(ctx, name, &format!("\r[{}] {}/{} {:>3}%", bar, done, p.total, percent))
+}
+
+// progress(total), where total is how many ticks make it full
+fn progress_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("term::progress expects 1 argument, got {}", args.len()));
+    }
+
+    let total = match &args[0] {
+        Value::Int(n) if *n >= 0 => *n as u64,
+        other => return Err(format!("term::progress expects a non negative total, got {}", other)),
+    };
+    let p = Progress { total, done: Cell::new(0), drawn: Cell::new(None), finished: Cell::new(false) };
+    Ok(Value::userdata(Userdata::new("progress", p).with_methods(PROGRESS_METHODS.with(Rc::clone))))
+}
+
+fn progress_tick(ctx: &mut Ctx, p: &Progress, args: &[Value]) -> Result<Value, String> {
+    let n = match args {
+        [] => 1,
+        [Value::Int(n)] if *n >= 0 => *n as u64,
+        [other] => return Err(format!("progress.tick expects a non negative count, got {}", other)),
+        _ => return Err(format!("progress.tick expects 0 or 1 arguments, got {}", args.len())),
+    };
+
+    p.done.set(p.done.get().saturating_add(n));
+    if due(&p.drawn) {
+        draw_progress(ctx, p, "progress")?;
+    }
+    Ok(Value::Nil)
+}
+
+// however far it got, the bar is drawn full
+fn progress_finish(ctx: &mut Ctx, p: &Progress, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("progress.finish expects no arguments, got {}", args.len()));
+    }
+    p.done.set(p.total);
+    draw_progress(ctx, p, "progress")?;
+    p.finished.set(true);
+    write_code(ctx, "progress", "\n")
+}
+
+// spinner(label), the label shows next to it
+fn spinner_nfn(args: &[Value]) -> Result<Value, String> {
+    let label = match args {
+        [] => String::new(),
+        [Value::String(s)] => s.to_string(),
+        [other] => other.to_string(),
+        _ => return Err(format!("term::spinner expects 0 or 1 arguments, got {}", args.len())),
+    };
+
+    let s = Spinner { label, frame: Cell::new(0), drawn: Cell::new(None), finished: Cell::new(false) };
+    Ok(Value::userdata(Userdata::new("spinner", s).with_methods(SPINNER_METHODS.with(Rc::clone))))
+}
+
+fn spinner_tick(ctx: &mut Ctx, s: &Spinner, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("spinner.tick expects no arguments, got {}", args.len()));
+    }
+    if !due(&s.drawn) {
+        return Ok(Value::Nil);
+    }
+
+    let frame = FRAMES[s.frame.get() % FRAMES.len()];
+    s.frame.set(s.frame.get() + 1);
+    write_code(ctx, "spinner", &format!("\r{} {}", frame, s.label))
+}
+
+// finish() leaves the label, finish(message) puts the message there instead
+fn spinner_finish(ctx: &mut Ctx, s: &Spinner, args: &[Value]) -> Result<Value, String> {
+    let message = match args {
+        [] => s.label.clone(),
+        [Value::String(m)] => m.to_string(),
+        [other] => other.to_string(),
+        _ => return Err(format!("spinner.finish expects 0 or 1 arguments, got {}", args.len())),
+    };
+
+    s.finished.set(true);
+    write_code(ctx, "spinner", &format!("\r\x1b[2K{}\n", message))
+}
+
+trait Finishable: Any {
+    fn finished(&self) -> bool;
+}
+
+impl Finishable for Progress {
+    fn finished(&self) -> bool {
+        self.finished.get()
+    }
+}
+
+impl Finishable for Spinner {
+    fn finished(&self) -> bool {
+        self.finished.get()
+    }
+}
+
+fn method<T: Finishable>(f: fn(&mut Ctx, &T, &[Value]) -> Result<Value, String>) -> UserMethod {
+    Rc::new(move |ctx: &mut Ctx, u: &Userdata, args: &[Value]| {
+        let this = u.downcast::<T>().expect("methods are only given to their own type");
+        if this.finished() {
+            return Err(HexiError::runtime(format!("this {} is already finished", u.type_name())));
+        }
+        f(ctx, this, args).map_err(HexiError::runtime)
+    })
+}
+
+thread_local! {
+    static PROGRESS_METHODS: Rc<MethodTable> = {
+        let mut methods = MethodTable::new();
+        methods.insert("tick", method(progress_tick));
+        methods.insert("finish", method(progress_finish));
+        Rc::new(methods)
+    };
+
+    static SPINNER_METHODS: Rc<MethodTable> = {
+        let mut methods = MethodTable::new();
+        methods.insert("tick", method(spinner_tick));
+        methods.insert("finish", method(spinner_finish));
+        Rc::new(methods)
+    };
+}
+
+pub const TERM_MOD: Module = Module {
+    name: "term",
+    funcs: &[
+        ("color", color_nfn),
+        ("bg", bg_nfn),
+        ("bold", bold_nfn),
+        ("dim", dim_nfn),
+        ("italic", italic_nfn),
+        ("underline", underline_nfn),
+        ("size", size_nfn),
+        ("progress", progress_nfn),
+        ("spinner", spinner_nfn),
+    ],
+    ctx_funcs: &[
+        ("clear", clear_nfn),
+        ("clear_line", clear_line_nfn),
+        ("move_to", move_to_nfn),
+        ("up", up_nfn),
+        ("down", down_nfn),
+        ("left", left_nfn),
+        ("right", right_nfn),
+        ("hide_cursor", hide_cursor_nfn),
+        ("show_cursor", show_cursor_nfn),
+    ],
+    method_types: &[],
+    consts: &[],
+};
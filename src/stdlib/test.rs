@@ -0,0 +1,112 @@
+use crate::error::ErrorKind;
+use crate::interpreter::Value;
+use crate::native::Ctx;
+use crate::stdlib::{parse_callback, Module};
+
+// include test
+// test::case("adds", "test::assert_eq(1 + 2, 3)")
+// test::case("parses", "
+//     val n = string::parse('42')
+//     test::assert(n == 42, 'parse broke')
+// ")
+//
+// a case is a string of code (see parse_callback) run in its own scope. a
+// failed assertion or any other error fails the case and the script carries
+// on with the next one. `hexi test` runs every *_test.hx file and adds the
+// counts up
+
+// how many cases an interpreter has run so far
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TestResults {
+    pub passed: usize,
+    pub failed: usize,
+}
+
+fn case_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("test::case expects 2 arguments, got {}", args.len()));
+    }
+
+    let name = match &args[0] {
+        Value::String(s) => s.clone(),
+        other => return Err(format!("test::case expects a name string, got {}", other.type_name())),
+    };
+    let code = parse_callback(&format!("test::case '{}'", name), &args[1])?;
+
+    let result = match ctx.interpreter().eval_with(&code, &[]) {
+        // running out of limits or ctrl+c stop the whole run, not just this case
+        Err(e) if matches!(e.kind, ErrorKind::Limit | ErrorKind::Interrupted) => return Err(ctx.interpreter().escape(e)),
+        result => result,
+    };
+
+    let written = match &result {
+        Ok(_) => writeln!(ctx.output(), "ok   {}", name),
+        Err(e) => writeln!(ctx.output(), "FAIL {}: {}", name, e.message),
+    };
+    written.map_err(|e| format!("test::case failed to write output: {}", e))?;
+
+    let results = ctx.interpreter().test_results_mut();
+    match result {
+        Ok(_) => results.passed += 1,
+        Err(_) => results.failed += 1,
+    }
+    Ok(Value::Nil)
+}
+
+fn message(args: &[Value], at: usize) -> Option<String> {
+    args.get(at).map(|m| match m {
+        Value::String(s) => s.to_string(),
+        other => other.to_string(),
+    })
+}
+
+// assert(cond) or assert(cond, message)
+fn assert_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(format!("test::assert expects 1 or 2 arguments, got {}", args.len()));
+    }
+
+    if args[0].is_truthy() {
+        return Ok(Value::Nil);
+    }
+    Err(message(args, 1).unwrap_or_else(|| format!("assertion failed, got {}", args[0])))
+}
+
+// assert_eq(actual, expected), collections compare by what's in them
+fn assert_eq_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(format!("test::assert_eq expects 2 or 3 arguments, got {}", args.len()));
+    }
+
+    if args[0] == args[1] {
+        return Ok(Value::Nil);
+    }
+
+    let detail = format!("expected {}, got {}", args[1], args[0]);
+    Err(match message(args, 2) {
+        Some(m) => format!("{} ({})", m, detail),
+        None => detail,
+    })
+}
+
+fn fail_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() > 1 {
+        return Err(format!("test::fail expects 0 or 1 arguments, got {}", args.len()));
+    }
+
+    Err(message(args, 0).unwrap_or_else(|| "test failed".to_string()))
+}
+
+pub const TEST_MOD: Module = Module {
+    name: "test",
+    funcs: &[
+        ("assert", assert_nfn),
+        ("assert_eq", assert_eq_nfn),
+        ("fail", fail_nfn),
+    ],
+    ctx_funcs: &[
+        ("case", case_nfn),
+    ],
+    method_types: &[],
+    consts: &[],
+};
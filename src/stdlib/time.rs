@@ -0,0 +1,85 @@
+use crate::interpreter::Value;
+use crate::native::Ctx;
+use crate::stdlib::{parse_callback, run_callback, Module};
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+fn since_epoch() -> Result<Duration, String> {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_err(|_| "system clock is set before 1970".to_string())
+}
+
+// whole seconds since the unix epoch
+fn now_nfn(args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("time::now expects no arguments, got {}", args.len()));
+    }
+
+    Ok(Value::Int(since_epoch()?.as_secs() as i64))
+}
+
+// milliseconds since the unix epoch
+fn millis_nfn(args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("time::millis expects no arguments, got {}", args.len()));
+    }
+
+    Ok(Value::Int(since_epoch()?.as_millis() as i64))
+}
+
+// sleeps in short slices so an interrupt (ctrl+c) doesn't have to wait the
+// whole thing out, the interpreter notices the flag at the next expression
+fn sleep_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("time::sleep expects 1 argument, got {}", args.len()));
+    }
+
+    let secs = match args[0].as_f64() {
+        Some(s) if s >= 0.0 && s.is_finite() => s,
+        _ => return Err(format!("time::sleep expects a non negative number of seconds, got {}", args[0])),
+    };
+
+    let interrupt = ctx.interpreter().interrupt_handle();
+    // too far out for the clock to count to, that's sleeping until interrupted
+    let end = Duration::try_from_secs_f64(secs).ok().and_then(|d| Instant::now().checked_add(d));
+    while !interrupt.load(Ordering::Relaxed) {
+        let left = match end {
+            Some(end) => end.saturating_duration_since(Instant::now()),
+            None => Duration::MAX,
+        };
+        if left.is_zero() {
+            break;
+        }
+        thread::sleep(left.min(Duration::from_millis(50)));
+    }
+
+    Ok(Value::Nil)
+}
+
+// runs some hexi source (see parse_callback) in a scope of its own and gives back how many seconds it took. it's parsed
+// before the clock starts, so only running it is measured
+fn measure_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("time::measure expects 1 argument, got {}", args.len()));
+    }
+
+    let code = parse_callback("time::measure", &args[0])?;
+
+    let start = Instant::now();
+    run_callback(ctx, &code, &[])?;
+    Ok(Value::Number(start.elapsed().as_secs_f64()))
+}
+
+pub const TIME_MOD: Module = Module {
+    name: "time",
+    funcs: &[
+        ("now", now_nfn),
+        ("millis", millis_nfn),
+    ],
+    ctx_funcs: &[
+        ("sleep", sleep_nfn),
+        ("measure", measure_nfn),
+    ],
+    method_types: &[],
+    consts: &[],
+};
@@ -0,0 +1,156 @@
+use crate::ast::Expr;
+use crate::interpreter::Value;
+use crate::native::Ctx;
+use crate::stdlib::{parse_callback, run_callback, Module};
+use std::rc::Rc;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// timers run bits of code (see parse_callback) later, or over and over, once timer::run() hands control to them:
+//
+// val job = timer::every(5, "io::println(fs::exists('ready'))")
+// timer::after(60, "timer::cancel(job)")
+// timer::run()
+//
+// run keeps going until there are no timers left or one of them calls
+// timer::stop(). the code runs in the scope run was called from, and an error
+// in it ends the run there and then
+
+struct Timer {
+    id: i64,
+    due: Instant,
+    every: Option<Duration>,
+    code: Rc<Vec<Expr>>,
+}
+
+// every interpreter has its own
+#[derive(Default)]
+pub(crate) struct Timers {
+    pending: Vec<Timer>,
+    next_id: i64,
+    stopped: bool,
+}
+
+fn schedule(ctx: &mut Ctx, name: &str, args: &[Value], repeat: bool) -> Result<Value, String> {
+    if args.len() != 2 {
+        return Err(format!("timer::{} expects 2 arguments, got {}", name, args.len()));
+    }
+
+    let secs = match args[0].as_f64() {
+        Some(s) if s >= 0.0 && s.is_finite() => s,
+        _ => return Err(format!("timer::{} expects a non negative number of seconds, got {}", name, args[0])),
+    };
+    // something that repeats with no gap at all would never let run() return
+    if repeat && secs == 0.0 {
+        return Err("timer::every needs an interval above 0".to_string());
+    }
+    let code = parse_callback(&format!("timer::{}", name), &args[1])?;
+
+    // far enough out that the clock can't count that high
+    let too_long = || format!("timer::{} can't wait {} seconds", name, secs);
+    let interval = Duration::try_from_secs_f64(secs).map_err(|_| too_long())?;
+    let due = Instant::now().checked_add(interval).ok_or_else(too_long)?;
+    let timers = ctx.interpreter().timers();
+    timers.next_id += 1;
+    let id = timers.next_id;
+    timers.pending.push(Timer {
+        id,
+        due,
+        every: repeat.then_some(interval),
+        code: Rc::new(code),
+    });
+    Ok(Value::Int(id))
+}
+
+// after(secs, code) runs the code once, gives back an id for cancel
+fn after_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    schedule(ctx, "after", args, false)
+}
+
+// every(secs, code) runs it every secs, the first time secs from now
+fn every_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    schedule(ctx, "every", args, true)
+}
+
+// true if there was a timer with that id left to cancel
+fn cancel_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("timer::cancel expects 1 argument, got {}", args.len()));
+    }
+
+    let Value::Int(id) = args[0] else {
+        return Err(format!("timer::cancel expects a timer id, got {}", args[0].type_name()));
+    };
+    let pending = &mut ctx.interpreter().timers().pending;
+    let before = pending.len();
+    pending.retain(|t| t.id != id);
+    Ok(Value::Bool(pending.len() != before))
+}
+
+// ends timer::run once the code that called this is done, timers that are
+// still pending stay that way for the next run
+fn stop_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("timer::stop expects no arguments, got {}", args.len()));
+    }
+
+    ctx.interpreter().timers().stopped = true;
+    Ok(Value::Nil)
+}
+
+fn run_nfn(ctx: &mut Ctx, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("timer::run expects no arguments, got {}", args.len()));
+    }
+
+    let interrupt = ctx.interpreter().interrupt_handle();
+    ctx.interpreter().timers().stopped = false;
+    loop {
+        let timers = ctx.interpreter().timers();
+        if timers.stopped {
+            break;
+        }
+        // the earliest one, ties go to whichever was scheduled first
+        let Some(next) = timers.pending.iter().enumerate().min_by_key(|(_, t)| (t.due, t.id)).map(|(i, _)| i) else {
+            break;
+        };
+        let due = timers.pending[next].due;
+
+        // sleeping in slices like time::sleep, so ctrl+c still gets through
+        let left = due.saturating_duration_since(Instant::now());
+        if !left.is_zero() {
+            if interrupt.load(Ordering::Relaxed) {
+                break;  // the interpreter reports it at the next expression
+            }
+            thread::sleep(left.min(Duration::from_millis(50)));
+            continue;
+        }
+
+        let mut timer = ctx.interpreter().timers().pending.remove(next);
+        let code = timer.code.clone();
+        if let Some(every) = timer.every {
+            // from when it was due rather than now so it doesn't drift, unless
+            // it's fallen so far behind that it would have to run twice in a row
+            timer.due = (timer.due + every).max(Instant::now());
+            ctx.interpreter().timers().pending.push(timer);
+        }
+        run_callback(ctx, &code, &[])?;
+    }
+
+    Ok(Value::Nil)
+}
+
+pub const TIMER_MOD: Module = Module {
+    name: "timer",
+    funcs: &[],
+    ctx_funcs: &[
+        ("after", after_nfn),
+        ("every", every_nfn),
+        ("cancel", cancel_nfn),
+        ("stop", stop_nfn),
+        ("run", run_nfn),
+    ],
+    method_types: &[],
+    consts: &[],
+};
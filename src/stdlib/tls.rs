@@ -0,0 +1,67 @@
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, OnceLock};
+
+// tls for the modules that talk to servers, checked against the usual web
+// roots (webpki-roots) rather than whatever the system has installed
+
+pub(crate) type TlsStream = StreamOwned<ClientConnection, TcpStream>;
+
+fn config() -> Arc<ClientConfig> {
+    static CONFIG: OnceLock<Arc<ClientConfig>> = OnceLock::new();
+    CONFIG.get_or_init(|| {
+        let roots = RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() };
+        Arc::new(ClientConfig::builder().with_root_certificates(roots).with_no_client_auth())
+    }).clone()
+}
+
+// starts tls on a connected socket, the handshake happens on the first read
+// or write
+pub(crate) fn wrap(host: &str, tcp: TcpStream) -> Result<TlsStream, String> {
+    let name = ServerName::try_from(host.to_string()).map_err(|e| format!("'{}' isn't a valid tls server name: {}", host, e))?;
+    let conn = ClientConnection::new(config(), name).map_err(|e| format!("couldn't start tls with '{}': {}", host, e))?;
+    Ok(StreamOwned::new(conn, tcp))
+}
+
+// a socket that may or may not have been upgraded to tls
+pub(crate) enum Conn {
+    Plain(TcpStream),
+    Tls(Box<TlsStream>),
+}
+
+impl Conn {
+    // for starttls, a connection that's already tls stays the way it is
+    pub(crate) fn upgrade(self, host: &str) -> Result<Conn, String> {
+        match self {
+            Conn::Plain(tcp) => Ok(Conn::Tls(Box::new(wrap(host, tcp)?))),
+            tls => Ok(tls),
+        }
+    }
+}
+
+impl Read for Conn {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Conn::Plain(s) => s.read(buf),
+            Conn::Tls(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for Conn {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Conn::Plain(s) => s.write(buf),
+            Conn::Tls(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Conn::Plain(s) => s.flush(),
+            Conn::Tls(s) => s.flush(),
+        }
+    }
+}
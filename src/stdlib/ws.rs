@@ -0,0 +1,156 @@
+use crate::error::HexiError;
+use crate::interpreter::Value;
+use crate::native::{Ctx, MethodTable, UserMethod, Userdata};
+use crate::stdlib::Module;
+use crate::stdlib::tls::Conn;
+use std::cell::RefCell;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::rc::Rc;
+use std::time::Duration;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::{Error, Message, WebSocket};
+
+// a websocket client, only built with the `ws` feature. wss:// urls go through
+// the same tls as the mail module
+//
+// val socket = ws::connect("wss://echo.example.com")
+// socket.send("hello")       -> strings go as text, bytes as binary
+// socket.recv()              -> "hello", nil once the server has closed it
+// socket.close()
+//
+// everything blocks, recv waits for as long as it takes for a message to come
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+type Handle = WebSocket<Conn>;
+
+struct Socket {
+    url: String,
+    ws: RefCell<Option<Handle>>,    // None once closed
+}
+
+fn connect_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("ws::connect expects 1 argument, got {}", args.len()));
+    }
+
+    let url = match &args[0] {
+        Value::String(s) => s,
+        other => return Err(format!("ws::connect expects a url, got {}", other.type_name())),
+    };
+    let failed = |e: &dyn std::fmt::Display| format!("ws::connect failed to connect to '{}': {}", url, e);
+
+    let request = (&**url).into_client_request().map_err(|e| failed(&e))?;
+    let uri = request.uri();
+    let secure = match uri.scheme_str() {
+        Some("ws") => false,
+        Some("wss") => true,
+        _ => return Err(format!("ws::connect expects a ws:// or wss:// url, got '{}'", url)),
+    };
+    let host = uri.host().ok_or_else(|| failed(&"the url has no host"))?.trim_matches(['[', ']']).to_string();
+    let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+
+    let addrs: Vec<_> = (host.as_str(), port).to_socket_addrs().map_err(|e| failed(&e))?.collect();
+    let mut result = Err(failed(&"no addresses found"));
+    for addr in addrs {
+        result = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).map_err(|e| failed(&e));
+        if result.is_ok() {
+            break;
+        }
+    }
+    let tcp = result?;
+    // ping and close frames are small, no point waiting to fill a packet
+    tcp.set_nodelay(true).map_err(|e| failed(&e))?;
+
+    let mut conn = Conn::Plain(tcp);
+    if secure {
+        conn = conn.upgrade(&host).map_err(|e| failed(&e))?;
+    }
+    let (ws, _) = tungstenite::client(request, conn).map_err(|e| failed(&e))?;
+
+    let socket = Socket { url: url.to_string(), ws: RefCell::new(Some(ws)) };
+    Ok(Value::userdata(Userdata::new("websocket", socket).with_methods(SOCKET_METHODS.with(Rc::clone))))
+}
+
+fn socket_method(f: fn(&Socket, &mut Handle, &[Value]) -> Result<Value, String>) -> UserMethod {
+    Rc::new(move |_: &mut Ctx, u: &Userdata, args: &[Value]| {
+        let socket = u.downcast::<Socket>().expect("websocket methods are only given to websockets");
+        let mut ws = socket.ws.borrow_mut();
+        let ws = ws.as_mut().ok_or_else(|| HexiError::runtime(format!("websocket '{}' is already closed", socket.url)))?;
+        f(socket, ws, args).map_err(HexiError::runtime)
+    })
+}
+
+fn socket_send(socket: &Socket, ws: &mut Handle, args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("websocket.send expects 1 argument, got {}", args.len()));
+    }
+
+    let message = match &args[0] {
+        Value::Bytes(b) => Message::binary(b.to_vec()),
+        Value::String(s) => Message::text(s.to_string()),
+        other => Message::text(other.to_string()),
+    };
+    ws.send(message).map_err(|e| format!("failed to send to '{}': {}", socket.url, e))?;
+    Ok(Value::Nil)
+}
+
+// the next text (as a string) or binary (as bytes) message, pings and pongs
+// are answered along the way without showing up here
+fn socket_recv(socket: &Socket, ws: &mut Handle, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("websocket.recv expects no arguments, got {}", args.len()));
+    }
+
+    loop {
+        match ws.read() {
+            Ok(Message::Text(text)) => return Ok(Value::String(text.as_str().into())),
+            Ok(Message::Binary(data)) => return Ok(Value::Bytes(data.to_vec().into())),
+            Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_)) => continue,
+            // the reply to a close is already queued, the next read sends it
+            Ok(Message::Close(_)) => continue,
+            Err(Error::ConnectionClosed | Error::AlreadyClosed) => return Ok(Value::Nil),
+            Err(e) => return Err(format!("failed to read from '{}': {}", socket.url, e)),
+        }
+    }
+}
+
+// closing a closed websocket is fine, and any use after this errors
+fn socket_close(socket: &Socket, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        return Err(format!("websocket.close expects no arguments, got {}", args.len()));
+    }
+
+    let Some(mut ws) = socket.ws.borrow_mut().take() else {
+        return Ok(Value::Nil);
+    };
+    // a polite close waits for the server to close back, but one that's gone
+    // away already isn't worth an error
+    if ws.close(None).is_ok() {
+        while ws.read().is_ok() {}
+    }
+    Ok(Value::Nil)
+}
+
+thread_local! {
+    static SOCKET_METHODS: Rc<MethodTable> = {
+        let mut methods = MethodTable::new();
+        methods.insert("send", socket_method(socket_send));
+        methods.insert("recv", socket_method(socket_recv));
+        methods.insert("close", Rc::new(|_: &mut Ctx, u: &Userdata, args: &[Value]| {
+            let socket = u.downcast::<Socket>().expect("websocket methods are only given to websockets");
+            socket_close(socket, args).map_err(HexiError::runtime)
+        }));
+        Rc::new(methods)
+    };
+}
+
+pub const WS_MOD: Module = Module {
+    name: "ws",
+    funcs: &[
+        ("connect", connect_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
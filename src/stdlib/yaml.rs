@@ -0,0 +1,38 @@
+use crate::interpreter::Value;
+use crate::stdlib::Module;
+
+// same as json, Value's serde impls do the work (see serialize.rs)
+
+fn parse_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("yaml::parse expects 1 argument, got {}", args.len()));
+    }
+
+    let content = match &args[0] {
+        Value::String(s) => s,
+        other => return Err(format!("yaml::parse expects a string, got {}", other.type_name())),
+    };
+
+    serde_yaml::from_str(content).map_err(|e| format!("error while parsing yaml: {}", e))
+}
+
+fn stringify_nfn(args: &[Value]) -> Result<Value, String> {
+    if args.len() != 1 {
+        return Err(format!("yaml::stringify expects 1 argument, got {}", args.len()));
+    }
+
+    serde_yaml::to_string(&args[0])
+        .map(|s| Value::String(s.into()))
+        .map_err(|e| format!("error while writing yaml: {}", e))
+}
+
+pub const YAML_MOD: Module = Module {
+    name: "yaml",
+    funcs: &[
+        ("parse", parse_nfn),
+        ("stringify", stringify_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
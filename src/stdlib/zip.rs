@@ -0,0 +1,155 @@
+use crate::interpreter::{CValue, Value};
+use crate::stdlib::Module;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use ::zip::write::SimpleFileOptions;
+use ::zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+// gzip for single blobs and .zip archives for bundles of files. everything
+// compressed comes back as bytes, `.to_string()` them for text
+//
+// zip::gzip(fs::read("app.log"))             -> <bytes 1f8b...>
+// zip::write("out.zip", ["a.txt" = "hi"])    -> a new archive with one entry
+// zip::read("out.zip", "a.txt").to_string()  -> "hi"
+
+// strings go in as their utf-8
+fn data_arg<'a>(name: &str, v: &'a Value) -> Result<&'a [u8], String> {
+    match v {
+        Value::Bytes(b) => Ok(b),
+        Value::String(s) => Ok(s.as_bytes()),
+        other => Err(format!("zip::{} expects bytes or a string, got {}", name, other.type_name())),
+    }
+}
+
+fn path_arg(v: &Value) -> Result<&str, String> {
+    match v {
+        Value::String(s) => Ok(s),
+        other => Err(format!("expected a string value for path argument, got {}", other)),
+    }
+}
+
+fn arg_count(name: &str, args: &[Value], want: usize) -> Result<(), String> {
+    if args.len() != want {
+        return Err(format!("zip::{} expects {} argument{}, got {}", name, want, if want == 1 { "" } else { "s" }, args.len()));
+    }
+    Ok(())
+}
+
+fn gzip_nfn(args: &[Value]) -> Result<Value, String> {
+    arg_count("gzip", args, 1)?;
+    let data = data_arg("gzip", &args[0])?;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).map_err(|e| format!("zip::gzip failed: {}", e))?;
+    let compressed = encoder.finish().map_err(|e| format!("zip::gzip failed: {}", e))?;
+    Ok(Value::Bytes(compressed.into()))
+}
+
+fn gunzip_nfn(args: &[Value]) -> Result<Value, String> {
+    arg_count("gunzip", args, 1)?;
+    let data = data_arg("gunzip", &args[0])?;
+
+    let mut out = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut out).map_err(|e| format!("zip::gunzip got data that isn't valid gzip: {}", e))?;
+    Ok(Value::Bytes(out.into()))
+}
+
+fn open_archive(name: &str, path: &str) -> Result<ZipArchive<fs::File>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("zip::{} failed to open '{}': {}", name, path, e))?;
+    ZipArchive::new(file).map_err(|e| format!("zip::{} couldn't read '{}' as a zip archive: {}", name, path, e))
+}
+
+// every entry name in the archive, directories end in a /
+fn list_nfn(args: &[Value]) -> Result<Value, String> {
+    arg_count("list", args, 1)?;
+    let archive = open_archive("list", path_arg(&args[0])?)?;
+
+    let names = archive.file_names()
+        .map(|n| n.map(|n| Value::String(n.as_ref().into())))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("zip::list failed to read '{}': {}", args[0], e))?;
+    Ok(Value::collection(CValue::from_array(names)))
+}
+
+// read(path, entry) is the entry's contents as bytes
+fn read_nfn(args: &[Value]) -> Result<Value, String> {
+    arg_count("read", args, 2)?;
+    let path = path_arg(&args[0])?;
+    let entry = match &args[1] {
+        Value::String(s) => s,
+        other => return Err(format!("zip::read expects an entry name, got {}", other.type_name())),
+    };
+
+    let mut archive = open_archive("read", path)?;
+    let mut file = archive.by_name(entry).map_err(|e| format!("zip::read couldn't get '{}' from '{}': {}", entry, path, e))?;
+    let mut out = Vec::new();
+    file.read_to_end(&mut out).map_err(|e| format!("zip::read failed to read '{}' from '{}': {}", entry, path, e))?;
+    Ok(Value::Bytes(out.into()))
+}
+
+// write(path, [name = contents, ...]) creates (or replaces) an archive with
+// those entries, names can have directories in them like "docs/readme.txt"
+fn write_nfn(args: &[Value]) -> Result<Value, String> {
+    arg_count("write", args, 2)?;
+    let path = path_arg(&args[0])?;
+    let Value::Collection(entries) = &args[1] else {
+        return Err(format!("zip::write expects a collection of entries, got {}", args[1].type_name()));
+    };
+
+    let failed = |e: &dyn std::fmt::Display| format!("zip::write failed to write '{}': {}", path, e);
+    let file = fs::File::create(path).map_err(|e| failed(&e))?;
+    let mut writer = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (key, value) in entries.borrow().iter() {
+        let name = match key.to_value() {
+            Value::String(s) => s,
+            other => return Err(format!("zip::write expects entry names as keys, got {}", other.type_name())),
+        };
+        writer.start_file(&*name, options).map_err(|e| failed(&e))?;
+        writer.write_all(data_arg("write", value)?).map_err(|e| failed(&e))?;
+    }
+
+    writer.finish().map_err(|e| failed(&e))?;
+    Ok(Value::Bool(true))
+}
+
+// extract(path, dir) unpacks everything into dir, creating it if needed.
+// entries that would land outside of dir (../ and such) are an error
+fn extract_nfn(args: &[Value]) -> Result<Value, String> {
+    arg_count("extract", args, 2)?;
+    let path = path_arg(&args[0])?;
+    let dir = path_arg(&args[1])?;
+
+    let mut archive = open_archive("extract", path)?;
+    // check every name before writing anything
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).map_err(|e| format!("zip::extract failed to read '{}': {}", path, e))?;
+        if file.enclosed_name().is_none() {
+            let name = file.name().map_or_else(|_| format!("entry {}", i), |n| n.to_string());
+            return Err(format!("zip::extract won't unpack '{}', it points outside of the target directory", name));
+        }
+    }
+
+    archive.extract(Path::new(dir)).map_err(|e| format!("zip::extract failed to unpack '{}' into '{}': {}", path, dir, e))?;
+    Ok(Value::Bool(true))
+}
+
+pub const ZIP_MOD: Module = Module {
+    name: "zip",
+    funcs: &[
+        ("gzip", gzip_nfn),
+        ("gunzip", gunzip_nfn),
+        ("list", list_nfn),
+        ("read", read_nfn),
+        ("write", write_nfn),
+        ("extract", extract_nfn),
+    ],
+    ctx_funcs: &[],
+    method_types: &[],
+    consts: &[],
+};
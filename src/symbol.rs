@@ -0,0 +1,87 @@
+use serde::{Serialize, Serializer};
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{LazyLock, Mutex};
+
+// every distinct identifier is stored exactly once and never freed, a symbol
+// is just a pointer to that copy, so cloning, comparing and hashing one never
+// touches the actual characters. only names from source code (variables,
+// functions, methods, modules) get interned, they come in limited numbers.
+// strings made while running, like collection keys, stay Rc<str> so they're
+// freed with everything else
+static INTERNER: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Clone, Copy)]
+pub struct Symbol(&'static str);
+
+impl Symbol {
+    pub fn intern(s: &str) -> Symbol {
+        let mut interner = INTERNER.lock().unwrap();
+        if let Some(existing) = interner.get(s) {
+            return Symbol(existing);
+        }
+
+        let leaked: &'static str = Box::leak(s.to_string().into_boxed_str());
+        interner.insert(leaked);
+        Symbol(leaked)
+    }
+
+    // only finds strings that were interned before, handy for lookups since
+    // a string nobody ever interned can't be a name anywhere
+    pub fn lookup(s: &str) -> Option<Symbol> {
+        INTERNER.lock().unwrap().get(s).map(|s| Symbol(s))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+// there's only ever one copy of each string, so the address is the identity
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_ptr().hash(state);
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(s: &str) -> Self {
+        Symbol::intern(s)
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
@@ -0,0 +1,168 @@
+use crate::bigint::BigInt;
+use crate::error::HexiError;
+use crate::interpreter::{CValue, Interpreter, Value};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+// values share their strings and collections through Rc, so neither they nor
+// the interpreter can cross threads. to run hexi off the main thread an
+// interpreter gets a thread of its own and everything else talks to it through
+// this handle, which can be sent and shared freely:
+//
+// let hexi = InterpreterThread::spawn(|i| i.set_global("greeting", "hi".into()));
+// let value = hexi.eval_str("greeting")?.into_value();
+
+type Job = Box<dyn FnOnce(&mut Interpreter) + Send>;
+
+pub struct InterpreterThread {
+    jobs: Option<Sender<Job>>,
+    interrupt: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl InterpreterThread {
+    // `setup` runs on the new thread right after the interpreter is created,
+    // it's the place to register natives, set globals, limits and so on
+    pub fn spawn<F>(setup: F) -> Self
+    where
+        F: FnOnce(&mut Interpreter) + Send + 'static,
+    {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let (ready, interrupt) = mpsc::channel();
+
+        let thread = thread::spawn(move || {
+            let mut interpreter = Interpreter::new();
+            setup(&mut interpreter);
+            let _ = ready.send(interpreter.interrupt_handle());
+
+            // jobs run one after another until every handle is gone. an
+            // interrupt that came in between jobs was for one already done
+            let interrupt = interpreter.interrupt_handle();
+            for job in queue {
+                interrupt.store(false, Ordering::Relaxed);
+                job(&mut interpreter);
+            }
+        });
+
+        // only fails if setup panicked, the thread is gone either way then
+        let interrupt = interrupt.recv().unwrap_or_default();
+        InterpreterThread { jobs: Some(jobs), interrupt, thread: Some(thread) }
+    }
+
+    // run `f` against the interpreter on its thread and wait for what it returns
+    pub fn with<F, R>(&self, f: F) -> Result<R, HexiError>
+    where
+        F: FnOnce(&mut Interpreter) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let job: Job = Box::new(move |i| {
+            let _ = tx.send(f(i));
+        });
+
+        let gone = || HexiError::runtime("interpreter thread has stopped");
+        self.jobs.as_ref().ok_or_else(gone)?.send(job).map_err(|_| gone())?;
+        rx.recv().map_err(|_| gone())
+    }
+
+    pub fn eval_str(&self, source: &str) -> Result<SendValue, HexiError> {
+        let source = source.to_string();
+        self.with(move |i| {
+            let value = i.eval_str(&source)?;
+            SendValue::try_from(&value).map_err(HexiError::runtime)
+        })?
+    }
+
+    // stop whatever script is running on the thread right now, with nothing
+    // running it does nothing
+    pub fn interrupt(&self) {
+        self.interrupt.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for InterpreterThread {
+    fn drop(&mut self) {
+        // closing the queue ends the thread's loop once the current job is done
+        self.jobs.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// a deep copy of a value that owns all its data, so it can go between threads.
+// turning it back into a Value on the other side builds fresh collections.
+// keys are copied like any other value, so a collection used as a key arrives
+// as a new collection with the same contents
+#[derive(Debug, Clone, PartialEq)]
+pub enum SendValue {
+    Int(i64),
+    BigInt(BigInt),
+    Number(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Bool(bool),
+    Collection(Vec<(SendValue, SendValue)>),
+    Nil,
+}
+
+impl SendValue {
+    pub fn into_value(self) -> Value {
+        match self {
+            SendValue::Int(i) => Value::Int(i),
+            SendValue::BigInt(b) => Value::from_bigint(b),
+            SendValue::Number(n) => Value::Number(n),
+            SendValue::String(s) => Value::String(s.into()),
+            SendValue::Bytes(b) => Value::Bytes(b.into()),
+            SendValue::Bool(b) => Value::Bool(b),
+            SendValue::Collection(entries) => {
+                let mut c = CValue::new();
+                for (k, v) in entries {
+                    // only NaN has no key, and a NaN key could never have been stored
+                    if let Some(k) = k.into_value().to_key() {
+                        c.insert(k, v.into_value());
+                    }
+                }
+                Value::collection(c)
+            },
+            SendValue::Nil => Value::Nil,
+        }
+    }
+
+    fn copy(value: &Value, path: &mut Vec<*const ()>) -> Result<SendValue, String> {
+        Ok(match value {
+            Value::Int(i) => SendValue::Int(*i),
+            Value::BigInt(b) => SendValue::BigInt(b.as_ref().clone()),
+            Value::Number(n) => SendValue::Number(*n),
+            Value::String(s) => SendValue::String(s.to_string()),
+            Value::Bytes(b) => SendValue::Bytes(b.to_vec()),
+            Value::Bool(b) => SendValue::Bool(*b),
+            Value::Nil => SendValue::Nil,
+            Value::Userdata(u) => return Err(format!("a {} can't be sent to another thread", u.type_name())),
+            Value::Collection(c) => {
+                // a collection that contains itself would copy forever
+                let ptr = c.as_ptr() as *const ();
+                if path.contains(&ptr) {
+                    return Err("a collection that contains itself can't be sent to another thread".to_string());
+                }
+
+                path.push(ptr);
+                let entries = c.borrow().iter()
+                    .map(|(k, v)| Ok((SendValue::copy(&k.to_value(), path)?, SendValue::copy(v, path)?)))
+                    .collect::<Result<_, String>>()?;
+                path.pop();
+                SendValue::Collection(entries)
+            },
+        })
+    }
+}
+
+impl TryFrom<&Value> for SendValue {
+    type Error = String;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        SendValue::copy(value, &mut Vec::new())
+    }
+}
@@ -0,0 +1,153 @@
+use crate::ast::{Block, CEntry, Expr, ExprKind};
+
+// walking and rewriting hexi asts without matching on every node by hand.
+// override the methods for the nodes you care about and call the walk_/fold_
+// function from inside them to keep going into the children
+//
+// struct CountCalls(usize);
+//
+// impl Visitor for CountCalls {
+//     fn visit_expr(&mut self, expr: &Expr) {
+//         if let ExprKind::Call(_) = expr.kind {
+//             self.0 += 1;
+//         }
+//         walk_expr(self, expr);
+//     }
+// }
+
+pub trait Visitor {
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr);
+    }
+
+    fn visit_block(&mut self, block: &Block) {
+        walk_block(self, block);
+    }
+}
+
+// visits every direct child of `expr`
+pub fn walk_expr<V: Visitor + ?Sized>(v: &mut V, expr: &Expr) {
+    match &expr.kind {
+        ExprKind::Identifier(_) | ExprKind::Int(_) | ExprKind::BigInt(_) |
+        ExprKind::Number(_) | ExprKind::String(_) | ExprKind::Bool(_) | ExprKind::Nil |
+        ExprKind::Include(_) => {},
+
+        ExprKind::Call(c) => c.args.iter().for_each(|a| v.visit_expr(a)),
+        ExprKind::VarDecl(d) => v.visit_expr(&d.value),
+        ExprKind::Assignment(a) => v.visit_expr(&a.assignee),
+        ExprKind::BinaryOp(b) => {
+            v.visit_expr(&b.left);
+            v.visit_expr(&b.right);
+        },
+        ExprKind::UnaryOp(u) => v.visit_expr(&u.operand),
+        ExprKind::Block(b) => v.visit_block(b),
+        ExprKind::If(i) => {
+            v.visit_expr(&i.cond);
+            v.visit_block(&i.block);
+            if let Some(e) = &i.else_block {
+                v.visit_block(e);
+            }
+        },
+        ExprKind::Collection(c) => {
+            for entry in &c.entries {
+                match entry {
+                    CEntry::Indexed(e) | CEntry::Keyed(_, e) | CEntry::NumKeyed(_, e) |
+                    CEntry::BoolKeyed(_, e) | CEntry::NilKeyed(e) => v.visit_expr(e),
+                }
+            }
+        },
+        ExprKind::IndexAccess(ia) => {
+            v.visit_expr(&ia.object);
+            v.visit_expr(&ia.index);
+        },
+        ExprKind::MethodCall(mc) => {
+            v.visit_expr(&mc.object);
+            mc.args.iter().for_each(|a| v.visit_expr(a));
+        },
+        ExprKind::FieldAccess(fa) => v.visit_expr(&fa.object),
+    }
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(v: &mut V, block: &Block) {
+    for e in &block.exprs {
+        v.visit_expr(e);
+    }
+}
+
+// same idea but each node is taken by value and the returned one replaces it,
+// the defaults hand back the same tree
+pub trait Fold {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        fold_expr(self, expr)
+    }
+
+    fn fold_block(&mut self, block: Block) -> Block {
+        fold_block(self, block)
+    }
+}
+
+// rebuilds `expr` out of its folded children
+pub fn fold_expr<F: Fold + ?Sized>(f: &mut F, expr: Expr) -> Expr {
+    let Expr { kind, span } = expr;
+    let kind = match kind {
+        ExprKind::Call(mut c) => {
+            c.args = c.args.into_iter().map(|a| f.fold_expr(a)).collect();
+            ExprKind::Call(c)
+        },
+        ExprKind::VarDecl(mut d) => {
+            d.value = Box::new(f.fold_expr(*d.value));
+            ExprKind::VarDecl(d)
+        },
+        ExprKind::Assignment(mut a) => {
+            a.assignee = Box::new(f.fold_expr(*a.assignee));
+            ExprKind::Assignment(a)
+        },
+        ExprKind::BinaryOp(mut b) => {
+            b.left = Box::new(f.fold_expr(*b.left));
+            b.right = Box::new(f.fold_expr(*b.right));
+            ExprKind::BinaryOp(b)
+        },
+        ExprKind::UnaryOp(mut u) => {
+            u.operand = Box::new(f.fold_expr(*u.operand));
+            ExprKind::UnaryOp(u)
+        },
+        ExprKind::Block(b) => ExprKind::Block(f.fold_block(b)),
+        ExprKind::If(mut i) => {
+            i.cond = Box::new(f.fold_expr(*i.cond));
+            i.block = f.fold_block(i.block);
+            i.else_block = i.else_block.map(|e| f.fold_block(e));
+            ExprKind::If(i)
+        },
+        ExprKind::Collection(mut c) => {
+            c.entries = c.entries.into_iter().map(|entry| match entry {
+                CEntry::Indexed(e) => CEntry::Indexed(f.fold_expr(e)),
+                CEntry::Keyed(k, e) => CEntry::Keyed(k, f.fold_expr(e)),
+                CEntry::NumKeyed(n, e) => CEntry::NumKeyed(n, f.fold_expr(e)),
+                CEntry::BoolKeyed(b, e) => CEntry::BoolKeyed(b, f.fold_expr(e)),
+                CEntry::NilKeyed(e) => CEntry::NilKeyed(f.fold_expr(e)),
+            }).collect();
+            ExprKind::Collection(c)
+        },
+        ExprKind::IndexAccess(mut ia) => {
+            ia.object = Box::new(f.fold_expr(*ia.object));
+            ia.index = Box::new(f.fold_expr(*ia.index));
+            ExprKind::IndexAccess(ia)
+        },
+        ExprKind::MethodCall(mut mc) => {
+            mc.object = Box::new(f.fold_expr(*mc.object));
+            mc.args = mc.args.into_iter().map(|a| f.fold_expr(a)).collect();
+            ExprKind::MethodCall(mc)
+        },
+        ExprKind::FieldAccess(mut fa) => {
+            fa.object = Box::new(f.fold_expr(*fa.object));
+            ExprKind::FieldAccess(fa)
+        },
+        leaf => leaf,
+    };
+
+    Expr::new(kind, span)
+}
+
+pub fn fold_block<F: Fold + ?Sized>(f: &mut F, block: Block) -> Block {
+    Block::new(block.exprs.into_iter().map(|e| f.fold_expr(e)).collect())
+}
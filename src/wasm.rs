@@ -0,0 +1,47 @@
+use crate::interpreter::Interpreter;
+use crate::output::Capture;
+use wasm_bindgen::prelude::*;
+
+// the browser side of hexi, build with `wasm-pack build --target web` and use it like:
+//
+// import init, { Hexi } from "./pkg/hexi.js";
+// await init();
+// const hexi = new Hexi();
+// hexi.eval("val x = [1, 2, 3]\nx");  // [1, 2, 3]
+// hexi.eval("io::println('hi')");
+// hexi.takeOutput();                  // "hi\n"
+//
+// values come back as plain js values (collections as arrays/objects), errors
+// are thrown with the same report the cli prints
+
+#[wasm_bindgen]
+pub struct Hexi {
+    interpreter: Interpreter,
+    output: Capture,
+}
+
+#[wasm_bindgen]
+impl Hexi {
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Hexi {
+        let output = Capture::new();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_output(output.clone());
+        // no console to tell them apart on, errors land in the same place
+        interpreter.set_error_output(output.clone());
+        Hexi { interpreter, output }
+    }
+
+    // variables stick around between calls, like the repl
+    pub fn eval(&mut self, code: &str) -> Result<JsValue, JsError> {
+        let value = self.interpreter.eval_str(code).map_err(|e| JsError::new(&e.report(code, "<playground>")))?;
+        serde_wasm_bindgen::to_value(&value).map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    // whatever the scripts printed since the last call
+    #[wasm_bindgen(js_name = takeOutput)]
+    pub fn take_output(&mut self) -> String {
+        self.output.take()
+    }
+}